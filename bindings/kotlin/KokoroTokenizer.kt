@@ -43,6 +43,81 @@ object KokoroTokenizer {
      */
     external fun textToPhonemes(text: String, language: String): String
 
+    /**
+     * Convert text to token IDs, throwing if no tokens can be produced.
+     *
+     * @param text The input text to tokenize
+     * @param language Language code (e.g., "en-us", "zh")
+     * @return Array of token IDs (i64) padded with 0 at start and end
+     * @throws IllegalArgumentException for unsupported languages or invalid input
+     * @throws IllegalStateException if conversion fails (e.g. unmappable phoneme, input too long)
+     */
+    external fun tryTokenizeWithLanguage(text: String, language: String): LongArray
+
+    /**
+     * Get the phoneme representation of text, throwing on failure.
+     *
+     * @param text The input text
+     * @param language Language code (e.g., "en-us", "zh")
+     * @return Phoneme string in IPA-like notation
+     * @throws IllegalArgumentException for unsupported languages or invalid input
+     * @throws IllegalStateException if conversion fails
+     */
+    external fun tryTextToPhonemes(text: String, language: String): String
+
+    /**
+     * Load a model vocabulary from its config.json file.
+     *
+     * @param path Path to the model's config.json
+     * @return Vocabulary handle; release it with [freeVocabulary]
+     * @throws IllegalArgumentException if the file can't be read or parsed
+     */
+    external fun loadVocabulary(path: String): Long
+
+    /**
+     * Release a vocabulary handle returned by [loadVocabulary].
+     */
+    external fun freeVocabulary(handle: Long)
+
+    /**
+     * Convert text to token IDs for a model vocabulary, throwing on failure.
+     *
+     * @param text The input text to tokenize
+     * @param language Language code (e.g., "en-us", "zh")
+     * @param handle Handle from [loadVocabulary], or 0 for the built-in vocabulary
+     * @return Array of token IDs (i64) padded with 0 at start and end
+     * @throws IllegalArgumentException for unsupported languages or invalid input
+     * @throws IllegalStateException if conversion fails
+     */
+    external fun tryTokenizeWithVocabulary(text: String, language: String, handle: Long): LongArray
+
+    /**
+     * Load and install English dictionaries from files, e.g. after downloading them.
+     *
+     * Required before converting English text when the library was built with
+     * the external-dictionaries feature; otherwise replaces the embedded ones.
+     *
+     * @param language "en-us" or "en-gb"
+     * @param goldPath Gold dictionary (.json, or a compiled dictionary)
+     * @param silverPath Silver dictionary (.json, or a compiled dictionary)
+     * @throws IllegalArgumentException for a language that is not English
+     * @throws IllegalStateException if a dictionary can't be loaded
+     */
+    external fun loadEnglishDictionaries(language: String, goldPath: String, silverPath: String)
+
+    /**
+     * Load and install English dictionaries from JSON or compiled bytes.
+     */
+    external fun loadEnglishDictionariesFromBytes(language: String, gold: ByteArray, silver: ByteArray)
+
+    /**
+     * Check whether dictionaries are available for an English dialect.
+     *
+     * @param language "en-us" or "en-gb"
+     * @return false if none have been loaded yet
+     */
+    external fun hasEnglishDictionaries(language: String): Boolean
+
     /**
      * Convert text to token IDs with default settings.
      * Convenience wrapper around [tokenize].
@@ -59,6 +59,116 @@ char *kokoro_text_to_phonemes(const char *text, const char *language);
  */
 void kokoro_free_string(char *s);
 
+/**
+ * Error codes returned by the kokoro_try_* functions (0 means success).
+ */
+#define KOKORO_OK 0
+#define KOKORO_ERR_UNSUPPORTED_LANGUAGE 1
+#define KOKORO_ERR_FEATURE_NOT_ENABLED 2
+#define KOKORO_ERR_DICTIONARY_LOAD 3
+#define KOKORO_ERR_UNMAPPABLE_PHONEME 4
+#define KOKORO_ERR_INPUT_TOO_LONG 5
+#define KOKORO_ERR_INVALID_INPUT 6
+#define KOKORO_ERR_INVALID_VOCABULARY 7
+#define KOKORO_ERR_DICTIONARY_NOT_LOADED 8
+
+/**
+ * Convert text to phoneme token IDs, reporting errors.
+ *
+ * @param text Null-terminated UTF-8 text string
+ * @param language Language code (e.g., "en-us", "zh")
+ * @param out Receives the token array on success. Must be freed with kokoro_free_tokens.
+ * @return KOKORO_OK, or an error code (see kokoro_last_error_message)
+ */
+int32_t kokoro_try_text_to_tokens(const char *text, const char *language, CTokenArray *out);
+
+/**
+ * Convert text to phoneme string, reporting errors.
+ *
+ * @param text Null-terminated UTF-8 text string
+ * @param language Language code (e.g., "en-us", "zh")
+ * @param out Receives the phoneme string on success. Must be freed with kokoro_free_string.
+ * @return KOKORO_OK, or an error code (see kokoro_last_error_message)
+ */
+int32_t kokoro_try_text_to_phonemes(const char *text, const char *language, char **out);
+
+/**
+ * Opaque handle to a model vocabulary.
+ */
+typedef struct KokoroVocabulary KokoroVocabulary;
+
+/**
+ * Load a model vocabulary from its config.json file.
+ *
+ * @param path Null-terminated path to the model's config.json
+ * @param out Receives the vocabulary on success. Must be freed with kokoro_vocabulary_free.
+ * @return KOKORO_OK, or an error code (see kokoro_last_error_message)
+ */
+int32_t kokoro_vocabulary_load(const char *path, const KokoroVocabulary **out);
+
+/**
+ * Free a vocabulary returned by kokoro_vocabulary_load.
+ *
+ * @param vocabulary Vocabulary to free (may be NULL)
+ */
+void kokoro_vocabulary_free(const KokoroVocabulary *vocabulary);
+
+/**
+ * Convert text to token IDs for a model vocabulary, reporting errors.
+ *
+ * @param text Null-terminated UTF-8 text string
+ * @param language Language code (e.g., "en-us", "zh")
+ * @param vocabulary Vocabulary from kokoro_vocabulary_load, or NULL for the built-in one
+ * @param out Receives the token array on success. Must be freed with kokoro_free_tokens.
+ * @return KOKORO_OK, or an error code (see kokoro_last_error_message)
+ */
+int32_t kokoro_try_text_to_tokens_with_vocabulary(const char *text, const char *language,
+                                                  const KokoroVocabulary *vocabulary,
+                                                  CTokenArray *out);
+
+/**
+ * Load and install English dictionaries from files.
+ *
+ * Required before converting English text when the library was built with
+ * the external-dictionaries feature; otherwise replaces the embedded ones.
+ *
+ * @param language "en-us" or "en-gb"
+ * @param gold_path Gold dictionary (.json, or a compiled dictionary)
+ * @param silver_path Silver dictionary (.json, or a compiled dictionary)
+ * @return KOKORO_OK, or an error code (see kokoro_last_error_message)
+ */
+int32_t kokoro_load_english_dictionaries(const char *language, const char *gold_path,
+                                         const char *silver_path);
+
+/**
+ * Load and install English dictionaries from memory. The buffers are copied.
+ *
+ * @param language "en-us" or "en-gb"
+ * @param gold Gold dictionary as JSON or compiled bytes
+ * @param gold_len Length of gold in bytes
+ * @param silver Silver dictionary as JSON or compiled bytes
+ * @param silver_len Length of silver in bytes
+ * @return KOKORO_OK, or an error code (see kokoro_last_error_message)
+ */
+int32_t kokoro_load_english_dictionaries_from_bytes(const char *language,
+                                                    const uint8_t *gold, size_t gold_len,
+                                                    const uint8_t *silver, size_t silver_len);
+
+/**
+ * Check that dictionaries are available for an English dialect.
+ *
+ * @param language "en-us" or "en-gb"
+ * @return KOKORO_OK, or KOKORO_ERR_DICTIONARY_NOT_LOADED if none have been loaded yet
+ */
+int32_t kokoro_check_english_dictionaries(const char *language);
+
+/**
+ * Get the message for the last error on the calling thread.
+ *
+ * @return Error message (do not free), or NULL if no error has occurred
+ */
+const char *kokoro_last_error_message(void);
+
 /**
  * Get the library version.
  *
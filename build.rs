@@ -2,14 +2,155 @@
 //!
 //! This script handles:
 //! - Android NDK configuration
-//! - Dictionary embedding
+//! - Compiling the English dictionaries into the binary lexicon format
+//! - Compiling the Chinese pinyin dictionaries into lookup tables
+
+#[path = "src/lexicon_format.rs"]
+#[allow(dead_code)]
+mod lexicon_format;
+
+use lexicon_format::RawEntry;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+const DICTIONARIES: [&str; 4] = ["us_gold", "us_silver", "gb_gold", "gb_silver"];
+
+/// Code points covered by the Chinese character table: CJK Unified
+/// Ideographs Extension A through the end of the basic block
+const HANZI_RANGE: std::ops::RangeInclusive<u32> = 0x3400..=0x9FFF;
+
+/// Compile `dictionaries/<name>.json` to `<out_dir>/<name>.lex`
+fn compile_dictionary(name: &str, out_dir: &Path) {
+    let source = format!("dictionaries/{}.json", name);
+    let json = std::fs::read_to_string(&source).unwrap_or_else(|e| panic!("{}: {}", source, e));
+    let Value::Object(words) = serde_json::from_str(&json).unwrap_or_else(|e| panic!("{}: {}", source, e)) else {
+        panic!("{}: expected a JSON object", source);
+    };
+
+    let entries: BTreeMap<String, RawEntry> = words
+        .into_iter()
+        .map(|(word, value)| {
+            let entry = match value {
+                Value::String(phonemes) => RawEntry::Simple(phonemes),
+                Value::Object(tags) => RawEntry::Tagged(
+                    tags.into_iter()
+                        .map(|(tag, phonemes)| (tag, phonemes.as_str().map(str::to_string)))
+                        .collect(),
+                ),
+                other => panic!("{}: unexpected entry for '{}': {}", source, word, other),
+            };
+            (word, entry)
+        })
+        .collect();
+
+    let out = out_dir.join(format!("{}.lex", name));
+    std::fs::write(&out, lexicon_format::encode(&entries)).unwrap_or_else(|e| panic!("{}: {}", out.display(), e));
+}
+
+/// Non-comment lines of a dictionary text file, split into the key and the rest
+fn dictionary_lines(source: &str) -> Vec<(String, String)> {
+    let text = std::fs::read_to_string(source).unwrap_or_else(|e| panic!("{}: {}", source, e));
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (key, value) = line
+                .split_once(char::is_whitespace)
+                .unwrap_or_else(|| panic!("{}: missing pinyin in '{}'", source, line));
+            (key.to_string(), value.split_whitespace().collect::<Vec<_>>().join(" "))
+        })
+        .collect()
+}
+
+/// Compile `dictionaries/zh_pinyin.txt` to `<out_dir>/zh_pinyin.rs`: a table of
+/// the distinct syllables and a dense index into it for every code point in
+/// `HANZI_RANGE` (0 = no reading)
+fn compile_hanzi_table(out_dir: &Path) {
+    let source = "dictionaries/zh_pinyin.txt";
+    let mut syllables: Vec<String> = vec![String::new()];
+    let mut ids: BTreeMap<String, u16> = BTreeMap::new();
+    let mut index = vec![0u16; (HANZI_RANGE.end() - HANZI_RANGE.start() + 1) as usize];
+
+    for (hanzi, pinyin) in dictionary_lines(source) {
+        let mut chars = hanzi.chars();
+        let (Some(c), None) = (chars.next(), chars.next()) else {
+            panic!("{}: expected a single character, got '{}'", source, hanzi);
+        };
+        if !HANZI_RANGE.contains(&(c as u32)) {
+            panic!("{}: '{}' is outside the covered range", source, c);
+        }
+        let id = *ids.entry(pinyin.clone()).or_insert_with(|| {
+            syllables.push(pinyin);
+            (syllables.len() - 1) as u16
+        });
+        index[(c as u32 - HANZI_RANGE.start()) as usize] = id;
+    }
+
+    let mut code = String::new();
+    writeln!(code, "/// First code point covered by `HANZI_INDEX`").unwrap();
+    writeln!(code, "const HANZI_START: u32 = {:#X};", HANZI_RANGE.start()).unwrap();
+    writeln!(code, "/// Distinct readings referenced by `HANZI_INDEX`").unwrap();
+    writeln!(code, "static HANZI_SYLLABLES: [&str; {}] = {:?};", syllables.len(), syllables).unwrap();
+    writeln!(code, "/// Reading index for each code point from `HANZI_START`").unwrap();
+    writeln!(code, "static HANZI_INDEX: [u16; {}] = {:?};", index.len(), index).unwrap();
+
+    let out = out_dir.join("zh_pinyin.rs");
+    std::fs::write(&out, code).unwrap_or_else(|e| panic!("{}: {}", out.display(), e));
+}
+
+/// Compile `dictionaries/zh_phrases.txt` to a perfect hash map in
+/// `<out_dir>/zh_phrases.rs`
+fn compile_phrases(out_dir: &Path) {
+    let source = "dictionaries/zh_phrases.txt";
+    let phrases: BTreeMap<String, String> = dictionary_lines(source).into_iter().collect();
+    for (word, pinyin) in &phrases {
+        if word.chars().count() != pinyin.split(' ').count() {
+            panic!("{}: '{}' needs one syllable per character, got '{}'", source, word, pinyin);
+        }
+    }
+
+    let mut map = phf_codegen::Map::new();
+    for (word, pinyin) in &phrases {
+        map.entry(word.as_str(), &format!("{:?}", pinyin));
+    }
+    let longest = phrases.keys().map(|word| word.chars().count()).max().unwrap_or(0);
+    let code = format!(
+        "/// Length in characters of the longest phrase in `PHRASE_PINYIN`\n\
+         pub const MAX_PHRASE_CHARS: usize = {};\n\
+         static PHRASE_PINYIN: phf::Map<&'static str, &'static str> = {};\n",
+        longest,
+        map.build()
+    );
+
+    let out = out_dir.join("zh_phrases.rs");
+    std::fs::write(&out, code).unwrap_or_else(|e| panic!("{}: {}", out.display(), e));
+}
 
 fn main() {
+    println!("cargo:rerun-if-changed=src/lexicon_format.rs");
     // Re-run if dictionaries change
-    println!("cargo:rerun-if-changed=dictionaries/us_gold.json");
-    println!("cargo:rerun-if-changed=dictionaries/us_silver.json");
-    println!("cargo:rerun-if-changed=dictionaries/gb_gold.json");
-    println!("cargo:rerun-if-changed=dictionaries/gb_silver.json");
+    for name in DICTIONARIES {
+        println!("cargo:rerun-if-changed=dictionaries/{}.json", name);
+    }
+
+    let embedded = std::env::var_os("CARGO_FEATURE_ENGLISH").is_some()
+        && std::env::var_os("CARGO_FEATURE_EXTERNAL_DICTIONARIES").is_none();
+    if embedded {
+        let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+        for name in DICTIONARIES {
+            compile_dictionary(name, Path::new(&out_dir));
+        }
+    }
+
+    println!("cargo:rerun-if-changed=dictionaries/zh_pinyin.txt");
+    println!("cargo:rerun-if-changed=dictionaries/zh_phrases.txt");
+    if std::env::var_os("CARGO_FEATURE_CHINESE").is_some() {
+        let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+        compile_hanzi_table(Path::new(&out_dir));
+        compile_phrases(Path::new(&out_dir));
+    }
 
     // Android-specific configuration
     #[cfg(target_os = "android")]
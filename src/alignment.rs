@@ -0,0 +1,363 @@
+//! Word-level alignment between source text, phonemes and tokens
+//!
+//! Engines that support alignment record which part of the phoneme string
+//! each source word (whitespace-delimited, including attached punctuation)
+//! produced. Backends convert the whole text and map the result back to
+//! the source words (rule-based ones by normalized word, English by token),
+//! so that numbers and other context-dependent expansions read the same as
+//! in plain output. Token ranges index into the
+//! padded token vector, so `tokens[segment.tokens]` are the word's token IDs.
+
+use crate::markup;
+use crate::tokenizer::{self, Vocabulary};
+use std::ops::Range;
+
+/// A source word aligned to its phonemes and tokens
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordSegment {
+    /// Byte range of the word in the source text
+    pub source: Range<usize>,
+    /// The word as written in the source text
+    pub word: String,
+    /// The word after normalization (numbers, abbreviations, etc. expanded)
+    pub normalized: String,
+    /// Phonemes produced for the word
+    pub phonemes: String,
+    /// Range of indices into the padded token vector
+    pub tokens: Range<usize>,
+}
+
+/// A phoneme string together with its word segments
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alignment {
+    /// Phoneme string for the whole text
+    pub phonemes: String,
+    /// Segments in source order
+    pub segments: Vec<WordSegment>,
+}
+
+impl Alignment {
+    /// Recompute each segment's token range for a different vocabulary
+    ///
+    /// Engines align against the built-in vocabulary; a model vocabulary that
+    /// lacks some phonemes shifts the token indices.
+    pub fn retokenize(&mut self, vocabulary: &Vocabulary) {
+        let mut offset = 0;
+        for segment in &mut self.segments {
+            let start = self.phonemes[offset..]
+                .find(&segment.phonemes)
+                .map_or(offset, |i| offset + i);
+            let end = start + segment.phonemes.len();
+            let index = |end| token_index(&self.phonemes, end, vocabulary);
+            segment.tokens = index(start)..index(end);
+            offset = end;
+        }
+    }
+
+    /// Replace phonemes the built-in vocabulary lacks with the fallbacks for
+    /// `language` (see [`tokenizer::apply_fallbacks`]), in the whole string
+    /// and in each segment
+    ///
+    /// Token ranges are left as they were; call [`Alignment::retokenize`]
+    /// afterwards.
+    pub fn apply_fallbacks(&mut self, language: &str) {
+        let mut phonemes = String::with_capacity(self.phonemes.len());
+        let mut offset = 0;
+        for segment in &mut self.segments {
+            let start = self.phonemes[offset..]
+                .find(&segment.phonemes)
+                .map_or(offset, |i| offset + i);
+            let end = start + segment.phonemes.len();
+            phonemes.push_str(&tokenizer::apply_fallbacks(language, &self.phonemes[offset..start]));
+            segment.phonemes = tokenizer::apply_fallbacks(language, &segment.phonemes);
+            phonemes.push_str(&segment.phonemes);
+            offset = end;
+        }
+        phonemes.push_str(&tokenizer::apply_fallbacks(language, &self.phonemes[offset..]));
+        self.phonemes = phonemes;
+    }
+}
+
+/// Split text into whitespace-delimited words with their byte ranges
+///
+/// Inline markup containing spaces (`[New York](/nˈu jˈɔɹk/)`) stays one word.
+pub fn source_words(text: &str) -> impl Iterator<Item = (Range<usize>, &str)> {
+    let spans: Vec<Range<usize>> = markup::find(text).into_iter().map(|m| m.range).collect();
+    let mut words = text
+        .split_whitespace()
+        .map(move |word| {
+            let start = word.as_ptr() as usize - text.as_ptr() as usize;
+            start..start + word.len()
+        })
+        .peekable();
+
+    std::iter::from_fn(move || {
+        let mut range = words.next()?;
+        // Extend over markup that continues past the end of the word
+        while let Some(span) = spans.iter().find(|s| s.start < range.end && s.end > range.end) {
+            range.end = span.end;
+            while let Some(next) = words.next_if(|next| next.start < range.end) {
+                range.end = range.end.max(next.end);
+            }
+        }
+        Some((range.clone(), &text[range]))
+    })
+}
+
+/// Align a text for backends that normalize and convert word by word
+///
+/// Each run of text between inline markup is normalized as a whole, as in
+/// plain output, and the normalized words are converted with
+/// `word_to_phonemes` and joined with spaces, matching the
+/// `split_whitespace` loops of the rule-based backends. Normalized words are
+/// mapped back to source words by normalizing the source words on their own;
+/// source words whose expansion depends on their neighbours ("Rp 1.500")
+/// share one segment.
+pub fn align_words<N, P>(text: &str, normalize: N, mut word_to_phonemes: P) -> Alignment
+where
+    N: Fn(&str) -> String,
+    P: FnMut(&str) -> String,
+{
+    let mut builder = AlignmentBuilder::new();
+    let words: Vec<(Range<usize>, &str)> = source_words(text).collect();
+    // Whether a word was converted yet, so the next one needs a separator
+    let mut started = false;
+
+    let mut i = 0;
+    while i < words.len() {
+        let (source, word) = words[i].clone();
+        if !markup::has_markup(word) {
+            let end = words[i..]
+                .iter()
+                .position(|(_, word)| markup::has_markup(word))
+                .map_or(words.len(), |n| i + n);
+            align_run(text, &words[i..end], &normalize, &mut word_to_phonemes, &mut builder, &mut started);
+            i = end;
+            continue;
+        }
+
+        let start = builder.phonemes.len();
+        let phonemes = markup::phonemize(word, |plain| {
+            let parts: Vec<String> = normalize(plain).split_whitespace().map(&mut word_to_phonemes).collect();
+            parts.join(" ")
+        });
+        if started {
+            builder.phonemes.push(' ');
+        }
+        started = true;
+        builder.phonemes.push_str(&phonemes);
+        builder.push_word(source, word, normalize(&markup::strip(word)), start);
+        i += 1;
+    }
+
+    builder.finish()
+}
+
+/// Longest group of source words tried when mapping normalized words back
+const MAX_GROUP: usize = 4;
+
+/// Align a run of source words without markup, normalized as a whole
+fn align_run<N, P>(
+    text: &str,
+    words: &[(Range<usize>, &str)],
+    normalize: &N,
+    word_to_phonemes: &mut P,
+    builder: &mut AlignmentBuilder,
+    started: &mut bool,
+) where
+    N: Fn(&str) -> String,
+    P: FnMut(&str) -> String,
+{
+    let normalized = normalize(&text[words[0].0.start..words[words.len() - 1].0.end]);
+    let targets: Vec<&str> = normalized.split_whitespace().collect();
+
+    let mut next = 0;
+    let mut k = 0;
+    while k < words.len() {
+        // The shortest group of source words whose own normalization is what
+        // comes next; failing that, the rest of the run
+        let (end, count) = (k + 1..=words.len().min(k + MAX_GROUP))
+            .find_map(|end| {
+                let own = normalize(&text[words[k].0.start..words[end - 1].0.end]);
+                let own: Vec<&str> = own.split_whitespace().collect();
+                targets[next..].starts_with(&own).then_some((end, own.len()))
+            })
+            .unwrap_or((words.len(), targets.len() - next));
+        // The last group takes anything left over
+        let count = if end == words.len() { targets.len() - next } else { count };
+
+        let start = builder.phonemes.len();
+        for target in &targets[next..next + count] {
+            if *started {
+                builder.phonemes.push(' ');
+            }
+            *started = true;
+            builder.phonemes.push_str(&word_to_phonemes(target));
+        }
+        let source = words[k].0.start..words[end - 1].0.end;
+        builder.push_word(source.clone(), &text[source], targets[next..next + count].join(" "), start);
+        next += count;
+        k = end;
+    }
+}
+
+/// Incrementally builds an [`Alignment`]
+///
+/// Callers append to `phonemes` directly, then call `push_word` with the
+/// offset at which the word's phonemes started.
+pub(crate) struct AlignmentBuilder {
+    pub phonemes: String,
+    words: Vec<(Range<usize>, String, String, Range<usize>)>,
+}
+
+impl AlignmentBuilder {
+    pub fn new() -> Self {
+        Self {
+            phonemes: String::new(),
+            words: Vec::new(),
+        }
+    }
+
+    /// Record a word whose phonemes were appended starting at byte `start`
+    pub fn push_word(&mut self, source: Range<usize>, word: &str, normalized: String, start: usize) {
+        let appended = &self.phonemes[start..];
+        let begin = start + appended.len() - appended.trim_start().len();
+        let phonemes = begin..begin + appended.trim().len();
+        self.words.push((source, word.to_string(), normalized, phonemes));
+    }
+
+    /// Collapse runs of whitespace into single spaces and trim the ends, as
+    /// engines do with their plain output, keeping word ranges in place
+    #[cfg(feature = "english")]
+    pub fn collapse_whitespace(&mut self) {
+        let mut collapsed = String::with_capacity(self.phonemes.len());
+        // New offset of each old byte offset
+        let mut offsets = Vec::with_capacity(self.phonemes.len() + 1);
+        let mut pending_space = false;
+        for (i, c) in self.phonemes.char_indices() {
+            offsets.resize(i, collapsed.len());
+            if c.is_whitespace() {
+                pending_space = !collapsed.is_empty();
+                offsets.push(collapsed.len());
+                continue;
+            }
+            if pending_space {
+                collapsed.push(' ');
+                pending_space = false;
+            }
+            offsets.push(collapsed.len());
+            collapsed.push(c);
+        }
+        offsets.resize(self.phonemes.len() + 1, collapsed.len());
+
+        for (_, _, _, range) in &mut self.words {
+            *range = offsets[range.start]..offsets[range.end].max(offsets[range.start]);
+        }
+        self.phonemes = collapsed;
+    }
+
+    /// Finish the alignment, computing token ranges for each word
+    pub fn finish(mut self) -> Alignment {
+        let trimmed = self.phonemes.trim_end().len();
+        self.phonemes.truncate(trimmed);
+
+        let vocabulary = Vocabulary::builtin();
+        let token_index = |end: usize| token_index(&self.phonemes, end, vocabulary);
+        let segments = self
+            .words
+            .iter()
+            .map(|(source, word, normalized, phonemes)| WordSegment {
+                source: source.clone(),
+                word: word.clone(),
+                normalized: normalized.clone(),
+                phonemes: self.phonemes[phonemes.clone()].to_string(),
+                tokens: token_index(phonemes.start)..token_index(phonemes.end),
+            })
+            .collect();
+
+        Alignment {
+            phonemes: self.phonemes,
+            segments,
+        }
+    }
+}
+
+/// Token index of a phoneme byte offset (offset by the leading PAD)
+fn token_index(phonemes: &str, end: usize, vocabulary: &Vocabulary) -> usize {
+    let count = phonemes[..end].chars().filter(|&c| vocabulary.contains(c)).count();
+    (count + 1).min(tokenizer::MAX_TOKENS + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_words() {
+        let text = "  one two\tthree ";
+        let words: Vec<_> = source_words(text).collect();
+        assert_eq!(words, vec![(2..5, "one"), (6..9, "two"), (10..15, "three")]);
+    }
+
+    #[test]
+    fn test_source_words_markup() {
+        let text = "in [New York](/nˈu jˈɔɹk/), now";
+        let words: Vec<_> = source_words(text).map(|(_, w)| w).collect();
+        assert_eq!(words, vec!["in", "[New York](/nˈu jˈɔɹk/),", "now"]);
+
+        let alignment = align_words(text, |w| w.to_string(), |w| w.to_uppercase());
+        assert_eq!(alignment.phonemes, "IN nˈu jˈɔɹk, NOW");
+        assert_eq!(alignment.segments[1].normalized, "New York,");
+        assert_eq!(alignment.segments[1].phonemes, "nˈu jˈɔɹk,");
+    }
+
+    #[test]
+    fn test_align_words() {
+        let text = "ab 2 cd";
+        let alignment = align_words(
+            text,
+            |w| w.replace('2', "two x"),
+            |w| w.to_string(),
+        );
+
+        assert_eq!(alignment.phonemes, "ab two x cd");
+        let seg = &alignment.segments[1];
+        assert_eq!(seg.source, 3..4);
+        assert_eq!(seg.word, "2");
+        assert_eq!(seg.normalized, "two x");
+        assert_eq!(seg.phonemes, "two x");
+
+        let tokens = tokenizer::phonemes_to_tokens(&alignment.phonemes);
+        assert_eq!(tokens[seg.tokens.clone()], tokenizer::phonemes_to_tokens("two x")[1..6]);
+    }
+
+    #[test]
+    fn test_aligned_matches_plain_output() {
+        let registry = crate::engine::EngineRegistry::with_builtin_engines();
+        let samples = [
+            ("es", "Tengo 25 años,  y pagué 1.500,50 € el 3/5/2024 a las 12:30."),
+            ("id", "Saya punya 25 buku,  harganya Rp 1.500 pada 3/5/2024 jam 12:30."),
+            ("tr", "25 yaşındayım,  fiyatı 1.500,50 TL ve %20 indirim var."),
+            ("it", "Ho 25 anni,  e ho pagato 1.500,50 € il 3/5/2024 alle 12:30."),
+            ("de", "Ich bin 25 Jahre alt,  und zahlte 1.500,50 € am 3.5.2024 um 12:30 Uhr."),
+            ("pt", "Tenho 25 anos,  e paguei R$ 1.500,50 em 3/5/2024 às 12:30."),
+            ("ko", "저는 25살이고,  1,500원을 12:30에 냈어요."),
+            ("vi", "Tôi 25 tuổi,  trả 1.500 đồng lúc 12:30 ngày 3/5/2024."),
+        ];
+        for (code, text) in samples {
+            let Some(engine) = registry.get(code) else {
+                continue;
+            };
+            let alignment = engine.phonemize_aligned(text).unwrap();
+            let plain = engine.phonemize(text);
+            assert_eq!(alignment.phonemes, plain, "{}", code);
+            let joined: Vec<&str> = alignment.segments.iter().map(|s| s.phonemes.as_str()).collect();
+            assert_eq!(joined.join(" "), plain, "{}", code);
+
+            // Words whose reading depends on their neighbours share a segment
+            if code == "id" {
+                assert!(alignment.segments.iter().any(|s| s.word == "Rp 1.500"));
+            }
+        }
+    }
+}
@@ -0,0 +1,317 @@
+//! Command-line batch phonemizer
+//!
+//! Reads text from files (or stdin) and writes one record per input line as
+//! phonemes, token IDs or JSON. Run `kokoro-g2p --help` for the options.
+
+use kokoro_g2p::pipeline::G2PResult;
+use kokoro_g2p::{G2PError, KPipeline};
+use std::io::{self, BufRead, BufWriter, Write};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: kokoro-g2p [OPTIONS] [FILE]...
+
+Convert each line of FILE (or stdin when no FILE or FILE is -) to phonemes.
+
+Options:
+  -l, --lang <CODE>      Language code (default: en-us, \"auto\" to detect)
+      --british          Use British English (same as --lang en-gb)
+  -f, --format <FORMAT>  Output format: phonemes, tokens or json (default: phonemes)
+      --lexicon <PATH>   Load a JSON or TSV user lexicon (English, repeatable)
+      --chunk            Split lines longer than the token limit into several records
+  -h, --help             Print this help
+  -V, --version          Print the version";
+
+/// Output format of each record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Phonemes,
+    Tokens,
+    Json,
+}
+
+impl Format {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "phonemes" => Some(Format::Phonemes),
+            "tokens" => Some(Format::Tokens),
+            "json" => Some(Format::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Parsed command-line options
+#[derive(Debug, PartialEq)]
+struct Options {
+    language: String,
+    format: Format,
+    lexicons: Vec<String>,
+    chunk: bool,
+    files: Vec<String>,
+}
+
+/// What the command line asks for
+#[derive(Debug, PartialEq)]
+enum Command {
+    Run(Options),
+    Help,
+    Version,
+}
+
+fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut options = Options {
+        language: "en-us".to_string(),
+        format: Format::Phonemes,
+        lexicons: Vec::new(),
+        chunk: false,
+        files: Vec::new(),
+    };
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        // Accept both `--opt value` and `--opt=value`
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name.to_string(), Some(value.to_string())),
+            _ => (arg.clone(), None),
+        };
+        let mut value = |name: &str| {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{} requires a value", name))
+        };
+
+        match name.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "-l" | "--lang" => options.language = value(&name)?,
+            "--british" => options.language = "en-gb".to_string(),
+            "-f" | "--format" => {
+                let format = value(&name)?;
+                options.format = Format::parse(&format).ok_or_else(|| format!("unknown format '{}'", format))?;
+            }
+            "--lexicon" => options.lexicons.push(value(&name)?),
+            "--chunk" => options.chunk = true,
+            "-" => options.files.push(arg),
+            _ if name.starts_with('-') => return Err(format!("unknown option '{}'", name)),
+            _ => options.files.push(arg),
+        }
+    }
+    Ok(Command::Run(options))
+}
+
+/// Write a result in the requested format
+fn write_record<W: Write>(out: &mut W, result: &G2PResult, format: Format, line: usize) -> io::Result<()> {
+    match format {
+        Format::Phonemes => writeln!(out, "{}", result.phonemes),
+        Format::Tokens => {
+            let ids: Vec<String> = result.tokens.iter().map(|id| id.to_string()).collect();
+            writeln!(out, "{}", ids.join(" "))
+        }
+        Format::Json => {
+            let mut json = result.to_json();
+            json["line"] = line.into();
+            writeln!(out, "{}", json)
+        }
+    }
+}
+
+/// Write the record for a line that failed to convert: an empty line, or a
+/// JSON object with the error
+fn write_failure<W: Write>(out: &mut W, err: &G2PError, format: Format, line: usize) -> io::Result<()> {
+    match format {
+        Format::Phonemes | Format::Tokens => writeln!(out),
+        Format::Json => writeln!(out, "{}", serde_json::json!({ "line": line, "error": err.to_string() })),
+    }
+}
+
+/// Process every line of `input`, returning the number of lines that failed
+fn process_input<R: BufRead, W: Write>(
+    pipeline: &KPipeline,
+    options: &Options,
+    name: &str,
+    input: R,
+    out: &mut W,
+) -> io::Result<usize> {
+    let mut failures = 0;
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        let line_number = index + 1;
+
+        let results = if options.chunk {
+            pipeline.try_process_chunked(&line)
+        } else {
+            pipeline.try_process(&line).map(|result| vec![result])
+        };
+        match results {
+            Ok(results) => {
+                for result in &results {
+                    write_record(out, result, options.format, line_number)?;
+                }
+            }
+            Err(err) => {
+                eprintln!("kokoro-g2p: {}:{}: {}", name, line_number, err);
+                failures += 1;
+                write_failure(out, &err, options.format, line_number)?;
+            }
+        }
+    }
+    Ok(failures)
+}
+
+fn build_pipeline(options: &Options) -> Result<KPipeline, G2PError> {
+    let mut pipeline = KPipeline::try_new(&options.language)?;
+    // JSON records include word alignment
+    pipeline.set_alignment(options.format == Format::Json);
+
+    #[cfg(feature = "english")]
+    for path in &options.lexicons {
+        pipeline.load_user_lexicon(path)?;
+    }
+    #[cfg(not(feature = "english"))]
+    if !options.lexicons.is_empty() {
+        return Err(G2PError::FeatureNotEnabled {
+            language: "en-us".to_string(),
+            feature: "english",
+        });
+    }
+
+    Ok(pipeline)
+}
+
+fn run(options: &Options) -> Result<usize, String> {
+    let pipeline = build_pipeline(options).map_err(|e| e.to_string())?;
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+
+    let files = if options.files.is_empty() {
+        vec!["-".to_string()]
+    } else {
+        options.files.clone()
+    };
+
+    let mut failures = 0;
+    for file in &files {
+        let result = if file == "-" {
+            process_input(&pipeline, options, "<stdin>", io::stdin().lock(), &mut out)
+        } else {
+            let reader = std::fs::File::open(file)
+                .map(io::BufReader::new)
+                .map_err(|e| format!("{}: {}", file, e))?;
+            process_input(&pipeline, options, file, reader, &mut out)
+        };
+        failures += result.map_err(|e| format!("{}: {}", file, e))?;
+    }
+    out.flush().map_err(|e| e.to_string())?;
+    Ok(failures)
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(Command::Run(options)) => options,
+        Ok(Command::Help) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Ok(Command::Version) => {
+            println!("kokoro-g2p {}", kokoro_g2p::VERSION);
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("kokoro-g2p: {}\n\n{}", message, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(&options) {
+        Ok(0) => ExitCode::SUCCESS,
+        Ok(_) => ExitCode::FAILURE,
+        Err(message) => {
+            eprintln!("kokoro-g2p: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        parse_args(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let Command::Run(options) = parse(&["--british", "-f", "json", "--lexicon=a.tsv", "--chunk", "in.txt", "-"]).unwrap() else {
+            panic!("expected options");
+        };
+        assert_eq!(options.language, "en-gb");
+        assert_eq!(options.format, Format::Json);
+        assert_eq!(options.lexicons, vec!["a.tsv"]);
+        assert!(options.chunk);
+        assert_eq!(options.files, vec!["in.txt", "-"]);
+
+        assert_eq!(parse(&["--help"]), Ok(Command::Help));
+        assert!(parse(&["--format", "xml"]).is_err());
+        assert!(parse(&["--lang"]).is_err());
+        assert!(parse(&["--bogus"]).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "spanish")]
+    fn test_process_input() {
+        let options = Options {
+            language: "es".to_string(),
+            format: Format::Tokens,
+            lexicons: Vec::new(),
+            chunk: false,
+            files: Vec::new(),
+        };
+        let pipeline = build_pipeline(&options).unwrap();
+        let mut out = Vec::new();
+        let failures = process_input(&pipeline, &options, "test", "hola\nmundo\n".as_bytes(), &mut out).unwrap();
+        assert_eq!(failures, 0);
+
+        let expected: Vec<String> = ["hola", "mundo"]
+            .iter()
+            .map(|w| {
+                let tokens: Vec<String> = kokoro_g2p::text_to_tokens(w, "es").iter().map(|id| id.to_string()).collect();
+                tokens.join(" ")
+            })
+            .collect();
+        assert_eq!(String::from_utf8(out).unwrap(), format!("{}\n{}\n", expected[0], expected[1]));
+    }
+
+    #[test]
+    #[cfg(feature = "spanish")]
+    fn test_process_input_failures() {
+        let mut options = Options {
+            language: "es".to_string(),
+            format: Format::Phonemes,
+            lexicons: Vec::new(),
+            chunk: false,
+            files: Vec::new(),
+        };
+        let mut pipeline = build_pipeline(&options).unwrap();
+        // A vocabulary without the phonemes of "hola"
+        let ids = [('ˈ', 1), ('m', 2), ('u', 3), ('n', 4), ('d', 5), ('o', 6)].into_iter().collect();
+        pipeline.set_vocabulary(kokoro_g2p::Vocabulary::from_map(ids));
+
+        for chunk in [false, true] {
+            options.chunk = chunk;
+            let mut out = Vec::new();
+            let failures = process_input(&pipeline, &options, "test", "hola\nmundo\n".as_bytes(), &mut out).unwrap();
+            assert_eq!(failures, 1, "chunk: {}", chunk);
+            assert_eq!(String::from_utf8(out).unwrap(), "\nmˈundo\n", "chunk: {}", chunk);
+        }
+
+        options.format = Format::Json;
+        let mut out = Vec::new();
+        process_input(&pipeline, &options, "test", "hola\n".as_bytes(), &mut out).unwrap();
+        let record: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(record["line"], 1);
+        assert!(record["error"].is_string());
+    }
+}
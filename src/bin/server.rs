@@ -0,0 +1,281 @@
+//! Local HTTP/JSON phonemization service
+//!
+//! Built with the `server` feature. Each worker thread owns a warm
+//! `KPipeline`, so engines are created once per worker rather than once per
+//! request.
+//!
+//! Endpoints:
+//! - `POST /phonemize` and `POST /tokenize` take
+//!   `{"text": "...", "language": "en-us", "alignment": false, "ssml": false}`
+//!   (only `text` is required) and return the `G2PResult` as JSON; `/phonemize`
+//!   leaves out the tokens
+//! - `GET /languages` lists the compiled-in features and language codes
+//! - `GET /health` reports liveness and the crate version
+
+use kokoro_g2p::{enabled_features, G2PError, InputMode, KPipeline};
+use serde_json::{json, Value};
+use std::io::Read;
+use std::sync::Arc;
+use tiny_http::{Header, Method, Response, Server};
+
+const USAGE: &str = "\
+Usage: kokoro-g2p-server [OPTIONS]
+
+Options:
+      --addr <ADDR>       Address to listen on (default: 127.0.0.1:8080)
+      --workers <N>       Number of worker threads (default: available CPUs)
+  -l, --lang <CODE>       Default language for requests without one (default: en-us)
+  -h, --help              Print this help";
+
+/// Largest request body accepted, in bytes
+const MAX_BODY: u64 = 1 << 20;
+
+/// Server configuration
+#[derive(Debug)]
+struct Config {
+    addr: String,
+    workers: usize,
+    language: String,
+}
+
+fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Option<Config>, String> {
+    let mut config = Config {
+        addr: "127.0.0.1:8080".to_string(),
+        workers: std::thread::available_parallelism().map_or(1, |n| n.get()),
+        language: "en-us".to_string(),
+    };
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} requires a value", arg));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--addr" => config.addr = value()?,
+            "--workers" => {
+                config.workers = value()?
+                    .parse()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .ok_or("--workers must be a positive integer")?;
+            }
+            "-l" | "--lang" => config.language = value()?,
+            _ => return Err(format!("unknown option '{}'", arg)),
+        }
+    }
+    Ok(Some(config))
+}
+
+/// HTTP status for a conversion error
+fn error_status(err: &G2PError) -> u16 {
+    match err {
+        G2PError::UnsupportedLanguage(_)
+        | G2PError::FeatureNotEnabled { .. }
+        | G2PError::InvalidInput(_)
+        | G2PError::InvalidVocabulary(_) => 400,
+        G2PError::UnmappablePhoneme { .. } | G2PError::InputTooLong { .. } => 422,
+        G2PError::DictionaryLoad(_) => 500,
+        G2PError::DictionaryNotLoaded(_) => 503,
+    }
+}
+
+fn error_body(status: u16, message: &str, code: Option<i32>) -> (u16, Value) {
+    (status, json!({ "error": message, "code": code }))
+}
+
+/// Handle a `/phonemize` or `/tokenize` request body
+fn convert(pipeline: &mut KPipeline, default_language: &str, body: &str, tokens: bool) -> (u16, Value) {
+    let request: Value = match serde_json::from_str(body) {
+        Ok(request) => request,
+        Err(e) => return error_body(400, &format!("invalid JSON: {}", e), None),
+    };
+    let Some(text) = request["text"].as_str() else {
+        return error_body(400, "missing string field 'text'", None);
+    };
+    let language = request["language"].as_str().unwrap_or(default_language);
+
+    if let Err(err) = pipeline.try_set_language(language) {
+        return error_body(error_status(&err), &err.to_string(), Some(err.code()));
+    }
+    pipeline.set_alignment(request["alignment"].as_bool().unwrap_or(false));
+    pipeline.set_input_mode(if request["ssml"].as_bool().unwrap_or(false) {
+        InputMode::Ssml
+    } else {
+        InputMode::PlainText
+    });
+
+    match pipeline.try_process(text) {
+        Ok(result) => {
+            let mut json = result.to_json();
+            if let (false, Some(object)) = (tokens, json.as_object_mut()) {
+                object.remove("tokens");
+            }
+            (200, json)
+        }
+        Err(err) => error_body(error_status(&err), &err.to_string(), Some(err.code())),
+    }
+}
+
+/// Read a request body of at most `MAX_BODY` bytes, failing with the
+/// response to send for bodies that are too large or unreadable
+///
+/// `declared` is the Content-Length, if any; a body without one is read up
+/// to one byte past the limit to tell whether it exceeds it.
+fn read_body<R: Read>(reader: R, declared: Option<usize>) -> Result<String, (u16, Value)> {
+    let too_large = || error_body(413, &format!("request body exceeds {} bytes", MAX_BODY), None);
+    if declared.is_some_and(|length| length as u64 > MAX_BODY) {
+        return Err(too_large());
+    }
+    let mut body = Vec::new();
+    reader
+        .take(MAX_BODY + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| error_body(400, &format!("unreadable body: {}", e), None))?;
+    if body.len() as u64 > MAX_BODY {
+        return Err(too_large());
+    }
+    String::from_utf8(body).map_err(|e| error_body(400, &format!("unreadable body: {}", e), None))
+}
+
+/// Route a request to its handler, returning the status and JSON body
+fn handle(pipeline: &mut KPipeline, default_language: &str, method: &Method, path: &str, body: &str) -> (u16, Value) {
+    match (method, path) {
+        (Method::Post, "/phonemize") => convert(pipeline, default_language, body, false),
+        (Method::Post, "/tokenize") => convert(pipeline, default_language, body, true),
+        (Method::Get, "/languages") => (
+            200,
+            json!({
+                "features": enabled_features(),
+                "languages": pipeline.registry().languages(),
+            }),
+        ),
+        (Method::Get, "/health") => (200, json!({ "status": "ok", "version": kokoro_g2p::VERSION })),
+        (_, "/phonemize" | "/tokenize" | "/languages" | "/health") => error_body(405, "method not allowed", None),
+        _ => error_body(404, "not found", None),
+    }
+}
+
+/// Serve requests until the server shuts down
+fn worker(server: Arc<Server>, default_language: String) {
+    let mut pipeline = KPipeline::new(&default_language);
+    // Load the default language's dictionaries before the first request
+    if let Err(err) = pipeline.warm_up(&[pipeline.language().clone()]) {
+        log::warn!("Failed to warm up {}: {}", default_language, err);
+    }
+
+    while let Ok(mut request) = server.recv() {
+        let declared = request.body_length();
+        let read = read_body(request.as_reader(), declared);
+        let path = request.url().split('?').next().unwrap_or("").to_string();
+
+        let (status, json) = match read {
+            Ok(body) => handle(&mut pipeline, &default_language, request.method(), &path, &body),
+            Err(response) => response,
+        };
+
+        let header = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).expect("valid header");
+        let response = Response::from_string(json.to_string())
+            .with_status_code(status)
+            .with_header(header);
+        if let Err(e) = request.respond(response) {
+            log::warn!("Failed to send response: {}", e);
+        }
+    }
+}
+
+fn main() {
+    let config = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(config)) => config,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(message) => {
+            eprintln!("kokoro-g2p-server: {}\n\n{}", message, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    if let Err(err) = KPipeline::try_new(&config.language) {
+        eprintln!("kokoro-g2p-server: {}", err);
+        std::process::exit(1);
+    }
+
+    let server = match Server::http(&config.addr) {
+        Ok(server) => Arc::new(server),
+        Err(e) => {
+            eprintln!("kokoro-g2p-server: cannot listen on {}: {}", config.addr, e);
+            std::process::exit(1);
+        }
+    };
+    eprintln!("kokoro-g2p-server: listening on http://{} with {} workers", config.addr, config.workers);
+
+    let workers: Vec<_> = (0..config.workers)
+        .map(|_| {
+            let server = Arc::clone(&server);
+            let language = config.language.clone();
+            std::thread::spawn(move || worker(server, language))
+        })
+        .collect();
+    for handle in workers {
+        let _ = handle.join();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "spanish")]
+    fn test_convert() {
+        let mut pipeline = KPipeline::new("es");
+        let (status, json) = handle(&mut pipeline, "es", &Method::Post, "/tokenize", r#"{"text": "hola", "alignment": true}"#);
+        assert_eq!(status, 200);
+        assert_eq!(json, {
+            let mut p = KPipeline::new("es");
+            p.set_alignment(true);
+            p.process("hola").to_json()
+        });
+
+        let (status, json) = handle(&mut pipeline, "es", &Method::Post, "/phonemize", r#"{"text": "hola"}"#);
+        assert_eq!(status, 200);
+        assert!(json.get("tokens").is_none());
+        assert_eq!(json["phonemes"], kokoro_g2p::text_to_phonemes("hola", "es"));
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut pipeline = KPipeline::new("en-us");
+        let post = |pipeline: &mut KPipeline, body: &str| handle(pipeline, "en-us", &Method::Post, "/phonemize", body).0;
+        assert_eq!(post(&mut pipeline, "not json"), 400);
+        assert_eq!(post(&mut pipeline, r#"{"language": "en-us"}"#), 400);
+        assert_eq!(post(&mut pipeline, r#"{"text": "a", "language": "xx"}"#), 400);
+        assert_eq!(handle(&mut pipeline, "en-us", &Method::Get, "/phonemize", "").0, 405);
+        assert_eq!(handle(&mut pipeline, "en-us", &Method::Get, "/nope", "").0, 404);
+    }
+
+    #[test]
+    fn test_read_body() {
+        let limit = MAX_BODY as usize;
+        assert_eq!(read_body("{}".as_bytes(), Some(2)), Ok("{}".to_string()));
+        assert_eq!(read_body(vec![b' '; limit].as_slice(), None).map(|body| body.len()), Ok(limit));
+
+        // Oversized bodies are rejected rather than truncated, with or
+        // without a Content-Length
+        assert_eq!(read_body("{}".as_bytes(), Some(limit + 1)).unwrap_err().0, 413);
+        assert_eq!(read_body(vec![b' '; limit + 1].as_slice(), None).unwrap_err().0, 413);
+        assert_eq!(read_body(&[0xff, 0xfe][..], None).unwrap_err().0, 400);
+    }
+
+    #[test]
+    fn test_languages_and_health() {
+        let mut pipeline = KPipeline::new("en-us");
+        let (status, json) = handle(&mut pipeline, "en-us", &Method::Get, "/languages", "");
+        assert_eq!(status, 200);
+        assert_eq!(json["features"], json!(enabled_features()));
+
+        let (status, json) = handle(&mut pipeline, "en-us", &Method::Get, "/health", "");
+        assert_eq!(status, 200);
+        assert_eq!(json["status"], "ok");
+    }
+}
@@ -8,6 +8,9 @@
 
 pub mod normalizer;
 
+use crate::alignment::{self, Alignment};
+use crate::engine::{EngineCapabilities, G2PEngine};
+use crate::markup;
 use crate::tokenizer;
 
 /// German G2P processor
@@ -19,6 +22,10 @@ impl GermanG2P {
     }
 
     pub fn text_to_phonemes(&self, text: &str) -> String {
+        if markup::has_markup(text) {
+            return markup::phonemize(text, |plain| self.text_to_phonemes(plain));
+        }
+
         let normalized = normalizer::normalize(text);
         let mut result = String::new();
         let words: Vec<&str> = normalized.split_whitespace().collect();
@@ -38,7 +45,7 @@ impl GermanG2P {
     }
 
     pub fn text_to_tokens(&self, text: &str) -> Vec<i64> {
-        let phonemes = self.text_to_phonemes(text);
+        let phonemes = tokenizer::apply_fallbacks("de", &self.text_to_phonemes(text));
         tokenizer::phonemes_to_tokens(&phonemes)
     }
 }
@@ -49,6 +56,34 @@ impl Default for GermanG2P {
     }
 }
 
+impl G2PEngine for GermanG2P {
+    fn language_code(&self) -> &str {
+        "de"
+    }
+
+    fn phonemize(&self, text: &str) -> String {
+        self.text_to_phonemes(text)
+    }
+
+    fn phonemize_aligned(&self, text: &str) -> Option<Alignment> {
+        Some(alignment::align_words(text, normalizer::normalize, |word| {
+            if is_punctuation(word) {
+                word.to_string()
+            } else {
+                word_to_phonemes(word)
+            }
+        }))
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            normalization: true,
+            stress: true,
+            ..Default::default()
+        }
+    }
+}
+
 pub fn text_to_tokens(text: &str) -> Vec<i64> {
     GermanG2P::new().text_to_tokens(text)
 }
@@ -348,6 +383,9 @@ mod tests {
     fn test_diphthongs() {
         let phonemes = word_to_phonemes("mein");
         assert!(phonemes.contains('a') && phonemes.contains('ɪ'));
+
+        assert!(word_to_phonemes("bäume").contains("ɔʏ"));
+        assert!(word_to_phonemes("freude").contains("ɔʏ"));
     }
 
     #[test]
@@ -0,0 +1,264 @@
+//! Common interface for language-specific G2P engines
+//!
+//! Every language backend implements [`G2PEngine`], and the [`EngineRegistry`]
+//! maps language codes to engines. `KPipeline` dispatches through the registry,
+//! so custom engines for languages this crate does not cover can be plugged in
+//! without modifying the pipeline.
+//!
+//! # Example
+//!
+//! ```rust
+//! use kokoro_g2p::engine::{EngineCapabilities, G2PEngine};
+//! use kokoro_g2p::KPipeline;
+//!
+//! struct Shouty;
+//!
+//! impl G2PEngine for Shouty {
+//!     fn language_code(&self) -> &str {
+//!         "x-shout"
+//!     }
+//!
+//!     fn phonemize(&self, text: &str) -> String {
+//!         text.to_lowercase()
+//!     }
+//! }
+//!
+//! let mut pipeline = KPipeline::new("en-us");
+//! pipeline.register_engine("x-shout", || Box::new(Shouty));
+//! pipeline.set_language("x-shout");
+//! assert_eq!(pipeline.process("HA").phonemes, "ha");
+//! ```
+
+use crate::alignment::Alignment;
+use crate::error::G2PError;
+use crate::tokenizer;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Static description of what an engine supports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EngineCapabilities {
+    /// Uses a pronunciation dictionary rather than rules alone
+    pub dictionary: bool,
+    /// Expands numbers, currency, dates, etc. before conversion
+    pub normalization: bool,
+    /// Emits lexical stress markers (ˈ, ˌ)
+    pub stress: bool,
+    /// Emits tone markers
+    pub tones: bool,
+}
+
+/// A grapheme-to-phoneme engine for a single language
+pub trait G2PEngine: Send + Sync {
+    /// Canonical language code handled by this engine (e.g. "en-us", "zh")
+    fn language_code(&self) -> &str;
+
+    /// Convert text to a phoneme string
+    fn phonemize(&self, text: &str) -> String;
+
+    /// Convert text to a phoneme string, reporting engine failures
+    ///
+    /// Engines that can fail (e.g. a dictionary that did not load) should
+    /// override this; the default never fails.
+    fn try_phonemize(&self, text: &str) -> Result<String, G2PError> {
+        Ok(self.phonemize(text))
+    }
+
+    /// Convert text to phonemes with word-level alignment
+    ///
+    /// Returns `None` if the engine does not support alignment.
+    fn phonemize_aligned(&self, _text: &str) -> Option<Alignment> {
+        None
+    }
+
+    /// Like [`G2PEngine::phonemize_aligned`], reporting engine failures
+    fn try_phonemize_aligned(&self, text: &str) -> Result<Option<Alignment>, G2PError> {
+        Ok(self.phonemize_aligned(text))
+    }
+
+    /// Convert text to token IDs, padded at start and end
+    fn tokenize(&self, text: &str) -> Vec<i64> {
+        tokenizer::phonemes_to_tokens(&self.phonemize(text))
+    }
+
+    /// Describe the features this engine supports
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities::default()
+    }
+}
+
+/// Factory used to lazily create an engine
+pub type EngineFactory = Box<dyn Fn() -> Box<dyn G2PEngine> + Send + Sync>;
+
+/// A registered factory and the engine it created, if any
+struct Entry {
+    factory: EngineFactory,
+    engine: OnceLock<Box<dyn G2PEngine>>,
+}
+
+/// Registry mapping language codes to G2P engines
+///
+/// Engines are created on first use and cached for subsequent calls. Lookup
+/// only needs `&self`, so a registry can be shared between threads.
+pub struct EngineRegistry {
+    entries: HashMap<String, Entry>,
+    #[cfg(feature = "english")]
+    user_lexicon: crate::lexicon::UserLexicon,
+}
+
+impl EngineRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+            #[cfg(feature = "english")]
+            user_lexicon: crate::lexicon::UserLexicon::new(),
+        }
+    }
+
+    /// Create a registry containing every engine compiled into this build
+    pub fn with_builtin_engines() -> Self {
+        // Every engine is behind a feature, so a build without any never mutates it
+        #[allow(unused_mut)]
+        let mut registry = Self::new();
+
+        #[cfg(feature = "english")]
+        {
+            use crate::g2p::G2P;
+            let user = registry.user_lexicon.clone();
+            registry.register("en-us", move || Box::new(G2P::with_user_lexicon(false, user.clone())));
+            let user = registry.user_lexicon.clone();
+            registry.register("en-gb", move || Box::new(G2P::with_user_lexicon(true, user.clone())));
+        }
+        #[cfg(feature = "chinese")]
+        registry.register("zh", || Box::new(crate::zh::ChineseG2P::new()));
+        #[cfg(feature = "spanish")]
+        registry.register("es", || Box::new(crate::es::SpanishG2P::new()));
+        #[cfg(feature = "indonesian")]
+        registry.register("id", || Box::new(crate::id::IndonesianG2P::new()));
+        #[cfg(feature = "turkish")]
+        registry.register("tr", || Box::new(crate::tr::TurkishG2P::new()));
+        #[cfg(feature = "italian")]
+        registry.register("it", || Box::new(crate::it::ItalianG2P::new()));
+        #[cfg(feature = "german")]
+        registry.register("de", || Box::new(crate::de::GermanG2P::new()));
+        #[cfg(feature = "portuguese")]
+        registry.register("pt", || Box::new(crate::pt::PortugueseG2P::new()));
+        #[cfg(feature = "korean")]
+        registry.register("ko", || Box::new(crate::ko::KoreanG2P::new()));
+        #[cfg(feature = "vietnamese")]
+        registry.register("vi", || Box::new(crate::vi::VietnameseG2P::new()));
+
+        registry
+    }
+
+    /// Register a factory for a language code
+    ///
+    /// Replaces any engine previously registered for the same code, including
+    /// built-in ones.
+    pub fn register<F>(&mut self, code: &str, factory: F)
+    where
+        F: Fn() -> Box<dyn G2PEngine> + Send + Sync + 'static,
+    {
+        let entry = Entry {
+            factory: Box::new(factory),
+            engine: OnceLock::new(),
+        };
+        self.entries.insert(code.to_lowercase(), entry);
+    }
+
+    /// Get the user lexicon shared by the built-in English engines
+    #[cfg(feature = "english")]
+    pub fn user_lexicon(&self) -> &crate::lexicon::UserLexicon {
+        &self.user_lexicon
+    }
+
+    /// Check whether an engine is registered for a language code
+    pub fn contains(&self, code: &str) -> bool {
+        self.entries.contains_key(&code.to_lowercase())
+    }
+
+    /// Get the engine for a language code, creating it on first use
+    ///
+    /// Concurrent first calls for the same code create the engine only once.
+    pub fn get(&self, code: &str) -> Option<&dyn G2PEngine> {
+        let entry = self.entries.get(&code.to_lowercase())?;
+        Some(entry.engine.get_or_init(|| (entry.factory)()).as_ref())
+    }
+
+    /// Check whether the engine for a language code has been created
+    pub fn is_initialized(&self, code: &str) -> bool {
+        self.entries
+            .get(&code.to_lowercase())
+            .is_some_and(|entry| entry.engine.get().is_some())
+    }
+
+    /// List all registered language codes, sorted
+    pub fn languages(&self) -> Vec<&str> {
+        let mut codes: Vec<&str> = self.entries.keys().map(|s| s.as_str()).collect();
+        codes.sort_unstable();
+        codes
+    }
+}
+
+impl Default for EngineRegistry {
+    fn default() -> Self {
+        Self::with_builtin_engines()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct EchoEngine;
+
+    impl G2PEngine for EchoEngine {
+        fn language_code(&self) -> &str {
+            "x-echo"
+        }
+
+        fn phonemize(&self, text: &str) -> String {
+            text.to_string()
+        }
+    }
+
+    #[test]
+    fn test_register_custom_engine() {
+        let mut registry = EngineRegistry::new();
+        assert!(!registry.contains("x-echo"));
+
+        registry.register("X-Echo", || Box::new(EchoEngine));
+        assert!(registry.contains("x-echo"));
+        assert!(!registry.is_initialized("x-echo"));
+
+        let engine = registry.get("x-echo").unwrap();
+        assert_eq!(engine.language_code(), "x-echo");
+        assert_eq!(engine.phonemize("abc"), "abc");
+        assert_eq!(engine.capabilities(), EngineCapabilities::default());
+        assert!(registry.is_initialized("x-echo"));
+    }
+
+    #[test]
+    fn test_default_tokenize() {
+        let tokens = EchoEngine.tokenize("ab");
+        assert_eq!(tokens.len(), 4);
+        assert_eq!(tokens[0], tokenizer::PAD_TOKEN);
+    }
+
+    #[test]
+    fn test_unknown_code() {
+        let registry = EngineRegistry::new();
+        assert!(registry.get("tlh").is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_builtin_english() {
+        let registry = EngineRegistry::with_builtin_engines();
+        assert!(registry.languages().contains(&"en-gb"));
+        let engine = registry.get("en-us").unwrap();
+        assert!(engine.capabilities().dictionary);
+        assert!(!engine.phonemize("hello").is_empty());
+    }
+}
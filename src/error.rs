@@ -0,0 +1,118 @@
+//! Error type for the fallible `try_*` APIs
+//!
+//! The infallible functions (`text_to_tokens`, `KPipeline::process`, ...) keep
+//! their lenient behaviour of logging and returning empty output. The `try_*`
+//! variants return a [`G2PError`] instead, so callers can tell *why* a request
+//! produced nothing.
+
+use std::fmt;
+
+/// Errors reported by the fallible G2P APIs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum G2PError {
+    /// The language code is not recognized and no custom engine is registered for it
+    UnsupportedLanguage(String),
+    /// The language is known, but the cargo feature providing it was not compiled in
+    FeatureNotEnabled {
+        language: String,
+        feature: &'static str,
+    },
+    /// A pronunciation dictionary could not be loaded
+    DictionaryLoad(String),
+    /// A phoneme has no token ID in the vocabulary
+    UnmappablePhoneme {
+        phoneme: char,
+        /// Character offset of the phoneme in the phoneme string
+        position: usize,
+    },
+    /// The input produces more tokens than the model accepts
+    InputTooLong { tokens: usize, max: usize },
+    /// The input itself is invalid (null pointer, invalid UTF-8, ...)
+    InvalidInput(String),
+    /// A model vocabulary could not be loaded
+    InvalidVocabulary(String),
+    /// The language's dictionaries are loaded at runtime and have not been loaded yet
+    DictionaryNotLoaded(String),
+}
+
+impl G2PError {
+    /// Stable numeric code used by the C and JNI layers (0 means success)
+    pub fn code(&self) -> i32 {
+        match self {
+            G2PError::UnsupportedLanguage(_) => 1,
+            G2PError::FeatureNotEnabled { .. } => 2,
+            G2PError::DictionaryLoad(_) => 3,
+            G2PError::UnmappablePhoneme { .. } => 4,
+            G2PError::InputTooLong { .. } => 5,
+            G2PError::InvalidInput(_) => 6,
+            G2PError::InvalidVocabulary(_) => 7,
+            G2PError::DictionaryNotLoaded(_) => 8,
+        }
+    }
+}
+
+impl fmt::Display for G2PError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            G2PError::UnsupportedLanguage(lang) => write!(f, "unsupported language '{}'", lang),
+            G2PError::FeatureNotEnabled { language, feature } => write!(
+                f,
+                "language '{}' requires the '{}' feature, which was not compiled in",
+                language, feature
+            ),
+            G2PError::DictionaryLoad(msg) => write!(f, "failed to load dictionary: {}", msg),
+            G2PError::UnmappablePhoneme { phoneme, position } => write!(
+                f,
+                "phoneme {:?} (U+{:04X}) at position {} has no token ID",
+                phoneme, *phoneme as u32, position
+            ),
+            G2PError::InputTooLong { tokens, max } => {
+                write!(f, "input produces {} tokens, the maximum is {}", tokens, max)
+            }
+            G2PError::InvalidInput(msg) => write!(f, "invalid input: {}", msg),
+            G2PError::InvalidVocabulary(msg) => write!(f, "invalid vocabulary: {}", msg),
+            G2PError::DictionaryNotLoaded(lang) => {
+                write!(f, "no pronunciation dictionaries have been loaded for '{}' yet", lang)
+            }
+        }
+    }
+}
+
+impl std::error::Error for G2PError {}
+
+/// Result type for the fallible G2P APIs
+pub type Result<T> = std::result::Result<T, G2PError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let err = G2PError::FeatureNotEnabled {
+            language: "zh".to_string(),
+            feature: "chinese",
+        };
+        assert!(err.to_string().contains("'chinese'"));
+
+        let err = G2PError::UnmappablePhoneme { phoneme: '❓', position: 3 };
+        assert!(err.to_string().contains("U+2753"));
+    }
+
+    #[test]
+    fn test_codes_are_distinct() {
+        let errors = [
+            G2PError::UnsupportedLanguage(String::new()),
+            G2PError::FeatureNotEnabled { language: String::new(), feature: "" },
+            G2PError::DictionaryLoad(String::new()),
+            G2PError::UnmappablePhoneme { phoneme: 'x', position: 0 },
+            G2PError::InputTooLong { tokens: 0, max: 0 },
+            G2PError::InvalidInput(String::new()),
+            G2PError::InvalidVocabulary(String::new()),
+            G2PError::DictionaryNotLoaded(String::new()),
+        ];
+        let codes: std::collections::HashSet<i32> = errors.iter().map(|e| e.code()).collect();
+        assert_eq!(codes.len(), errors.len());
+        assert!(!codes.contains(&0));
+    }
+}
@@ -5,6 +5,9 @@
 
 pub mod normalizer;
 
+use crate::alignment::{self, Alignment};
+use crate::engine::{EngineCapabilities, G2PEngine};
+use crate::markup;
 use crate::tokenizer;
 
 /// Spanish G2P processor
@@ -18,6 +21,10 @@ impl SpanishG2P {
 
     /// Convert Spanish text to phoneme string (IPA-based)
     pub fn text_to_phonemes(&self, text: &str) -> String {
+        if markup::has_markup(text) {
+            return markup::phonemize(text, |plain| self.text_to_phonemes(plain));
+        }
+
         // Step 1: Normalize text (numbers, dates, currency)
         let normalized = normalizer::normalize(text);
 
@@ -55,6 +62,34 @@ impl Default for SpanishG2P {
     }
 }
 
+impl G2PEngine for SpanishG2P {
+    fn language_code(&self) -> &str {
+        "es"
+    }
+
+    fn phonemize(&self, text: &str) -> String {
+        self.text_to_phonemes(text)
+    }
+
+    fn phonemize_aligned(&self, text: &str) -> Option<Alignment> {
+        Some(alignment::align_words(text, normalizer::normalize, |word| {
+            if is_punctuation(word) {
+                word.to_string()
+            } else {
+                word_to_phonemes(word)
+            }
+        }))
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            normalization: true,
+            stress: true,
+            ..Default::default()
+        }
+    }
+}
+
 /// Convert Spanish text to token IDs (convenience function)
 pub fn text_to_tokens(text: &str) -> Vec<i64> {
     let g2p = SpanishG2P::new();
@@ -268,6 +303,17 @@ fn find_stress_position(chars: &[char]) -> usize {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_inline_markup() {
+        let g2p = SpanishG2P::new();
+        let hola = g2p.text_to_phonemes("hola");
+        assert_eq!(
+            g2p.text_to_phonemes("hola [Kokoro](/kokˈoɾo/), hola"),
+            format!("{} kokˈoɾo, {}", hola, hola)
+        );
+        assert!(!g2p.text_to_phonemes("[hola](-2)").contains('ˈ'));
+    }
+
     #[test]
     fn test_basic_conversion() {
         let g2p = SpanishG2P::new();
@@ -3,13 +3,22 @@
 //! Main conversion pipeline that transforms English text into phoneme sequences
 //! suitable for the Kokoro TTS model.
 
-use crate::lexicon::LexiconRef;
-use crate::preprocessor::{preprocess, tokenize};
+use crate::alignment::{self, Alignment, AlignmentBuilder};
+use crate::engine::{EngineCapabilities, G2PEngine};
+use crate::error::G2PError;
+use crate::letter_to_sound;
+use crate::markup;
+use crate::lexicon::{
+    apply_stress, Lexicon, LexiconRef, UserLexicon, GOLD_RATING, GUESS_RATING, SILVER_RATING, USER_RATING,
+};
+use crate::preprocessor::{preprocess_dialect, tokenize, Token};
+use crate::tagger;
 use crate::tokenizer::phonemes_to_tokens;
 pub use crate::tokenizer::{MAX_TOKENS, PAD_TOKEN};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::collections::HashSet;
+use std::ops::Range;
 
 /// Characters that should be passed through as phonemes
 static PASSTHROUGH_PUNCT: Lazy<HashSet<char>> = Lazy::new(|| {
@@ -17,10 +26,82 @@ static PASSTHROUGH_PUNCT: Lazy<HashSet<char>> = Lazy::new(|| {
     chars.chars().collect()
 });
 
+/// Contractions with fixed pronunciations: (word, American, British)
+const CONTRACTIONS: &[(&str, &str, &str)] = &[
+    ("can't", "kˈænt", "kˈɑːnt"),
+    ("won't", "wˈOnt", "wˈQnt"),
+    ("don't", "dˈOnt", "dˈQnt"),
+    ("didn't", "dˈɪdᵊnt", "dˈɪdᵊnt"),
+    ("doesn't", "dˈʌzᵊnt", "dˈʌzᵊnt"),
+    ("couldn't", "kˈʊdᵊnt", "kˈʊdᵊnt"),
+    ("wouldn't", "wˈʊdᵊnt", "wˈʊdᵊnt"),
+    ("shouldn't", "ʃˈʊdᵊnt", "ʃˈʊdᵊnt"),
+    ("isn't", "ˈɪzᵊnt", "ˈɪzᵊnt"),
+    ("aren't", "ˈɑɹᵊnt", "ˈɑːnt"),
+    ("wasn't", "wˈɑzᵊnt", "wˈɒzᵊnt"),
+    ("weren't", "wˈɜɹᵊnt", "wˈɜːnt"),
+    ("haven't", "hˈævᵊnt", "hˈavᵊnt"),
+    ("hasn't", "hˈæzᵊnt", "hˈazᵊnt"),
+    ("hadn't", "hˈædᵊnt", "hˈadᵊnt"),
+    ("I'm", "ˌIm", "ˌIm"),
+    ("I've", "ˌIv", "ˌIv"),
+    ("I'll", "ˌIl", "ˌIl"),
+    ("I'd", "ˌId", "ˌId"),
+    ("you're", "jˈʊɹ", "jˈɔː"),
+    ("you've", "jˈuv", "jˈuːv"),
+    ("you'll", "jˈul", "jˈuːl"),
+    ("you'd", "jˈud", "jˈuːd"),
+    ("he's", "hˈiz", "hˈiːz"),
+    ("she's", "ʃˈiz", "ʃˈiːz"),
+    ("it's", "ˈɪts", "ˈɪts"),
+    ("we're", "wˈɪɹ", "wˈɪə"),
+    ("we've", "wˈiv", "wˈiːv"),
+    ("we'll", "wˈil", "wˈiːl"),
+    ("we'd", "wˈid", "wˈiːd"),
+    ("they're", "ðˈɛɹ", "ðˈɛː"),
+    ("they've", "ðˈAv", "ðˈAv"),
+    ("they'll", "ðˈAl", "ðˈAl"),
+    ("they'd", "ðˈAd", "ðˈAd"),
+    ("that's", "ðˈæts", "ðˈats"),
+    ("what's", "wˈʌts", "wˈɒts"),
+    ("there's", "ðˈɛɹz", "ðˈɛːz"),
+    ("here's", "hˈɪɹz", "hˈɪəz"),
+    ("let's", "lˈɛts", "lˈɛts"),
+];
+
+/// Letter names for spelling out acronyms: (American, British)
+const LETTERS: [(&str, &str); 26] = [
+    ("ˈA", "ˈA"),
+    ("bˈi", "bˈiː"),
+    ("sˈi", "sˈiː"),
+    ("dˈi", "dˈiː"),
+    ("ˈi", "ˈiː"),
+    ("ˈɛf", "ˈɛf"),
+    ("ʤˈi", "ʤˈiː"),
+    ("ˈAʧ", "ˈAʧ"),
+    ("ˈI", "ˈI"),
+    ("ʤˈA", "ʤˈA"),
+    ("kˈA", "kˈA"),
+    ("ˈɛl", "ˈɛl"),
+    ("ˈɛm", "ˈɛm"),
+    ("ˈɛn", "ˈɛn"),
+    ("ˈO", "ˈQ"),
+    ("pˈi", "pˈiː"),
+    ("kjˈu", "kjˈuː"),
+    ("ˈɑɹ", "ˈɑː"),
+    ("ˈɛs", "ˈɛs"),
+    ("tˈi", "tˈiː"),
+    ("jˈu", "jˈuː"),
+    ("vˈi", "vˈiː"),
+    ("dˈʌbᵊljˌu", "dˈʌbᵊljˌuː"),
+    ("ˈɛks", "ˈɛks"),
+    ("wˈI", "wˈI"),
+    ("zˈi", "zˈɛd"),
+];
+
 /// G2P Engine for converting text to phonemes
 pub struct G2P {
     lexicon: LexiconRef,
-    #[allow(dead_code)] // Reserved for future dialect-specific features
     british: bool,
     unk_marker: String,
 }
@@ -38,12 +119,35 @@ impl G2P {
     /// * `british` - Use British English pronunciation if true
     pub fn new(british: bool) -> Self {
         Self {
-            lexicon: crate::lexicon::Lexicon::new_static(british),
+            lexicon: Lexicon::new_static(british),
             british,
             unk_marker: "❓".to_string(),
         }
     }
 
+    /// Create a new G2P engine, failing if the dictionaries could not be loaded
+    pub fn try_new(british: bool) -> Result<Self, G2PError> {
+        Ok(Self {
+            lexicon: Lexicon::try_new_static(british)?,
+            british,
+            unk_marker: "❓".to_string(),
+        })
+    }
+
+    /// Create a new G2P engine that consults a user lexicon before the built-in dictionaries
+    pub fn with_user_lexicon(british: bool, user: UserLexicon) -> Self {
+        let mut g2p = Self::new(british);
+        g2p.lexicon.set_user_lexicon(user);
+        g2p
+    }
+
+    /// Get the user lexicon
+    ///
+    /// Words added to it take effect immediately, with priority over the gold dictionary.
+    pub fn user_lexicon(&self) -> &UserLexicon {
+        self.lexicon.user_lexicon()
+    }
+
     /// Set the unknown word marker
     pub fn set_unk_marker(&mut self, marker: &str) {
         self.unk_marker = marker.to_string();
@@ -56,16 +160,93 @@ impl G2P {
 
     /// Convert text to phoneme string
     pub fn text_to_phonemes(&self, text: &str) -> String {
-        // Preprocess the text (numbers, abbreviations, etc.)
-        let preprocessed = preprocess(text);
+        // Preprocess the text (numbers, abbreviations, etc.), leaving inline markup intact
+        let preprocessed = markup::map_plain(text, |plain| preprocess_dialect(plain, self.british));
 
-        // Tokenize into words and punctuation
-        let tokens = tokenize(&preprocessed);
+        // Tokenize into words and punctuation, and tag parts of speech
+        let mut tokens = tokenize(&preprocessed);
+        tagger::tag(&mut tokens);
 
         // Convert each token to phonemes
         let mut result = String::new();
         let mut prev_was_word = false;
+        self.append_tokens(&tokens, &mut result, &mut prev_was_word);
 
+        // Clean up the result
+        self.clean_phonemes(&result)
+    }
+
+    /// Convert text to phonemes, recording the phonemes produced by each source word
+    ///
+    /// The whole text is preprocessed and tagged at once, as in
+    /// [`G2P::text_to_phonemes`], so the phonemes are the same; the resulting
+    /// tokens are then assigned back to the source words they came from.
+    pub fn text_to_alignment(&self, text: &str) -> Alignment {
+        let preprocessed = markup::map_plain(text, |plain| preprocess_dialect(plain, self.british));
+        let mut tokens = tokenize(&preprocessed);
+        tagger::tag(&mut tokens);
+
+        let words: Vec<_> = alignment::source_words(text).collect();
+        let owners = self.assign_tokens(&words, &tokens);
+
+        let mut builder = AlignmentBuilder::new();
+        let mut prev_was_word = false;
+        let mut next = 0;
+        for (i, (source, word)) in words.into_iter().enumerate() {
+            let end = next + owners[next..].iter().take_while(|&&owner| owner == i).count();
+            let own = &tokens[next..end];
+            let normalized: String = own.iter().map(|t| format!("{}{}", t.text, t.whitespace)).collect();
+
+            let start = builder.phonemes.len();
+            self.append_tokens(own, &mut builder.phonemes, &mut prev_was_word);
+            builder.push_word(source, word, normalized.trim().to_string(), start);
+            next = end;
+        }
+
+        builder.collapse_whitespace();
+        builder.finish()
+    }
+
+    /// Index of the source word each token came from, in order
+    ///
+    /// A token belongs to the nearest word ahead whose own tokens or whose
+    /// expansion on its own contain it; other tokens (context-dependent
+    /// expansions such as "fifteen hundred" for 1500) stay with the current
+    /// word, or move on to the next word if that one is expanded.
+    fn assign_tokens(&self, words: &[(Range<usize>, &str)], tokens: &[Token]) -> Vec<usize> {
+        const LOOKAHEAD: usize = 4;
+        if words.is_empty() {
+            return Vec::new();
+        }
+
+        let lowercase = |tokens: Vec<Token>| -> Vec<String> { tokens.into_iter().map(|t| t.text.to_lowercase()).collect() };
+        let forms: Vec<(Vec<String>, bool)> = words
+            .iter()
+            .map(|(_, word)| {
+                let own = lowercase(tokenize(&markup::strip(word)));
+                let expanded = lowercase(tokenize(&markup::map_plain(word, |plain| preprocess_dialect(plain, self.british))));
+                let changed = own != expanded;
+                (own.into_iter().chain(expanded).collect(), changed)
+            })
+            .collect();
+
+        let mut owners: Vec<usize> = Vec::with_capacity(tokens.len());
+        let mut current = 0;
+        for token in tokens {
+            let text = token.text.to_lowercase();
+            let last = (current + LOOKAHEAD).min(words.len().saturating_sub(1));
+            if let Some(i) = (current..=last).find(|&i| forms[i].0.contains(&text)) {
+                current = i;
+            } else if owners.last() == Some(&current) && !forms[current].1 && forms.get(current + 1).is_some_and(|f| f.1) {
+                current += 1;
+            }
+            owners.push(current);
+        }
+        owners
+    }
+
+    /// Append the phonemes for a token stream to `result`
+    fn append_tokens(&self, tokens: &[Token], result: &mut String, prev_was_word: &mut bool) {
         for token in tokens {
             if token.is_punct {
                 // Handle punctuation
@@ -79,126 +260,107 @@ impl G2P {
                     };
                     result.push(normalized);
                 }
-                prev_was_word = false;
+                *prev_was_word = false;
             } else {
                 // Add space between words
-                if prev_was_word && !result.is_empty() && !result.ends_with(' ') {
+                if *prev_was_word && !result.is_empty() && !result.ends_with(' ') {
                     result.push(' ');
                 }
 
-                // Convert word to phonemes
-                let phonemes = self.word_to_phonemes(&token.text, None);
+                // Convert word to phonemes, honoring inline markup
+                let phonemes = match &token.phonemes {
+                    Some(phonemes) => phonemes.clone(),
+                    None => {
+                        let tag = token.tag.as_deref();
+                        let phonemes = if token.text.contains(char::is_whitespace) {
+                            token
+                                .text
+                                .split_whitespace()
+                                .map(|word| self.word_to_phonemes(word, tag))
+                                .collect::<Vec<_>>()
+                                .join(" ")
+                        } else {
+                            self.word_to_phonemes(&token.text, tag)
+                        };
+                        apply_stress(&phonemes, token.stress)
+                    }
+                };
                 result.push_str(&phonemes);
-                prev_was_word = true;
-            }
-
-            // Handle whitespace
-            if !token.whitespace.is_empty() && prev_was_word {
-                // Whitespace after word handled by next iteration
+                *prev_was_word = true;
             }
         }
-
-        // Clean up the result
-        self.clean_phonemes(&result)
     }
 
     /// Convert a single word to phonemes
     pub fn word_to_phonemes(&self, word: &str, tag: Option<&str>) -> String {
+        self.word_to_phonemes_rated(word, tag).0
+    }
+
+    /// Convert a single word to phonemes, also returning how reliable the result is
+    ///
+    /// The rating is one of the `*_RATING` constants in [`crate::lexicon`];
+    /// `None` means the word could not be converted and the unknown marker was used.
+    pub fn word_to_phonemes_rated(&self, word: &str, tag: Option<&str>) -> (String, Option<u8>) {
         // Skip empty words
         if word.is_empty() {
-            return String::new();
+            return (String::new(), None);
+        }
+
+        // User lexicon overrides everything else
+        if let Some(phonemes) = self.lexicon.user_lexicon().lookup(word, tag) {
+            return (phonemes, Some(USER_RATING));
         }
 
         // Handle contractions
         if let Some(phonemes) = self.handle_contraction(word) {
-            return phonemes;
+            return (phonemes, Some(GOLD_RATING));
         }
 
         // Try dictionary lookup
-        if let Some((phonemes, _rating)) = self.lexicon.get_word(word, tag) {
-            return phonemes;
+        if let Some((phonemes, rating)) = self.lexicon.get_word(word, tag) {
+            return (phonemes, Some(rating));
         }
 
         // Try lowercase
         let lower = word.to_lowercase();
         if lower != word {
-            if let Some((phonemes, _rating)) = self.lexicon.get_word(&lower, tag) {
-                return phonemes;
+            if let Some((phonemes, rating)) = self.lexicon.get_word(&lower, tag) {
+                return (phonemes, Some(rating));
             }
         }
 
         // Handle compound words (split by hyphen or camelCase)
         if word.contains('-') || has_internal_caps(word) {
-            if let Some(phonemes) = self.handle_compound(word, tag) {
-                return phonemes;
+            if let Some((phonemes, rating)) = self.handle_compound(word, tag) {
+                return (phonemes, Some(rating));
             }
         }
 
         // Handle all-caps acronyms
         if word.len() <= 5 && word.chars().all(|c| c.is_ascii_uppercase()) {
-            return self.spell_out(word);
+            return (self.spell_out(word), Some(SILVER_RATING));
         }
 
         // Fallback: try letter-by-letter for short words
         if word.len() <= 3 {
-            return self.spell_out(word);
+            return (self.spell_out(word), Some(SILVER_RATING));
+        }
+
+        // Guess the pronunciation from the spelling
+        if let Some(phonemes) = letter_to_sound::word_to_phonemes(word, self.british) {
+            log::debug!("Guessed pronunciation for unknown word {}: {}", word, phonemes);
+            return (phonemes, Some(GUESS_RATING));
         }
 
         // Ultimate fallback: use unknown marker
         log::warn!("Unknown word: {}", word);
-        self.unk_marker.clone()
+        (self.unk_marker.clone(), None)
     }
 
     /// Handle contractions like "don't", "I'm", etc.
     fn handle_contraction(&self, word: &str) -> Option<String> {
-        // Common contractions
-        let contractions: &[(&str, &str)] = &[
-            ("can't", "kˈænt"),
-            ("won't", "wˈOnt"),
-            ("don't", "dˈOnt"),
-            ("didn't", "dˈɪdᵊnt"),
-            ("doesn't", "dˈʌzᵊnt"),
-            ("couldn't", "kˈʊdᵊnt"),
-            ("wouldn't", "wˈʊdᵊnt"),
-            ("shouldn't", "ʃˈʊdᵊnt"),
-            ("isn't", "ˈɪzᵊnt"),
-            ("aren't", "ˈɑɹᵊnt"),
-            ("wasn't", "wˈɑzᵊnt"),
-            ("weren't", "wˈɜɹᵊnt"),
-            ("haven't", "hˈævᵊnt"),
-            ("hasn't", "hˈæzᵊnt"),
-            ("hadn't", "hˈædᵊnt"),
-            ("I'm", "ˌIm"),
-            ("I've", "ˌIv"),
-            ("I'll", "ˌIl"),
-            ("I'd", "ˌId"),
-            ("you're", "jˈʊɹ"),
-            ("you've", "jˈuv"),
-            ("you'll", "jˈul"),
-            ("you'd", "jˈud"),
-            ("he's", "hˈiz"),
-            ("she's", "ʃˈiz"),
-            ("it's", "ˈɪts"),
-            ("we're", "wˈɪɹ"),
-            ("we've", "wˈiv"),
-            ("we'll", "wˈil"),
-            ("we'd", "wˈid"),
-            ("they're", "ðˈɛɹ"),
-            ("they've", "ðˈAv"),
-            ("they'll", "ðˈAl"),
-            ("they'd", "ðˈAd"),
-            ("that's", "ðˈæts"),
-            ("what's", "wˈʌts"),
-            ("there's", "ðˈɛɹz"),
-            ("here's", "hˈɪɹz"),
-            ("let's", "lˈɛts"),
-        ];
-
-        let word_lower = word.to_lowercase();
-        for (contraction, phonemes) in contractions {
-            if &word_lower == *contraction {
-                return Some(phonemes.to_string());
-            }
+        if let Some(&(_, american, british)) = CONTRACTIONS.iter().find(|(c, _, _)| c.eq_ignore_ascii_case(word)) {
+            return Some(if self.british { british } else { american }.to_string());
         }
 
         // Handle 's, 're, 've, 'll, 'd suffixes dynamically
@@ -207,7 +369,11 @@ impl G2P {
             if let Some((base_phonemes, _)) = self.lexicon.get_word(base, None) {
                 let last = base_phonemes.chars().last().unwrap_or(' ');
                 let suffix = if "szʃʒʧʤ".contains(last) {
-                    "ᵻz"
+                    if self.british {
+                        "ɪz"
+                    } else {
+                        "ᵻz"
+                    }
                 } else if "ptkfθ".contains(last) {
                     "s"
                 } else {
@@ -220,32 +386,32 @@ impl G2P {
         None
     }
 
-    /// Handle compound words
-    fn handle_compound(&self, word: &str, tag: Option<&str>) -> Option<String> {
+    /// Handle compound words, rated by their least reliable part
+    fn handle_compound(&self, word: &str, tag: Option<&str>) -> Option<(String, u8)> {
+        let convert = |parts: &[&str]| -> Option<(String, u8)> {
+            let mut phonemes = Vec::with_capacity(parts.len());
+            let mut rating = USER_RATING;
+            for part in parts.iter().filter(|p| !p.is_empty()) {
+                let (part_phonemes, part_rating) = self.word_to_phonemes_rated(part, tag);
+                rating = rating.min(part_rating?);
+                phonemes.push(part_phonemes);
+            }
+            (!phonemes.is_empty()).then(|| (phonemes.join(" "), rating))
+        };
+
         // Split by hyphens
         if word.contains('-') {
             let parts: Vec<&str> = word.split('-').collect();
-            let phoneme_parts: Vec<String> = parts
-                .iter()
-                .map(|p| self.word_to_phonemes(p, tag))
-                .collect();
-
-            if phoneme_parts.iter().all(|p| !p.contains(&self.unk_marker)) {
-                return Some(phoneme_parts.join(" "));
+            if let Some(result) = convert(&parts) {
+                return Some(result);
             }
         }
 
         // Split by camelCase
         let parts = split_camel_case(word);
         if parts.len() > 1 {
-            let phoneme_parts: Vec<String> = parts
-                .iter()
-                .map(|p| self.word_to_phonemes(p, tag))
-                .collect();
-
-            if phoneme_parts.iter().all(|p| !p.contains(&self.unk_marker)) {
-                return Some(phoneme_parts.join(" "));
-            }
+            let parts: Vec<&str> = parts.iter().map(|p| p.as_str()).collect();
+            return convert(&parts);
         }
 
         None
@@ -255,35 +421,13 @@ impl G2P {
     fn spell_out(&self, word: &str) -> String {
         let letter_phonemes: Vec<&str> = word
             .chars()
-            .filter_map(|c| {
-                match c.to_ascii_uppercase() {
-                    'A' => Some("ˈA"),
-                    'B' => Some("bˈi"),
-                    'C' => Some("sˈi"),
-                    'D' => Some("dˈi"),
-                    'E' => Some("ˈi"),
-                    'F' => Some("ˈɛf"),
-                    'G' => Some("ʤˈi"),
-                    'H' => Some("ˈAʧ"),
-                    'I' => Some("ˈI"),
-                    'J' => Some("ʤˈA"),
-                    'K' => Some("kˈA"),
-                    'L' => Some("ˈɛl"),
-                    'M' => Some("ˈɛm"),
-                    'N' => Some("ˈɛn"),
-                    'O' => Some("ˈO"),
-                    'P' => Some("pˈi"),
-                    'Q' => Some("kjˈu"),
-                    'R' => Some("ˈɑɹ"),
-                    'S' => Some("ˈɛs"),
-                    'T' => Some("tˈi"),
-                    'U' => Some("jˈu"),
-                    'V' => Some("vˈi"),
-                    'W' => Some("dˈʌbᵊljˌu"),
-                    'X' => Some("ˈɛks"),
-                    'Y' => Some("wˈI"),
-                    'Z' => Some("zˈi"),
-                    _ => None,
+            .filter(char::is_ascii_alphabetic)
+            .map(|c| {
+                let (american, british) = LETTERS[(c.to_ascii_uppercase() as u8 - b'A') as usize];
+                if self.british {
+                    british
+                } else {
+                    american
                 }
             })
             .collect();
@@ -306,6 +450,43 @@ impl G2P {
     }
 }
 
+impl G2PEngine for G2P {
+    fn language_code(&self) -> &str {
+        if self.british {
+            "en-gb"
+        } else {
+            "en-us"
+        }
+    }
+
+    fn phonemize(&self, text: &str) -> String {
+        self.text_to_phonemes(text)
+    }
+
+    fn try_phonemize(&self, text: &str) -> Result<String, G2PError> {
+        Lexicon::check_loaded(self.british)?;
+        Ok(self.text_to_phonemes(text))
+    }
+
+    fn phonemize_aligned(&self, text: &str) -> Option<Alignment> {
+        Some(self.text_to_alignment(text))
+    }
+
+    fn try_phonemize_aligned(&self, text: &str) -> Result<Option<Alignment>, G2PError> {
+        Lexicon::check_loaded(self.british)?;
+        Ok(self.phonemize_aligned(text))
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            dictionary: true,
+            normalization: true,
+            stress: true,
+            tones: false,
+        }
+    }
+}
+
 /// Check if word has internal capital letters (camelCase)
 fn has_internal_caps(word: &str) -> bool {
     let chars: Vec<char> = word.chars().collect();
@@ -363,6 +544,29 @@ pub fn text_to_phoneme_string(text: &str, language: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_user_lexicon() {
+        let g2p = G2P::new(false);
+        assert!(g2p.text_to_phonemes("Zxqwvk").contains('❓'));
+
+        g2p.user_lexicon().insert("Zxqwvk", "zˈɪkswɪv");
+        assert_eq!(g2p.text_to_phonemes("zxqwvk"), "zˈɪkswɪv");
+        assert_eq!(g2p.word_to_phonemes_rated("zxqwvk", None).1, Some(USER_RATING));
+    }
+
+    #[test]
+    fn test_letter_to_sound_fallback() {
+        crate::lexicon::install_test_dictionaries();
+        let g2p = G2P::new(false);
+        let (phonemes, rating) = g2p.word_to_phonemes_rated("blorfle", None);
+        assert!(!phonemes.contains('❓'));
+        assert_eq!(rating, Some(GUESS_RATING));
+
+        let (_, rating) = g2p.word_to_phonemes_rated("hello", None);
+        assert!(rating.unwrap() >= SILVER_RATING);
+        assert_eq!(g2p.word_to_phonemes_rated("bcdfg", None), ("❓".to_string(), None));
+    }
+
     #[test]
     fn test_text_to_tokens() {
         let tokens = text_to_tokens("Hello, world!", "en");
@@ -379,6 +583,57 @@ mod tests {
         assert!(!phonemes.contains("❓"));
     }
 
+    #[test]
+    fn test_inline_markup() {
+        crate::lexicon::install_test_dictionaries();
+        let g2p = G2P::new(false);
+        assert_eq!(g2p.text_to_phonemes("[Kokoro](/kˈOkəɹO/) says hi"), format!("kˈOkəɹO {}", g2p.text_to_phonemes("says hi")));
+        assert_eq!(g2p.text_to_phonemes("[hello](-1)"), "həlˌO");
+        assert_eq!(g2p.text_to_phonemes("[hello](-2)"), "həlO");
+
+        let alignment = g2p.text_to_alignment("I like [New York](/nˈu jˈɔɹk/).");
+        let segment = alignment.segments.last().unwrap();
+        assert_eq!(segment.normalized, "New York.");
+        assert_eq!(segment.phonemes, "nˈu jˈɔɹk.");
+    }
+
+    #[test]
+    fn test_heteronyms() {
+        crate::lexicon::install_test_dictionaries();
+        let g2p = G2P::new(false);
+        let phonemes = g2p.text_to_phonemes("I will record the record");
+        assert!(phonemes.starts_with("ˈI wɪl ɹəkˈɔɹd "));
+        assert!(phonemes.ends_with(" ɹˈɛkəɹd"));
+        assert!(g2p.text_to_phonemes("I have read it").contains("ɹˈɛd"));
+        assert!(g2p.text_to_phonemes("I will read it").contains("ɹˈid"));
+
+        let alignment = g2p.text_to_alignment("They object to the object");
+        assert_eq!(alignment.segments[1].phonemes, "əbʤˈɛkt");
+        assert_eq!(alignment.segments[4].phonemes, "ˈɑbʤəkt");
+    }
+
+    #[test]
+    fn test_alignment_matches_plain_output() {
+        let g2p = G2P::new(false);
+        for text in [
+            "In 1995 there were 1500 people.",
+            "I paid $5 million for 3/4 of it on 1/1/2000.",
+            "Call 555-1234 now,   or read pages 5 - 10 of v2.0.",
+            "I like [New York](/nˈu jˈɔɹk/) in 2019.",
+        ] {
+            let alignment = g2p.text_to_alignment(text);
+            assert_eq!(alignment.phonemes, g2p.text_to_phonemes(text), "{}", text);
+            for segment in &alignment.segments {
+                assert!(alignment.phonemes.contains(&segment.phonemes));
+            }
+        }
+
+        let alignment = g2p.text_to_alignment("In 1995 there were 1500 people.");
+        let words: Vec<&str> = alignment.segments.iter().map(|s| s.normalized.as_str()).collect();
+        assert_eq!(words[4], "one thousand five hundred");
+        assert_eq!(words[5], "people.");
+    }
+
     #[test]
     fn test_contractions() {
         let g2p = G2P::new(false);
@@ -414,6 +669,24 @@ mod tests {
         assert!(!gb.is_empty());
     }
 
+    #[test]
+    fn test_british_tables() {
+        let g2p_us = G2P::new(false);
+        let g2p_gb = G2P::new(true);
+
+        assert_eq!(g2p_us.word_to_phonemes("don't", None), "dˈOnt");
+        assert_eq!(g2p_gb.word_to_phonemes("don't", None), "dˈQnt");
+        assert_eq!(g2p_gb.word_to_phonemes("aren't", None), "ˈɑːnt");
+        assert_eq!(g2p_us.word_to_phonemes("XYZ", None), "ˈɛks wˈI zˈi");
+        assert_eq!(g2p_gb.word_to_phonemes("XYZ", None), "ˈɛks wˈI zˈɛd");
+        assert_eq!(g2p_gb.spell_out("OR"), "ˈQ ˈɑː");
+
+        let gb = g2p_gb.text_to_phonemes("at 9:15");
+        assert_eq!(gb, g2p_gb.text_to_phonemes("at quarter past nine"));
+        assert_eq!(g2p_gb.text_to_phonemes("105"), g2p_gb.text_to_phonemes("one hundred and five"));
+        assert!(!gb.contains('ɹ'));
+    }
+
     #[test]
     fn test_max_length() {
         let long_text = "word ".repeat(200);
@@ -6,6 +6,9 @@
 
 pub mod normalizer;
 
+use crate::alignment::{self, Alignment};
+use crate::engine::{EngineCapabilities, G2PEngine};
+use crate::markup;
 use crate::tokenizer;
 
 /// Indonesian G2P processor
@@ -19,6 +22,10 @@ impl IndonesianG2P {
 
     /// Convert Indonesian text to phoneme string (IPA-based)
     pub fn text_to_phonemes(&self, text: &str) -> String {
+        if markup::has_markup(text) {
+            return markup::phonemize(text, |plain| self.text_to_phonemes(plain));
+        }
+
         // Step 1: Normalize text (numbers, dates, currency)
         let normalized = normalizer::normalize(text);
 
@@ -55,6 +62,34 @@ impl Default for IndonesianG2P {
     }
 }
 
+impl G2PEngine for IndonesianG2P {
+    fn language_code(&self) -> &str {
+        "id"
+    }
+
+    fn phonemize(&self, text: &str) -> String {
+        self.text_to_phonemes(text)
+    }
+
+    fn phonemize_aligned(&self, text: &str) -> Option<Alignment> {
+        Some(alignment::align_words(text, normalizer::normalize, |word| {
+            if is_punctuation(word) {
+                word.to_string()
+            } else {
+                word_to_phonemes(word)
+            }
+        }))
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            normalization: true,
+            stress: true,
+            ..Default::default()
+        }
+    }
+}
+
 /// Convert Indonesian text to token IDs (convenience function)
 pub fn text_to_tokens(text: &str) -> Vec<i64> {
     let g2p = IndonesianG2P::new();
@@ -12,6 +12,9 @@
 
 pub mod normalizer;
 
+use crate::alignment::{self, Alignment};
+use crate::engine::{EngineCapabilities, G2PEngine};
+use crate::markup;
 use crate::tokenizer;
 
 /// Korean G2P processor
@@ -23,6 +26,10 @@ impl KoreanG2P {
     }
 
     pub fn text_to_phonemes(&self, text: &str) -> String {
+        if markup::has_markup(text) {
+            return markup::phonemize(text, |plain| self.text_to_phonemes(plain));
+        }
+
         let normalized = normalizer::normalize(text);
         let mut result = String::new();
         let words: Vec<&str> = normalized.split_whitespace().collect();
@@ -42,7 +49,7 @@ impl KoreanG2P {
     }
 
     pub fn text_to_tokens(&self, text: &str) -> Vec<i64> {
-        let phonemes = self.text_to_phonemes(text);
+        let phonemes = tokenizer::apply_fallbacks("ko", &self.text_to_phonemes(text));
         tokenizer::phonemes_to_tokens(&phonemes)
     }
 }
@@ -53,6 +60,33 @@ impl Default for KoreanG2P {
     }
 }
 
+impl G2PEngine for KoreanG2P {
+    fn language_code(&self) -> &str {
+        "ko"
+    }
+
+    fn phonemize(&self, text: &str) -> String {
+        self.text_to_phonemes(text)
+    }
+
+    fn phonemize_aligned(&self, text: &str) -> Option<Alignment> {
+        Some(alignment::align_words(text, normalizer::normalize, |word| {
+            if is_punctuation(word) {
+                word.to_string()
+            } else {
+                word_to_phonemes(word)
+            }
+        }))
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            normalization: true,
+            ..Default::default()
+        }
+    }
+}
+
 pub fn text_to_tokens(text: &str) -> Vec<i64> {
     KoreanG2P::new().text_to_tokens(text)
 }
@@ -0,0 +1,287 @@
+//! Automatic language identification for the `"auto"` language code
+//!
+//! Detection works in two stages. The share of letters in each script picks
+//! Korean (Hangul), Japanese (kana, with any Han characters) and Chinese (Han).
+//! Latin-script text is then classified from letters that only some
+//! languages use (Vietnamese tone marks, Turkish ı/ş/ğ, German ß/ä, Spanish ñ,
+//! Portuguese ã/õ, ...) together with a compact embedded profile of the most
+//! frequent character trigrams of each language.
+//!
+//! Only languages whose engines were compiled in are considered, see
+//! [`available_languages`].
+
+use crate::pipeline::{self, Language};
+use crate::script::{self, Script};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+
+/// A detected language and how confident the detection is
+#[derive(Debug, Clone, PartialEq)]
+pub struct Detection {
+    /// The detected language
+    pub language: Language,
+    /// Confidence from 0.0 (a guess) to 1.0
+    pub confidence: f32,
+}
+
+/// Built-in languages that can be detected, in order of preference on ties
+const DETECTABLE: &[Language] = &[
+    Language::EnglishUS,
+    Language::Spanish,
+    Language::Portuguese,
+    Language::Italian,
+    Language::German,
+    Language::Indonesian,
+    Language::Turkish,
+    Language::Vietnamese,
+    Language::Chinese,
+    Language::Japanese,
+    Language::Korean,
+];
+
+/// The most frequent character trigrams of each Latin-script language (by
+/// feature name), most frequent first. `_` marks a word boundary.
+const TRIGRAM_PROFILES: &[(&str, &str)] = &[
+    ("english", "_th the he_ _an and nd_ ing ng_ _to _of of_ to_ ed_ _in in_ is_ _a_ ion er_ _is re_ on_ \
+        es_ tio hat tha _wh at_ ent _be her for _fo or_ you _yo ou_ _ha _it it_ ati ter was _wa \
+        his _hi ere ly_ all wit ith th_ ve_ _co _we _so _i_ _he _my my_ _me me_ _no _do ow_ _ho \
+        how _wo wor _lo ove ood _go day ay_ ell _ar are hav ave _ca _wi _ne _sh thi _on one ght \
+        igh _ma _se _sa ll_ rld"),
+    ("spanish", "_de de_ _la la_ que _qu ue_ _el el_ os_ _en en_ as_ es_ _lo ión ón_ _co ent _es nte ado \
+        do_ _se _un una _pa par ara _po por con _y_ los las del ció est _ha _me ero mos ien _mu \
+        muy _su _al nto ida _ho ola _gr aci cia ias ías _bu bue uen nos _dí día señ _ti qué ué_ \
+        _pe _si sí_ _mi _tu _yo yo_ _te ndo _ca _ma _ve tá_ _ah ora ier ito"),
+    ("portuguese", "_de de_ _qu que ue_ _o_ _a_ os_ _do do_ _da da_ ão_ ção _co com _em em_ _pa par ara _um \
+        uma ma_ _no não nte ent as_ es_ _se _pr ado men _na _es est ida _po por or_ ões lhe nho \
+        _ma mos _vo voc ocê cê_ obr bri rig gad ela _aj aju _bo bom om_ _eu eu_ _fa faz zer _is \
+        iss sso _mu mui uit ito ele _aq lho _ob _ve _te ten _ag _ai"),
+    ("italian", "_di di_ _la la_ _il il_ che _ch he_ _co _de del ell lla _e_ to_ _pe per er_ re_ one ne_ \
+        zio ion _un una _no non on_ are ent nte _in ato no_ lo_ gli _gl ett sta _si _al all ano \
+        _ma _qu _so ono _è_ _ha zza _ci cia iao ao_ com ome me_ _st tai ai_ _bu buo uon gio ior \
+        orn rno _tu tut tti _gr raz azi sto _ve _mo _fa _pr _io io_ ich tto ggi cch _og amo mo_ \
+        ani ni_ _do oma man iam rse se_ _fo _an al_ mar _mi mio _ne nel ssa le_ te_ ri_ ti_ ta_ \
+        na_"),
+    ("german", "_de der er_ die ie_ _di und _un nd_ ein _ei ich ch_ sch _sc den en_ _da das as_ ten _ni \
+        nic cht ht_ ung ng_ _zu zu_ _ge gen _ve ver _be ist _is st_ _mi mit it_ auf _au ine ber \
+        ier _wi _we eit _si sie _gu gut ute _mo mor org rge _ta tag _wa was wie _ha hab abe _du \
+        du_ _ih ihr _ma ach lic ige _fr _üb _ka kan _ja _he _vo von _ic _bi bin"),
+    ("indonesian", "an_ _me kan _di ang ng_ _ke nya ya_ _be yan _ya men ada _da dan _se ber eng _pe lah ah_ \
+        _ti ida tid dak ak_ _sa aka _ak aku _in ini ni_ _it itu tu_ nga ter _te per tuk ntu uk_ \
+        ama _ba sud dah gan ran ora eri rim ima ma_ _ka kas asi sih ih_ _ap apa pa_ kab bar ar_ \
+        _pa pag agi gi_ sel ela lam mat at_ _ma mak bai aik _bi bis isa _or ung ras"),
+    ("turkish", "_bi bir ir_ lar ler _ve ve_ in_ ın_ _ol yor iyo _bu bu_ ini eri _ka da_ nda nde _ge ile \
+        _il ası esi _ya ım_ ını dır mak mek ek_ _ço çok ok_ iç_ _iç lık lik _ha ğı_ _gü _me mer \
+        erh rha hab aba ba_ _dü dün üny nya şek kür _na nas sıl _iy iyi yi_ gün _ne ne_ _ev _ta \
+        _se sev _be ben _si siz _te teş eşe"),
+    ("vietnamese", "_ng ng_ _nh nh_ _kh _th _tr _ch ông _là là_ _có có_ _và và_ _đư ược _mộ một _tô tôi ời_ \
+        khô _cá các _củ của _nà _đã ch_ anh inh _ti _gi _xi xin in_ chà ào_ _bạ ạn_ _cả cảm _ơn \
+        ơn_ _yê yêu _đi _ha _họ _vi ười"),
+];
+
+/// Letters used by only some Latin-script languages (by feature name), and
+/// how strongly each points to them
+const DISTINCTIVE_LETTERS: &[(&str, &str, f32)] = &[
+    ("vietnamese", "ơưđăạảấầẩẫậắằẳẵặẹẻẽếềểễệỉịọỏốồổỗộớờởỡợụủứừửữựỳỵỷỹ", 6.0),
+    ("vietnamese", "àáâãèéêìíòóôõùúý", 0.5),
+    ("turkish", "ışğ", 6.0),
+    ("turkish", "çöü", 1.5),
+    ("german", "ßä", 6.0),
+    ("german", "öü", 2.0),
+    ("spanish", "ñ¿¡", 6.0),
+    ("spanish", "áéíóú", 1.0),
+    ("portuguese", "ãõ", 6.0),
+    ("portuguese", "çâêôáéíóúà", 1.0),
+    ("italian", "èìòù", 2.0),
+    ("italian", "àé", 1.0),
+];
+
+/// Trigram weights per language feature, from 2.0 for the most frequent to 1.0
+static PROFILES: Lazy<Vec<(&'static str, HashMap<String, f32>)>> = Lazy::new(|| {
+    TRIGRAM_PROFILES
+        .iter()
+        .map(|&(feature, trigrams)| {
+            let trigrams: Vec<&str> = trigrams.split_whitespace().collect();
+            let len = trigrams.len() as f32;
+            let weights = trigrams
+                .iter()
+                .enumerate()
+                .map(|(rank, t)| (t.replace('_', " "), 2.0 - rank as f32 / len))
+                .collect();
+            (feature, weights)
+        })
+        .collect()
+});
+
+/// Built-in languages whose engines were compiled in
+pub fn available_languages() -> Vec<Language> {
+    DETECTABLE
+        .iter()
+        .filter(|lang| lang.feature().is_some_and(pipeline::is_feature_enabled))
+        .cloned()
+        .collect()
+}
+
+/// Detect the language of `text` among the compiled-in languages
+///
+/// Returns `None` only if no built-in language was compiled in. Text without
+/// letters is reported as the first available language with zero confidence.
+pub fn detect(text: &str) -> Option<Detection> {
+    detect_among(text, &available_languages())
+}
+
+/// Detect the language of `text`, choosing only from `candidates`
+pub fn detect_among(text: &str, candidates: &[Language]) -> Option<Detection> {
+    let first = candidates.first()?;
+
+    let (mut hangul, mut kana, mut han, mut latin, mut total) = (0, 0, 0, 0, 0);
+    for c in text.chars() {
+        match script::script_of(c) {
+            Some(Script::Hangul) => hangul += 1,
+            Some(Script::Kana) => kana += 1,
+            Some(Script::Han) => han += 1,
+            Some(Script::Latin) => latin += 1,
+            Some(Script::Other) => {}
+            None => continue,
+        }
+        total += 1;
+    }
+    if total == 0 {
+        return Some(Detection {
+            language: first.clone(),
+            confidence: 0.0,
+        });
+    }
+
+    let share = |count: usize| count as f32 / total as f32;
+    let has = |lang: &Language| candidates.contains(lang);
+    // Han characters are read as Japanese alongside kana, otherwise as Chinese
+    let han_is_japanese = kana > 0 || !has(&Language::Chinese);
+    let latin_candidates: Vec<&Language> = candidates
+        .iter()
+        .filter(|lang| lang.supports_script(Script::Latin))
+        .collect();
+    let latin_scores = if latin > 0 {
+        classify_latin(text, &latin_candidates)
+    } else {
+        vec![0.0; latin_candidates.len()]
+    };
+
+    let mut best: Option<Detection> = None;
+    for lang in candidates {
+        let score = match lang {
+            Language::Korean => share(hangul),
+            Language::Japanese if han_is_japanese => share(kana + han),
+            Language::Japanese => share(kana),
+            Language::Chinese if !han_is_japanese => share(han),
+            Language::Chinese => 0.0,
+            _ => match latin_candidates.iter().position(|l| *l == lang) {
+                Some(i) => share(latin) * latin_scores[i],
+                None => 0.0,
+            },
+        };
+        if best.as_ref().is_none_or(|b| score > b.confidence) {
+            best = Some(Detection {
+                language: lang.clone(),
+                confidence: score,
+            });
+        }
+    }
+    best
+}
+
+/// Probability of each Latin-script candidate, from distinctive letters and trigrams
+fn classify_latin(text: &str, candidates: &[&Language]) -> Vec<f32> {
+    if candidates.len() <= 1 {
+        return vec![1.0; candidates.len()];
+    }
+
+    let lower = text.to_lowercase();
+    let scores: Vec<f32> = candidates
+        .iter()
+        .map(|lang| {
+            let feature = lang.feature();
+            let letters: f32 = lower
+                .chars()
+                .map(|c| {
+                    DISTINCTIVE_LETTERS
+                        .iter()
+                        .filter(|(lang, letters, _)| Some(*lang) == feature && letters.contains(c))
+                        .map(|(_, _, weight)| weight)
+                        .sum::<f32>()
+                })
+                .sum();
+            let trigrams = match PROFILES.iter().find(|(lang, _)| Some(*lang) == feature) {
+                Some((_, profile)) => trigrams(&lower).iter().filter_map(|t| profile.get(t)).sum(),
+                None => 0.0,
+            };
+            letters + trigrams
+        })
+        .collect();
+
+    // Softmax: each point of score makes a language e times more likely
+    let max = scores.iter().copied().fold(f32::MIN, f32::max);
+    let weights: Vec<f32> = scores.iter().map(|s| (s - max).exp()).collect();
+    let sum: f32 = weights.iter().sum();
+    weights.iter().map(|w| w / sum).collect()
+}
+
+/// Character trigrams of each word, padded with a space on both sides
+fn trigrams(text: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    for word in text.split(|c: char| !c.is_alphabetic()).filter(|w| !w.is_empty()) {
+        let chars: Vec<char> = format!(" {} ", word).chars().collect();
+        result.extend(chars.windows(3).map(|w| w.iter().collect::<String>()));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detected(text: &str) -> Language {
+        detect_among(text, DETECTABLE).unwrap().language
+    }
+
+    #[test]
+    fn test_detect_by_script() {
+        assert_eq!(detected("안녕하세요, 반갑습니다"), Language::Korean);
+        assert_eq!(detected("こんにちは世界"), Language::Japanese);
+        assert_eq!(detected("你好世界"), Language::Chinese);
+
+        let detection = detect_among("안녕하세요", DETECTABLE).unwrap();
+        assert_eq!(detection.confidence, 1.0);
+    }
+
+    #[test]
+    fn test_detect_latin_languages() {
+        assert_eq!(detected("The quick brown fox jumps over the lazy dog"), Language::EnglishUS);
+        assert_eq!(detected("¿Dónde está la biblioteca?"), Language::Spanish);
+        assert_eq!(detected("Eu não sei o que fazer com isso"), Language::Portuguese);
+        assert_eq!(detected("Questa è la casa di mio padre"), Language::Italian);
+        assert_eq!(detected("Der Hund läuft über die Straße"), Language::German);
+        assert_eq!(detected("Saya akan pergi ke pasar dengan ibu"), Language::Indonesian);
+        assert_eq!(detected("Bugün hava çok güzel"), Language::Turkish);
+        assert_eq!(detected("Xin chào các bạn"), Language::Vietnamese);
+    }
+
+    #[test]
+    fn test_detect_among_candidates() {
+        // Chinese isn't available, so Han characters are read as Japanese
+        let detection = detect_among("你好", &[Language::EnglishUS, Language::Japanese]).unwrap();
+        assert_eq!(detection.language, Language::Japanese);
+
+        let detection = detect_among("Hola amigo", &[Language::EnglishUS]).unwrap();
+        assert_eq!(detection.language, Language::EnglishUS);
+        assert_eq!(detection.confidence, 1.0);
+
+        assert_eq!(detect_among("Hello", &[]), None);
+    }
+
+    #[test]
+    fn test_no_letters() {
+        let detection = detect_among("123 !?", DETECTABLE).unwrap();
+        assert_eq!(detection.language, Language::EnglishUS);
+        assert_eq!(detection.confidence, 0.0);
+    }
+}
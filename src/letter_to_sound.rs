@@ -0,0 +1,472 @@
+//! Rule-based letter-to-sound conversion for out-of-vocabulary English words
+//!
+//! Used as a last resort when a word is neither in the dictionaries nor
+//! reachable by stemming. Letters are grouped into grapheme clusters and mapped
+//! to phonemes in Kokoro's notation, then primary/secondary stress is assigned
+//! and unstressed vowels are reduced. Results are guesses and are rated
+//! [`GUESS_RATING`](crate::lexicon::GUESS_RATING).
+
+/// Where in the word a cluster rule may match
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Position {
+    Anywhere,
+    Start,
+    End,
+    /// Not followed by a vowel letter or another 'r' (for r-colored vowels)
+    BeforeConsonant,
+}
+
+/// What a cluster produces
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Consonant,
+    /// A vowel that can carry stress; its phonemes start with the vowel
+    Vowel,
+    /// A syllable that never carries stress (e.g. "-tion", "-le")
+    Unstressed,
+}
+
+/// Grapheme cluster rules, tried longest first
+const CLUSTERS: &[(&str, &str, Kind, Position)] = &[
+    // Unstressed suffix syllables
+    ("tious", "ʃəs", Kind::Unstressed, Position::End),
+    ("cious", "ʃəs", Kind::Unstressed, Position::End),
+    ("tion", "ʃən", Kind::Unstressed, Position::Anywhere),
+    ("sion", "ʒən", Kind::Unstressed, Position::Anywhere),
+    ("cian", "ʃən", Kind::Unstressed, Position::Anywhere),
+    ("tial", "ʃᵊl", Kind::Unstressed, Position::Anywhere),
+    ("cial", "ʃᵊl", Kind::Unstressed, Position::Anywhere),
+    ("ture", "ʧəɹ", Kind::Unstressed, Position::End),
+    ("ous", "əs", Kind::Unstressed, Position::End),
+    // Vowel clusters
+    ("eigh", "A", Kind::Vowel, Position::Anywhere),
+    ("augh", "ɔ", Kind::Vowel, Position::Anywhere),
+    ("igh", "I", Kind::Vowel, Position::Anywhere),
+    ("eau", "O", Kind::Vowel, Position::Anywhere),
+    ("air", "ɛɹ", Kind::Vowel, Position::BeforeConsonant),
+    ("ear", "ɪɹ", Kind::Vowel, Position::BeforeConsonant),
+    ("eer", "ɪɹ", Kind::Vowel, Position::BeforeConsonant),
+    ("oor", "ɔɹ", Kind::Vowel, Position::BeforeConsonant),
+    ("our", "Wɹ", Kind::Vowel, Position::BeforeConsonant),
+    ("ey", "i", Kind::Vowel, Position::End),
+    ("ie", "i", Kind::Vowel, Position::End),
+    ("ue", "u", Kind::Vowel, Position::End),
+    ("ow", "O", Kind::Vowel, Position::End),
+    ("ai", "A", Kind::Vowel, Position::Anywhere),
+    ("ay", "A", Kind::Vowel, Position::Anywhere),
+    ("ei", "A", Kind::Vowel, Position::Anywhere),
+    ("ey", "A", Kind::Vowel, Position::Anywhere),
+    ("ee", "i", Kind::Vowel, Position::Anywhere),
+    ("ea", "i", Kind::Vowel, Position::Anywhere),
+    ("ie", "i", Kind::Vowel, Position::Anywhere),
+    ("oa", "O", Kind::Vowel, Position::Anywhere),
+    ("oo", "u", Kind::Vowel, Position::Anywhere),
+    ("ou", "W", Kind::Vowel, Position::Anywhere),
+    ("ow", "W", Kind::Vowel, Position::Anywhere),
+    ("oi", "Y", Kind::Vowel, Position::Anywhere),
+    ("oy", "Y", Kind::Vowel, Position::Anywhere),
+    ("au", "ɔ", Kind::Vowel, Position::Anywhere),
+    ("aw", "ɔ", Kind::Vowel, Position::Anywhere),
+    ("ew", "u", Kind::Vowel, Position::Anywhere),
+    ("ui", "u", Kind::Vowel, Position::Anywhere),
+    ("ar", "ɑɹ", Kind::Vowel, Position::BeforeConsonant),
+    ("er", "ɜɹ", Kind::Vowel, Position::BeforeConsonant),
+    ("ir", "ɜɹ", Kind::Vowel, Position::BeforeConsonant),
+    ("ur", "ɜɹ", Kind::Vowel, Position::BeforeConsonant),
+    ("yr", "ɜɹ", Kind::Vowel, Position::BeforeConsonant),
+    ("or", "ɔɹ", Kind::Vowel, Position::BeforeConsonant),
+    // Consonant clusters
+    ("kn", "n", Kind::Consonant, Position::Start),
+    ("gn", "n", Kind::Consonant, Position::Start),
+    ("pn", "n", Kind::Consonant, Position::Start),
+    ("ps", "s", Kind::Consonant, Position::Start),
+    ("wr", "ɹ", Kind::Consonant, Position::Start),
+    ("gh", "ɡ", Kind::Consonant, Position::Start),
+    ("x", "z", Kind::Consonant, Position::Start),
+    ("mb", "m", Kind::Consonant, Position::End),
+    ("gn", "n", Kind::Consonant, Position::End),
+    ("tch", "ʧ", Kind::Consonant, Position::Anywhere),
+    ("sch", "sk", Kind::Consonant, Position::Anywhere),
+    ("chr", "kɹ", Kind::Consonant, Position::Anywhere),
+    ("dg", "ʤ", Kind::Consonant, Position::Anywhere),
+    ("ch", "ʧ", Kind::Consonant, Position::Anywhere),
+    ("sh", "ʃ", Kind::Consonant, Position::Anywhere),
+    ("th", "θ", Kind::Consonant, Position::Anywhere),
+    ("ph", "f", Kind::Consonant, Position::Anywhere),
+    ("wh", "w", Kind::Consonant, Position::Anywhere),
+    ("ck", "k", Kind::Consonant, Position::Anywhere),
+    ("nk", "ŋk", Kind::Consonant, Position::Anywhere),
+    ("ng", "ŋ", Kind::Consonant, Position::Anywhere),
+    ("qu", "kw", Kind::Consonant, Position::Anywhere),
+    ("gh", "", Kind::Consonant, Position::Anywhere),
+];
+
+/// Suffixes that put primary stress on the syllable before them
+const PRE_STRESSING_SUFFIXES: &[&str] = &[
+    "tion", "sion", "cian", "tial", "cial", "tious", "cious", "ical", "ics", "ic", "ity", "ify", "ian",
+    "ial", "ious", "eous", "ual", "uous", "itis", "osis", "logy", "graphy", "meter",
+];
+
+/// Suffixes that carry primary stress themselves
+const STRESSED_SUFFIXES: &[&str] = &["eer", "ee", "ese", "ette", "oon", "ique", "esque"];
+
+/// Unstressed prefixes: stress falls on the syllable after them
+const UNSTRESSED_PREFIXES: &[&str] = &["be", "de", "re", "pre", "un", "dis", "mis", "con", "com", "ex", "en", "em"];
+
+/// A phoneme unit produced from a grapheme cluster
+#[derive(Debug, Clone)]
+struct Unit {
+    phonemes: String,
+    kind: Kind,
+    /// Index of the first letter of the cluster
+    start: usize,
+}
+
+fn is_vowel_letter(c: u8) -> bool {
+    matches!(c, b'a' | b'e' | b'i' | b'o' | b'u' | b'y')
+}
+
+/// Convert a word to phonemes using letter-to-sound rules
+///
+/// Returns `None` if the word has no Latin letters or no vowel letters.
+pub fn word_to_phonemes(word: &str, british: bool) -> Option<String> {
+    let letters: String = word
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    if !letters.bytes().any(is_vowel_letter) {
+        return None;
+    }
+
+    let mut units = letters_to_units(&letters);
+    let (primary, secondary) = choose_stress(&letters, &units)?;
+    reduce_vowels(&letters, &mut units, primary, secondary, british);
+    if british {
+        apply_british(&mut units);
+    } else {
+        apply_flap_t(&mut units, primary);
+    }
+
+    let mut phonemes = String::new();
+    for (i, unit) in units.iter().enumerate() {
+        // Stress goes before the vowel, after a leading glide (jˈu)
+        let glide = if unit.phonemes.starts_with('j') && unit.kind == Kind::Vowel { 1 } else { 0 };
+        let (onset, nucleus) = unit.phonemes.split_at(glide);
+        phonemes.push_str(onset);
+        if i == primary {
+            phonemes.push('ˈ');
+        } else if Some(i) == secondary {
+            phonemes.push('ˌ');
+        }
+        phonemes.push_str(nucleus);
+    }
+    Some(phonemes)
+}
+
+/// Group letters into clusters and map each to phonemes
+fn letters_to_units(word: &str) -> Vec<Unit> {
+    let bytes = word.as_bytes();
+    let len = bytes.len();
+    let mut units: Vec<Unit> = Vec::new();
+    let mut i = 0;
+
+    let push = |units: &mut Vec<Unit>, phonemes: &str, kind: Kind, start: usize| {
+        units.push(Unit {
+            phonemes: phonemes.to_string(),
+            kind,
+            start,
+        });
+    };
+
+    while i < len {
+        let rest = &word[i..];
+        let c = bytes[i];
+        let prev = if i > 0 { Some(bytes[i - 1]) } else { None };
+        let next = bytes.get(i + 1).copied();
+        let has_vowel_before = units.iter().any(|u| u.kind != Kind::Consonant);
+
+        // Final silent e, and syllabic "-le" / "-re" after a consonant
+        if has_vowel_before && prev.is_some_and(|p| !is_vowel_letter(p)) {
+            match rest {
+                "e" | "es" if units.len() > 1 => {
+                    i += 1;
+                    continue;
+                }
+                "le" => {
+                    push(&mut units, "ᵊl", Kind::Unstressed, i);
+                    break;
+                }
+                "re" => {
+                    push(&mut units, "əɹ", Kind::Unstressed, i);
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        // Grapheme clusters
+        let cluster = CLUSTERS.iter().find(|(pattern, _, _, position)| {
+            rest.starts_with(pattern)
+                && match position {
+                    Position::Anywhere => true,
+                    Position::Start => i == 0,
+                    Position::End => rest.len() == pattern.len(),
+                    Position::BeforeConsonant => rest[pattern.len()..]
+                        .bytes()
+                        .next()
+                        .is_none_or(|n| !is_vowel_letter(n) && n != b'r'),
+                }
+        });
+        if let Some(&(pattern, phonemes, kind, _)) = cluster {
+            push(&mut units, phonemes, kind, i);
+            i += pattern.len();
+            continue;
+        }
+
+        // Single vowels
+        if is_vowel_letter(c) && !(c == b'y' && i == 0 && next.is_some_and(is_vowel_letter)) {
+            let (phonemes, consumed) = vowel_phonemes(word, i);
+            push(&mut units, phonemes, Kind::Vowel, i);
+            i += consumed;
+            continue;
+        }
+
+        // Single consonants (doubled letters collapse to one)
+        if prev == Some(c) && !(c == b'c' && next.is_some_and(|n| matches!(n, b'e' | b'i' | b'y'))) {
+            i += 1;
+            continue;
+        }
+        let phonemes = match c {
+            b'c' if next.is_some_and(|n| matches!(n, b'e' | b'i' | b'y')) => "s",
+            b'c' | b'k' | b'q' => "k",
+            b'g' if next.is_some_and(|n| matches!(n, b'e' | b'i' | b'y')) => "ʤ",
+            b'g' => "ɡ",
+            b'j' => "ʤ",
+            b'r' => "ɹ",
+            b'y' => "j",
+            b'x' => "ks",
+            b'h' if !next.is_some_and(is_vowel_letter) && i > 0 => "",
+            b's' if prev.is_some_and(is_vowel_letter) && next.is_some_and(is_vowel_letter) => "z",
+            b's' if next.is_none() && prev.is_some_and(|p| b"bdglmnrv".contains(&p)) => "z",
+            _ => &rest[..1],
+        };
+        push(&mut units, phonemes, Kind::Consonant, i);
+        i += 1;
+    }
+
+    units.retain(|u| !u.phonemes.is_empty());
+    units
+}
+
+/// Phonemes for a single vowel letter at `i`, and the number of letters consumed
+fn vowel_phonemes(word: &str, i: usize) -> (&'static str, usize) {
+    let bytes = word.as_bytes();
+    let c = bytes[i];
+    let is_last = i + 1 == bytes.len();
+    let tail = &word[i + 1..];
+
+    // Vowel + "re" at the end: care, here, fire, more, cure
+    if tail == "re" {
+        let phonemes = match c {
+            b'a' => "ɛɹ",
+            b'e' => "ɪɹ",
+            b'i' | b'y' => "Iəɹ",
+            b'o' => "ɔɹ",
+            _ => "jʊɹ",
+        };
+        return (phonemes, 2);
+    }
+
+    // Magic e (vowel + single consonant + final "e"/"es"/"ed"), and the
+    // long vowel before "-tion" and similar endings
+    let magic_e = tail.len() >= 2
+        && !is_vowel_letter(tail.as_bytes()[0])
+        && !matches!(tail.as_bytes()[0], b'w' | b'x' | b'r')
+        && matches!(&tail[1..], "e" | "es" | "ed");
+    let before_tion = ["tion", "sion", "cian", "tial", "cial"]
+        .iter()
+        .any(|suffix| tail.starts_with(suffix));
+
+    let phonemes = match c {
+        b'a' if magic_e || before_tion => "A",
+        b'e' if magic_e || before_tion => "i",
+        b'i' | b'y' if magic_e => "I",
+        b'o' if magic_e || before_tion => "O",
+        b'u' if magic_e || before_tion => {
+            let after_coronal = i > 0 && b"dlnrstjz".contains(&bytes[i - 1]);
+            if after_coronal {
+                "u"
+            } else {
+                "ju"
+            }
+        }
+        b'a' if is_last => "ə",
+        b'e' | b'i' if is_last => "i",
+        b'o' if is_last => "O",
+        b'y' if is_last => {
+            if word[..i].bytes().any(is_vowel_letter) {
+                "i"
+            } else {
+                "I"
+            }
+        }
+        b'a' if i > 0 && bytes[i - 1] == b'w' => "ɑ",
+        b'a' => "æ",
+        b'e' => "ɛ",
+        b'i' | b'y' => "ɪ",
+        b'o' => "ɑ",
+        _ => "ʌ",
+    };
+    (phonemes, 1)
+}
+
+/// Pick the units carrying primary and (optionally) secondary stress
+fn choose_stress(word: &str, units: &[Unit]) -> Option<(usize, Option<usize>)> {
+    let vowels: Vec<usize> = (0..units.len()).filter(|&i| units[i].kind == Kind::Vowel).collect();
+    let last = *vowels.last()?;
+    let syllables = units.iter().filter(|u| u.kind != Kind::Consonant).count();
+
+    let primary = if let Some(suffix) = STRESSED_SUFFIXES.iter().find(|s| word.ends_with(*s)) {
+        let start = word.len() - suffix.len();
+        vowels.iter().copied().find(|&i| units[i].start >= start).unwrap_or(last)
+    } else if let Some(suffix) = PRE_STRESSING_SUFFIXES
+        .iter()
+        .find(|s| word.ends_with(*s) && word.len() > s.len() + 1)
+    {
+        let start = word.len() - suffix.len();
+        vowels
+            .iter()
+            .copied()
+            .rev()
+            .find(|&i| units[i].start < start)
+            .unwrap_or(vowels[0])
+    } else if let Some(prefix) = UNSTRESSED_PREFIXES
+        .iter()
+        .find(|p| word.starts_with(*p) && word.len() >= p.len() + 4)
+    {
+        vowels
+            .iter()
+            .copied()
+            .find(|&i| units[i].start >= prefix.len())
+            .unwrap_or(vowels[0])
+    } else if syllables >= 4 && vowels.len() >= 3 {
+        vowels[vowels.len() - 3]
+    } else {
+        vowels[0]
+    };
+
+    let position = vowels.iter().position(|&i| i == primary)?;
+    let secondary = if position >= 2 { Some(vowels[0]) } else { None };
+    Some((primary, secondary))
+}
+
+/// Reduce unstressed vowels (æ, ɛ, ʌ, ɑ, ɔ → ə; r-colored → əɹ)
+fn reduce_vowels(word: &str, units: &mut [Unit], primary: usize, secondary: Option<usize>, british: bool) {
+    let last_vowel = units.iter().rposition(|u| u.kind == Kind::Vowel);
+
+    for i in 0..units.len() {
+        if units[i].kind != Kind::Vowel || i == primary || Some(i) == secondary {
+            continue;
+        }
+
+        // Unstressed "e" in a final "-es", "-ed", "-est" syllable
+        let tail = &word[units[i].start..];
+        if units[i].phonemes == "ɛ" && Some(i) == last_vowel && matches!(tail, "es" | "ed" | "est" | "et") {
+            units[i].phonemes = if british { "ɪ" } else { "ᵻ" }.to_string();
+            continue;
+        }
+
+        let reduced = match units[i].phonemes.as_str() {
+            "æ" | "ɛ" | "ʌ" | "ɑ" | "ɔ" => "ə",
+            "ɜɹ" | "ɑɹ" | "ɔɹ" => "əɹ",
+            _ => continue,
+        };
+        units[i].phonemes = reduced.to_string();
+    }
+}
+
+/// American flap: t between vowels before an unstressed vowel becomes ɾ
+fn apply_flap_t(units: &mut [Unit], primary: usize) {
+    for i in 1..units.len().saturating_sub(1) {
+        let after_vowel = units[i - 1].kind == Kind::Vowel;
+        let before_unstressed = units[i + 1].kind != Kind::Consonant
+            && i + 1 != primary
+            && units[i + 1].phonemes.starts_with(|c: char| !"ʃʒʧ".contains(c));
+        if units[i].phonemes == "t" && after_vowel && before_unstressed {
+            units[i].phonemes = "ɾ".to_string();
+        }
+    }
+}
+
+/// British vowels and non-rhotic r
+fn apply_british(units: &mut [Unit]) {
+    for i in 0..units.len() {
+        let next_is_vowel = units.get(i + 1).is_some_and(|u| u.kind != Kind::Consonant);
+        let phonemes = units[i].phonemes.as_str();
+        let replaced = match phonemes {
+            "O" => "Q",
+            "ɑ" => "ɒ",
+            "æ" => "a",
+            "ɔ" => "ɔː",
+            _ if next_is_vowel || !phonemes.ends_with('ɹ') || units[i].kind == Kind::Consonant => continue,
+            "ɑɹ" => "ɑː",
+            "ɜɹ" => "ɜː",
+            "ɔɹ" => "ɔː",
+            "ɪɹ" => "ɪə",
+            "ɛɹ" => "ɛə",
+            "Wɹ" => "Wə",
+            _ => {
+                units[i].phonemes.pop();
+                continue;
+            }
+        };
+        units[i].phonemes = replaced.to_string();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::is_valid_phoneme;
+
+    #[test]
+    fn test_basic_words() {
+        assert_eq!(word_to_phonemes("blick", false).unwrap(), "blˈɪk");
+        assert_eq!(word_to_phonemes("zorblax", false).unwrap(), "zˈɔɹbləks");
+        assert_eq!(word_to_phonemes("frate", false).unwrap(), "fɹˈAt");
+        assert_eq!(word_to_phonemes("snoy", false).unwrap(), "snˈY");
+    }
+
+    #[test]
+    fn test_stress_and_suffixes() {
+        assert_eq!(word_to_phonemes("flobation", false).unwrap(), "fləbˈAʃən");
+        assert_eq!(word_to_phonemes("flure", false).unwrap(), "fljˈʊɹ");
+        assert_eq!(word_to_phonemes("grandle", false).unwrap(), "ɡɹˈændᵊl");
+        assert_eq!(word_to_phonemes("zippo", false).unwrap(), "zˈɪpO");
+    }
+
+    #[test]
+    fn test_british() {
+        assert_eq!(word_to_phonemes("zippo", true).unwrap(), "zˈɪpQ");
+        assert_eq!(word_to_phonemes("zorblax", true).unwrap(), "zˈɔːbləks");
+        assert_eq!(word_to_phonemes("blare", true).unwrap(), "blˈɛə");
+    }
+
+    #[test]
+    fn test_output_is_tokenizable() {
+        for word in ["quixotry", "Xanthippe", "schmoozle", "wrightful", "phlegmatics", "yurt"] {
+            for british in [false, true] {
+                let phonemes = word_to_phonemes(word, british).unwrap();
+                assert!(phonemes.chars().all(is_valid_phoneme), "{} -> {}", word, phonemes);
+                assert_eq!(phonemes.matches('ˈ').count(), 1, "{} -> {}", word, phonemes);
+            }
+        }
+    }
+
+    #[test]
+    fn test_no_vowels() {
+        assert_eq!(word_to_phonemes("brr", false), None);
+        assert_eq!(word_to_phonemes("日本", false), None);
+    }
+}
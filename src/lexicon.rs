@@ -5,13 +5,18 @@
 //! 2. Silver dictionary (fallback)
 //! 3. Stemming rules for -s, -ed, -ing suffixes
 //!
-//! Dictionaries are compressed with bincode + zstd at build time
-//! for ~60% size reduction compared to raw JSON.
+//! The build script compiles the JSON dictionaries into a sorted string table
+//! with interned phoneme strings (see [`CompactDictionary`]), which is
+//! searched directly in the embedded bytes. Nothing is decompressed or parsed
+//! at startup, and case variants are resolved at lookup time.
 
+use crate::error::G2PError;
+use crate::lexicon_format::{self, RawEntry};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::io::Cursor;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 /// Phoneme entry that can be either a simple string or tag-dependent
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -26,29 +31,43 @@ impl PhonemeEntry {
     pub fn get(&self, tag: Option<&str>) -> Option<&str> {
         match self {
             PhonemeEntry::Simple(s) => Some(s.as_str()),
+            PhonemeEntry::Tagged(map) => resolve_tag(tag, |t| map.get(t).and_then(|v| v.as_deref())),
+        }
+    }
+}
+
+impl From<PhonemeEntry> for RawEntry {
+    fn from(entry: PhonemeEntry) -> Self {
+        match entry {
+            PhonemeEntry::Simple(s) => RawEntry::Simple(s),
             PhonemeEntry::Tagged(map) => {
-                // Try exact tag match first
-                if let Some(tag) = tag {
-                    if let Some(Some(val)) = map.get(tag) {
-                        return Some(val.as_str());
-                    }
-                    // Try parent tag
-                    let parent = get_parent_tag(tag);
-                    if parent != tag {
-                        if let Some(Some(val)) = map.get(parent) {
-                            return Some(val.as_str());
-                        }
-                    }
-                }
-                // Fall back to DEFAULT
-                map.get("DEFAULT")
-                    .and_then(|v| v.as_ref())
-                    .map(|s| s.as_str())
+                let mut pairs: Vec<_> = map.into_iter().collect();
+                pairs.sort();
+                RawEntry::Tagged(pairs)
             }
         }
     }
 }
 
+/// Pick the phonemes for a POS tag from a tag-dependent entry
+///
+/// `get` returns the phonemes stored under a tag, if any. The exact tag is
+/// tried first, then its parent tag, then `DEFAULT`.
+fn resolve_tag<'a>(tag: Option<&str>, get: impl Fn(&str) -> Option<&'a str>) -> Option<&'a str> {
+    if let Some(tag) = tag {
+        if let Some(val) = get(tag) {
+            return Some(val);
+        }
+        let parent = get_parent_tag(tag);
+        if parent != tag {
+            if let Some(val) = get(parent) {
+                return Some(val);
+            }
+        }
+    }
+    get("DEFAULT")
+}
+
 /// Map POS tags to their parent categories
 fn get_parent_tag(tag: &str) -> &str {
     if tag.starts_with("VB") {
@@ -64,14 +83,12 @@ fn get_parent_tag(tag: &str) -> &str {
     }
 }
 
-/// Stress markers
-const PRIMARY_STRESS: char = 'ˈ';
-const SECONDARY_STRESS: char = 'ˌ';
-const PRIMARY_STRESS_STR: &str = "ˈ";
-const SECONDARY_STRESS_STR: &str = "ˌ";
-
-/// Vowels for stress placement
-const VOWELS: &str = "AIOQWYaiuæɑɒɔəɛɜɪʊʌᵻ";
+/// Lookup ratings, from most to least trustworthy
+pub const USER_RATING: u8 = 5;
+pub const GOLD_RATING: u8 = 4;
+pub const SILVER_RATING: u8 = 3;
+/// Rating for pronunciations guessed by letter-to-sound rules
+pub const GUESS_RATING: u8 = 2;
 
 /// Characters that indicate word ending sounds
 const VOICELESS_ENDINGS: &str = "ptkfθʃsʧ";
@@ -80,66 +97,243 @@ const SIBILANT_ENDINGS: &str = "szʃʒʧʤ";
 /// Dictionary type
 type Dictionary = HashMap<String, PhonemeEntry>;
 
-/// Load a dictionary from zstd-compressed JSON data
-fn load_dictionary(compressed: &[u8]) -> Dictionary {
-    // Decompress zstd
-    let cursor = Cursor::new(compressed);
-    let decompressed = match zstd::decode_all(cursor) {
-        Ok(data) => data,
-        Err(e) => {
-            log::error!("CRITICAL: Failed to decompress dictionary: {}. All words will be unknown.", e);
-            return HashMap::new();
-        }
-    };
+/// Bytes backing a compiled dictionary
+enum Storage {
+    Static(&'static [u8]),
+    Owned(Vec<u8>),
+    #[cfg(feature = "mmap")]
+    Mapped(memmap2::Mmap),
+}
 
-    // Parse JSON
-    let json_str = match std::str::from_utf8(&decompressed) {
-        Ok(s) => s,
-        Err(e) => {
-            log::error!("CRITICAL: Failed to decode dictionary as UTF-8: {}. All words will be unknown.", e);
-            return HashMap::new();
+impl Storage {
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Storage::Static(bytes) => bytes,
+            Storage::Owned(bytes) => bytes,
+            #[cfg(feature = "mmap")]
+            Storage::Mapped(map) => map,
         }
-    };
+    }
+}
 
-    serde_json::from_str(json_str).unwrap_or_else(|e| {
-        log::error!("CRITICAL: Failed to parse dictionary JSON: {}. All words will be unknown.", e);
-        HashMap::new()
-    })
+/// A pronunciation dictionary compiled into a sorted string table
+///
+/// Words are found by binary search directly in the compiled bytes, which
+/// can be embedded in the binary, owned, or memory-mapped from a file (with
+/// the `mmap` feature). Loading only validates the header, so it costs no
+/// time or memory proportional to the dictionary size.
+///
+/// Lookups also match case variants the way the Python lexicon grows its
+/// dictionaries: a lowercase word matches a capitalized entry and vice versa.
+pub struct CompactDictionary {
+    data: Storage,
+    entry_count: usize,
+    string_count: usize,
+    /// Byte offsets of the sections after the header
+    values: usize,
+    string_offsets: usize,
+    tagged: usize,
+    words: usize,
+    strings: usize,
 }
 
-/// Grow dictionary with case variants
-fn grow_dictionary(dict: &mut Dictionary) {
-    let additions: Vec<(String, PhonemeEntry)> = dict
-        .iter()
-        .filter(|(k, _)| k.len() >= 2)
-        .filter_map(|(k, v)| {
-            if k == &k.to_lowercase() {
-                // lowercase -> Capitalized
-                let cap = capitalize(k);
-                if &cap != k && !dict.contains_key(&cap) {
-                    Some((cap, v.clone()))
-                } else {
-                    None
-                }
-            } else if k == &capitalize(k) {
-                // Capitalized -> lowercase
-                let lower = k.to_lowercase();
-                if &lower != k && !dict.contains_key(&lower) {
-                    Some((lower, v.clone()))
-                } else {
-                    None
-                }
-            } else {
-                None
+impl CompactDictionary {
+    /// Use compiled dictionary bytes in place
+    pub fn from_static(bytes: &'static [u8]) -> Result<Self, G2PError> {
+        Self::parse(Storage::Static(bytes))
+    }
+
+    /// Take ownership of compiled dictionary bytes
+    pub fn from_vec(bytes: Vec<u8>) -> Result<Self, G2PError> {
+        Self::parse(Storage::Owned(bytes))
+    }
+
+    /// Open a compiled dictionary file
+    ///
+    /// With the `mmap` feature the file is memory-mapped rather than read, and
+    /// must not be modified while the dictionary is in use.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, G2PError> {
+        let path = path.as_ref();
+        let io_error = |e: std::io::Error| G2PError::DictionaryLoad(format!("{}: {}", path.display(), e));
+
+        #[cfg(feature = "mmap")]
+        {
+            let file = std::fs::File::open(path).map_err(io_error)?;
+            // SAFETY: the file is only read, and callers are told not to modify it while mapped
+            let map = unsafe { memmap2::Mmap::map(&file) }.map_err(io_error)?;
+            Self::parse(Storage::Mapped(map))
+        }
+        #[cfg(not(feature = "mmap"))]
+        {
+            Self::from_vec(std::fs::read(path).map_err(io_error)?)
+        }
+    }
+
+    /// Compile a dictionary from JSON in the built-in dictionary format
+    ///
+    /// Returns the compiled bytes, ready for [`CompactDictionary::from_vec`]
+    /// or to be written to a file for [`CompactDictionary::open`].
+    pub fn compile_json(json: &str) -> Result<Vec<u8>, G2PError> {
+        let entries: Dictionary =
+            serde_json::from_str(json).map_err(|e| G2PError::DictionaryLoad(format!("invalid JSON: {}", e)))?;
+        let entries: BTreeMap<String, RawEntry> = entries.into_iter().map(|(k, v)| (k, v.into())).collect();
+        Ok(lexicon_format::encode(&entries))
+    }
+
+    /// Validate the header and locate the sections
+    fn parse(data: Storage) -> Result<Self, G2PError> {
+        let invalid = |reason: &str| G2PError::DictionaryLoad(format!("invalid compiled dictionary: {}", reason));
+        let bytes = data.bytes();
+        if bytes.len() < lexicon_format::HEADER_LEN || &bytes[..4] != lexicon_format::MAGIC {
+            return Err(invalid("bad signature"));
+        }
+
+        let header = |i: usize| read_u32(bytes, 4 + 4 * i) as usize;
+        if header(0) != lexicon_format::VERSION as usize {
+            return Err(invalid("unsupported version"));
+        }
+        let (entry_count, string_count, tagged_len, words_len, strings_len) =
+            (header(1), header(2), header(3), header(4), header(5));
+
+        let values = lexicon_format::HEADER_LEN + 4 * (entry_count + 1);
+        let string_offsets = values + 4 * entry_count;
+        let tagged = string_offsets + 4 * (string_count + 1);
+        let words = tagged + 4 * tagged_len;
+        let strings = words + words_len;
+        if strings + strings_len != bytes.len() {
+            return Err(invalid("section sizes do not match the data length"));
+        }
+
+        Ok(Self {
+            data,
+            entry_count,
+            string_count,
+            values,
+            string_offsets,
+            tagged,
+            words,
+            strings,
+        })
+    }
+
+    /// An empty dictionary
+    fn empty() -> Self {
+        Self::from_vec(lexicon_format::encode(&BTreeMap::new())).expect("encoded dictionary is valid")
+    }
+
+    /// Number of words (not counting case variants)
+    pub fn len(&self) -> usize {
+        self.entry_count
+    }
+
+    /// Check if the dictionary has no words
+    pub fn is_empty(&self) -> bool {
+        self.entry_count == 0
+    }
+
+    /// Check if a word, or a case variant of it, is in the dictionary
+    pub fn contains(&self, word: &str) -> bool {
+        self.find(word).is_some()
+    }
+
+    /// Look up a word's phonemes for a POS tag
+    pub fn get(&self, word: &str, tag: Option<&str>) -> Option<&str> {
+        self.find(word).and_then(|index| self.entry(index, tag))
+    }
+
+    /// Find a word, falling back to its case variant
+    fn find(&self, word: &str) -> Option<usize> {
+        self.search(word.as_bytes())
+            .or_else(|| case_variant(word).and_then(|variant| self.search(variant.as_bytes())))
+    }
+
+    /// Binary search for an exact word
+    fn search(&self, word: &[u8]) -> Option<usize> {
+        let (mut low, mut high) = (0, self.entry_count);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match self.word(mid)?.cmp(word) {
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+                std::cmp::Ordering::Equal => return Some(mid),
             }
+        }
+        None
+    }
+
+    fn u32_at(&self, offset: usize) -> u32 {
+        read_u32(self.data.bytes(), offset)
+    }
+
+    fn word(&self, index: usize) -> Option<&[u8]> {
+        let offset = lexicon_format::HEADER_LEN + 4 * index;
+        let (start, end) = (self.u32_at(offset) as usize, self.u32_at(offset + 4) as usize);
+        self.data.bytes().get(self.words + start..self.words + end)
+    }
+
+    fn string(&self, index: u32) -> Option<&str> {
+        let index = index as usize;
+        if index >= self.string_count {
+            return None;
+        }
+        let offset = self.string_offsets + 4 * index;
+        let (start, end) = (self.u32_at(offset) as usize, self.u32_at(offset + 4) as usize);
+        let bytes = self.data.bytes().get(self.strings + start..self.strings + end)?;
+        std::str::from_utf8(bytes).ok()
+    }
+
+    /// Phonemes of the entry at `index` for a POS tag
+    fn entry(&self, index: usize, tag: Option<&str>) -> Option<&str> {
+        let value = self.u32_at(self.values + 4 * index);
+        if value & lexicon_format::TAGGED == 0 {
+            return self.string(value);
+        }
+
+        let offset = self.tagged + 4 * (value & !lexicon_format::TAGGED) as usize;
+        if offset + 4 > self.words {
+            return None;
+        }
+        let count = self.u32_at(offset) as usize;
+        resolve_tag(tag, |t| {
+            (0..count)
+                .map(|i| offset + 4 + 8 * i)
+                .take_while(|&pair| pair + 8 <= self.words)
+                .find(|&pair| self.string(self.u32_at(pair)) == Some(t))
+                .and_then(|pair| self.string(self.u32_at(pair + 4)))
         })
-        .collect();
+    }
+}
 
-    for (k, v) in additions {
-        dict.insert(k, v);
+impl std::fmt::Debug for CompactDictionary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompactDictionary")
+            .field("len", &self.entry_count)
+            .field("bytes", &self.data.bytes().len())
+            .finish()
     }
 }
 
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().expect("4 bytes"))
+}
+
+/// The case variant a word can match when it has no entry of its own
+///
+/// Lowercase words match their capitalized entry ("paris" -> "Paris") and
+/// capitalized words their lowercase entry ("Hello" -> "hello"). Words
+/// shorter than two bytes have no variant.
+fn case_variant(word: &str) -> Option<String> {
+    let lower = word.to_lowercase();
+    let variant = if word == lower {
+        capitalize(word)
+    } else if word == capitalize(&lower) {
+        lower
+    } else {
+        return None;
+    };
+    (variant != word && variant.len() >= 2).then_some(variant)
+}
+
 fn capitalize(s: &str) -> String {
     let mut c = s.chars();
     match c.next() {
@@ -148,90 +342,194 @@ fn capitalize(s: &str) -> String {
     }
 }
 
-/// The Lexicon handles word to phoneme conversion
+/// Load a gold or silver dictionary from JSON or compiled bytes
+fn dictionary_from_bytes(bytes: Vec<u8>) -> Result<CompactDictionary, G2PError> {
+    if bytes.starts_with(lexicon_format::MAGIC) {
+        return CompactDictionary::from_vec(bytes);
+    }
+    let json = String::from_utf8(bytes)
+        .map_err(|_| G2PError::DictionaryLoad("neither a compiled dictionary nor UTF-8 JSON".to_string()))?;
+    CompactDictionary::from_vec(CompactDictionary::compile_json(&json)?)
+}
+
+/// Load a gold or silver dictionary file
+///
+/// Files ending in `.json` are compiled on load, anything else is opened as a
+/// compiled dictionary.
+fn dictionary_from_path(path: &Path) -> Result<CompactDictionary, G2PError> {
+    let is_json = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    if !is_json {
+        return CompactDictionary::open(path);
+    }
+    let json = std::fs::read_to_string(path)
+        .map_err(|e| G2PError::DictionaryLoad(format!("{}: {}", path.display(), e)))?;
+    CompactDictionary::from_vec(CompactDictionary::compile_json(&json)?)
+        .map_err(|e| G2PError::DictionaryLoad(format!("{}: {}", path.display(), e)))
+}
+
+/// Gold and silver dictionaries of one English dialect
+///
+/// Cloning is cheap: clones share the dictionaries.
+#[derive(Clone, Debug)]
 pub struct Lexicon {
     british: bool,
-    gold: Dictionary,
-    silver: Dictionary,
-}
-
-// Embed compressed dictionaries at compile time (zstd-compressed JSON)
-static US_GOLD_COMPRESSED: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/us_gold.json.zst"));
-static US_SILVER_COMPRESSED: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/us_silver.json.zst"));
-static GB_GOLD_COMPRESSED: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/gb_gold.json.zst"));
-static GB_SILVER_COMPRESSED: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/gb_silver.json.zst"));
-
-// Pre-loaded US dictionaries (decompressed on first use)
-static US_GOLD: Lazy<Dictionary> = Lazy::new(|| {
-    let mut d = load_dictionary(US_GOLD_COMPRESSED);
-    grow_dictionary(&mut d);
-    d
-});
-
-static US_SILVER: Lazy<Dictionary> = Lazy::new(|| {
-    let mut d = load_dictionary(US_SILVER_COMPRESSED);
-    grow_dictionary(&mut d);
-    d
-});
-
-// Pre-loaded GB dictionaries (decompressed on first use)
-static GB_GOLD: Lazy<Dictionary> = Lazy::new(|| {
-    let mut d = load_dictionary(GB_GOLD_COMPRESSED);
-    grow_dictionary(&mut d);
-    d
-});
-
-static GB_SILVER: Lazy<Dictionary> = Lazy::new(|| {
-    let mut d = load_dictionary(GB_SILVER_COMPRESSED);
-    grow_dictionary(&mut d);
-    d
-});
+    gold: Arc<CompactDictionary>,
+    silver: Arc<CompactDictionary>,
+}
+
+// Embed dictionaries compiled by build.rs
+#[cfg(not(feature = "external-dictionaries"))]
+static US_GOLD_DATA: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/us_gold.lex"));
+#[cfg(not(feature = "external-dictionaries"))]
+static US_SILVER_DATA: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/us_silver.lex"));
+#[cfg(not(feature = "external-dictionaries"))]
+static GB_GOLD_DATA: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/gb_gold.lex"));
+#[cfg(not(feature = "external-dictionaries"))]
+static GB_SILVER_DATA: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/gb_silver.lex"));
+
+/// Use the embedded dictionaries of a dialect, logging on failure
+#[cfg(not(feature = "external-dictionaries"))]
+fn default_dictionaries(british: bool) -> Result<Lexicon, G2PError> {
+    let (gold, silver) = if british {
+        (("gb_gold", GB_GOLD_DATA), ("gb_silver", GB_SILVER_DATA))
+    } else {
+        (("us_gold", US_GOLD_DATA), ("us_silver", US_SILVER_DATA))
+    };
+    let load = |(name, bytes): (&str, &'static [u8])| {
+        CompactDictionary::from_static(bytes).map(Arc::new).map_err(|e| {
+            log::error!("CRITICAL: {} dictionary: {}. All words will be unknown.", name, e);
+            e
+        })
+    };
+    Ok(Lexicon {
+        british,
+        gold: load(gold)?,
+        silver: load(silver)?,
+    })
+}
+
+/// Without embedded data, nothing is available until dictionaries are installed
+#[cfg(feature = "external-dictionaries")]
+fn default_dictionaries(british: bool) -> Result<Lexicon, G2PError> {
+    let language = if british { "en-gb" } else { "en-us" };
+    Err(G2PError::DictionaryNotLoaded(language.to_string()))
+}
+
+/// Dictionaries used by the English engines, indexed by `british as usize`
+static INSTALLED: Lazy<[RwLock<Result<Lexicon, G2PError>>; 2]> =
+    Lazy::new(|| [RwLock::new(default_dictionaries(false)), RwLock::new(default_dictionaries(true))]);
+
+fn installed(british: bool) -> RwLockReadGuard<'static, Result<Lexicon, G2PError>> {
+    INSTALLED[british as usize]
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+}
 
 impl Lexicon {
-    /// Create a new Lexicon for American or British English
+    /// Get the installed dictionaries for American or British English
+    ///
+    /// If none are available (see [`Lexicon::check_loaded`]), the lexicon is
+    /// empty and every word is unknown.
     pub fn new(british: bool) -> Self {
-        if british {
-            Self {
-                british,
-                gold: GB_GOLD.clone(),
-                silver: GB_SILVER.clone(),
-            }
-        } else {
-            Self {
-                british,
-                gold: US_GOLD.clone(),
-                silver: US_SILVER.clone(),
-            }
-        }
+        Self::try_new(british).unwrap_or_else(|_| Self {
+            british,
+            gold: Arc::new(CompactDictionary::empty()),
+            silver: Arc::new(CompactDictionary::empty()),
+        })
+    }
+
+    /// Like [`Lexicon::new`], but fails if no dictionaries are available
+    pub fn try_new(british: bool) -> Result<Self, G2PError> {
+        installed(british).clone()
     }
 
-    /// Create a lexicon using static references (more memory efficient)
+    /// Load gold and silver dictionaries from files
+    ///
+    /// Files ending in `.json` use the JSON dictionary format and are compiled
+    /// on load; anything else must be a compiled dictionary, which is
+    /// memory-mapped with the `mmap` feature. Call [`Lexicon::install`] to have
+    /// the English engines use them.
+    pub fn from_path<P: AsRef<Path>, Q: AsRef<Path>>(british: bool, gold: P, silver: Q) -> Result<Self, G2PError> {
+        Ok(Self {
+            british,
+            gold: Arc::new(dictionary_from_path(gold.as_ref())?),
+            silver: Arc::new(dictionary_from_path(silver.as_ref())?),
+        })
+    }
+
+    /// Load gold and silver dictionaries from JSON or compiled bytes
+    pub fn from_bytes(british: bool, gold: Vec<u8>, silver: Vec<u8>) -> Result<Self, G2PError> {
+        Ok(Self {
+            british,
+            gold: Arc::new(dictionary_from_bytes(gold)?),
+            silver: Arc::new(dictionary_from_bytes(silver)?),
+        })
+    }
+
+    /// Use these dictionaries for every English engine of this dialect
+    ///
+    /// Takes effect immediately, including for engines created earlier, and
+    /// replaces the embedded or previously installed dictionaries.
+    pub fn install(&self) {
+        *INSTALLED[self.british as usize]
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = Ok(self.clone());
+    }
+
+    /// Check if this lexicon is for British English
+    pub fn is_british(&self) -> bool {
+        self.british
+    }
+
+    /// The gold dictionary
+    pub fn gold(&self) -> &CompactDictionary {
+        &self.gold
+    }
+
+    /// The silver dictionary
+    pub fn silver(&self) -> &CompactDictionary {
+        &self.silver
+    }
+
+    /// Create a lexicon that consults a user lexicon before the installed dictionaries
     pub fn new_static(british: bool) -> LexiconRef {
         LexiconRef {
             british,
-            gold: if british { &GB_GOLD } else { &US_GOLD },
-            silver: if british { &GB_SILVER } else { &US_SILVER },
+            user: UserLexicon::new(),
         }
     }
 
+    /// Like [`Lexicon::new_static`], but fails if no dictionaries are available
+    pub fn try_new_static(british: bool) -> Result<LexiconRef, G2PError> {
+        Self::check_loaded(british)?;
+        Ok(Self::new_static(british))
+    }
+
+    /// Check that gold and silver dictionaries for a dialect are available
+    ///
+    /// Fails with [`G2PError::DictionaryNotLoaded`] when the crate was built
+    /// with the `external-dictionaries` feature and none have been installed,
+    /// or [`G2PError::DictionaryLoad`] if the embedded ones are corrupt.
+    pub fn check_loaded(british: bool) -> Result<(), G2PError> {
+        installed(british).as_ref().map(|_| ()).map_err(Clone::clone)
+    }
+
     /// Check if a word is in the lexicon
     pub fn contains(&self, word: &str) -> bool {
-        self.gold.contains_key(word) || self.silver.contains_key(word)
+        self.gold.contains(word) || self.silver.contains(word)
     }
 
     /// Look up a word's phonemes
     pub fn lookup(&self, word: &str, tag: Option<&str>) -> Option<(String, u8)> {
         // Try gold dictionary first
-        if let Some(entry) = self.gold.get(word) {
-            if let Some(ps) = entry.get(tag) {
-                return Some((ps.to_string(), 4));
-            }
+        if let Some(ps) = self.gold.get(word, tag) {
+            return Some((ps.to_string(), GOLD_RATING));
         }
         // Try silver dictionary
-        if let Some(entry) = self.silver.get(word) {
-            if let Some(ps) = entry.get(tag) {
-                return Some((ps.to_string(), 3));
-            }
+        if let Some(ps) = self.silver.get(word, tag) {
+            return Some((ps.to_string(), SILVER_RATING));
         }
         None
     }
@@ -429,32 +727,213 @@ fn is_doubled_consonant(word: &str) -> bool {
     c1 == c2 && matches!(c1, 'b' | 'c' | 'd' | 'g' | 'k' | 'l' | 'm' | 'n' | 'p' | 'r' | 's' | 't' | 'v' | 'x' | 'z')
 }
 
-/// Reference-based lexicon (more memory efficient)
+/// User-supplied pronunciations that take priority over the gold dictionary
+///
+/// Entries use the same format as the built-in dictionaries: a phoneme string,
+/// or a map from POS tag (and `DEFAULT`) to phonemes. Matches are rated
+/// [`USER_RATING`], above gold. Cloning a `UserLexicon` yields a handle to the
+/// same entries, so words added later are seen by every engine sharing it.
+#[derive(Clone, Default)]
+pub struct UserLexicon {
+    entries: Arc<RwLock<Dictionary>>,
+}
+
+impl UserLexicon {
+    /// Create an empty user lexicon
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a user lexicon from a JSON or TSV file
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, G2PError> {
+        let lexicon = Self::new();
+        lexicon.load_path(path)?;
+        Ok(lexicon)
+    }
+
+    /// Load entries from a file, returning the number of entries loaded
+    ///
+    /// Files ending in `.json` are parsed as JSON, anything else as TSV.
+    pub fn load_path<P: AsRef<Path>>(&self, path: P) -> Result<usize, G2PError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| G2PError::DictionaryLoad(format!("{}: {}", path.display(), e)))?;
+
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        if is_json {
+            self.load_json_str(&contents)
+        } else {
+            self.load_tsv_str(&contents)
+        }
+    }
+
+    /// Load entries from JSON in the built-in dictionary format
+    ///
+    /// ```json
+    /// {"Kokoro": "kəkˈɔɹO", "read": {"VBD": "ɹˈɛd", "DEFAULT": "ɹˈid"}}
+    /// ```
+    pub fn load_json_str(&self, json: &str) -> Result<usize, G2PError> {
+        let entries: Dictionary = serde_json::from_str(json)
+            .map_err(|e| G2PError::DictionaryLoad(format!("invalid user lexicon JSON: {}", e)))?;
+
+        let count = entries.len();
+        for (word, entry) in entries {
+            self.insert_entry(word, entry);
+        }
+        Ok(count)
+    }
+
+    /// Load entries from tab-separated `word<TAB>phonemes[<TAB>tag]` lines
+    ///
+    /// Blank lines and lines starting with `#` are ignored.
+    pub fn load_tsv_str(&self, tsv: &str) -> Result<usize, G2PError> {
+        let mut count = 0;
+        for (i, line) in tsv.lines().enumerate() {
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split('\t').map(str::trim).collect();
+            match fields.as_slice() {
+                [word, phonemes] if !word.is_empty() => self.insert(word, phonemes),
+                [word, phonemes, tag] if !word.is_empty() => self.insert_tagged(word, tag, phonemes),
+                _ => {
+                    return Err(G2PError::DictionaryLoad(format!(
+                        "user lexicon line {}: expected word<TAB>phonemes[<TAB>tag]",
+                        i + 1
+                    )))
+                }
+            }
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Add or replace the pronunciation of a word
+    pub fn insert(&self, word: &str, phonemes: &str) {
+        self.insert_entry(word.to_string(), PhonemeEntry::Simple(phonemes.to_string()));
+    }
+
+    /// Add the pronunciation of a word for a POS tag (e.g. "VBD", "NOUN", "DEFAULT")
+    pub fn insert_tagged(&self, word: &str, tag: &str, phonemes: &str) {
+        warn_invalid_phonemes(word, phonemes);
+        let mut entries = self.write();
+        let entry = entries
+            .entry(word.to_string())
+            .or_insert_with(|| PhonemeEntry::Tagged(HashMap::new()));
+
+        if let PhonemeEntry::Simple(default) = entry {
+            let mut map = HashMap::new();
+            map.insert("DEFAULT".to_string(), Some(std::mem::take(default)));
+            *entry = PhonemeEntry::Tagged(map);
+        }
+        if let PhonemeEntry::Tagged(map) = entry {
+            map.insert(tag.to_string(), Some(phonemes.to_string()));
+        }
+    }
+
+    fn insert_entry(&self, word: String, entry: PhonemeEntry) {
+        match &entry {
+            PhonemeEntry::Simple(phonemes) => warn_invalid_phonemes(&word, phonemes),
+            PhonemeEntry::Tagged(map) => {
+                for phonemes in map.values().flatten() {
+                    warn_invalid_phonemes(&word, phonemes);
+                }
+            }
+        }
+        self.write().insert(word, entry);
+    }
+
+    /// Remove a word, returning whether it was present
+    pub fn remove(&self, word: &str) -> bool {
+        self.write().remove(word).is_some()
+    }
+
+    /// Remove all entries
+    pub fn clear(&self) {
+        self.write().clear();
+    }
+
+    /// Number of words in the lexicon
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    /// Check if the lexicon has no entries
+    pub fn is_empty(&self) -> bool {
+        self.read().is_empty()
+    }
+
+    /// Check if a word has an entry (exact match)
+    pub fn contains(&self, word: &str) -> bool {
+        self.read().contains_key(word)
+    }
+
+    /// Look up a word, trying the exact spelling, then lowercase, then capitalized
+    pub fn lookup(&self, word: &str, tag: Option<&str>) -> Option<String> {
+        let entries = self.read();
+        let lower = word.to_lowercase();
+        [word, &lower, &capitalize(&lower)]
+            .iter()
+            .find_map(|w| entries.get(*w).and_then(|e| e.get(tag)))
+            .map(str::to_string)
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Dictionary> {
+        self.entries.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Dictionary> {
+        self.entries.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Warn about phonemes that have no token ID (they would be dropped)
+fn warn_invalid_phonemes(word: &str, phonemes: &str) {
+    if let Some(c) = phonemes.chars().find(|&c| !crate::tokenizer::is_valid_phoneme(c)) {
+        log::warn!("User lexicon entry '{}' contains unknown phoneme {:?}", word, c);
+    }
+}
+
+/// Lexicon over the installed dictionaries plus a user lexicon
+///
+/// The installed dictionaries are read on every lookup, so dictionaries
+/// installed after the engine was created are used right away.
 pub struct LexiconRef {
     british: bool,
-    gold: &'static Dictionary,
-    silver: &'static Dictionary,
+    user: UserLexicon,
 }
 
 impl LexiconRef {
+    /// Get the user lexicon consulted before the built-in dictionaries
+    pub fn user_lexicon(&self) -> &UserLexicon {
+        &self.user
+    }
+
+    /// Replace the user lexicon
+    pub fn set_user_lexicon(&mut self, user: UserLexicon) {
+        self.user = user;
+    }
+
     /// Look up a word's phonemes
     pub fn lookup(&self, word: &str, tag: Option<&str>) -> Option<(String, u8)> {
-        if let Some(entry) = self.gold.get(word) {
+        if let Some(entry) = self.user.read().get(word) {
             if let Some(ps) = entry.get(tag) {
-                return Some((ps.to_string(), 4));
+                return Some((ps.to_string(), USER_RATING));
             }
         }
-        if let Some(entry) = self.silver.get(word) {
-            if let Some(ps) = entry.get(tag) {
-                return Some((ps.to_string(), 3));
-            }
-        }
-        None
+        installed(self.british)
+            .as_ref()
+            .ok()
+            .and_then(|lexicon| lexicon.lookup(word, tag))
     }
 
     /// Check if a word is in the lexicon
     pub fn contains(&self, word: &str) -> bool {
-        self.gold.contains_key(word) || self.silver.contains_key(word)
+        self.user.contains(word) || installed(self.british).as_ref().is_ok_and(|lexicon| lexicon.contains(word))
     }
 
     /// Get phonemes for a word
@@ -606,51 +1085,32 @@ fn apply_ing_phoneme(stem: &str, british: bool) -> String {
 }
 
 /// Apply stress modification to phonemes
+///
+/// See [`crate::markup::adjust_stress`] for the meaning of the values.
 pub fn apply_stress(phonemes: &str, stress: Option<i8>) -> String {
-    let stress = match stress {
-        Some(s) => s,
-        None => return phonemes.to_string(),
-    };
+    match stress {
+        Some(stress) => crate::markup::adjust_stress(phonemes, stress),
+        None => phonemes.to_string(),
+    }
+}
 
-    if stress < -1 {
-        // Remove all stress
-        phonemes
-            .chars()
-            .filter(|&c| c != PRIMARY_STRESS && c != SECONDARY_STRESS)
-            .collect()
-    } else if stress == -1 || (stress == 0 && phonemes.contains(PRIMARY_STRESS)) {
-        // Demote primary to secondary
-        phonemes
-            .replace(SECONDARY_STRESS_STR, "")
-            .replace(PRIMARY_STRESS_STR, SECONDARY_STRESS_STR)
-    } else if (stress == 0 || stress == 1)
-        && !phonemes.contains(PRIMARY_STRESS)
-        && !phonemes.contains(SECONDARY_STRESS)
-    {
-        // Add secondary stress
-        if !phonemes.chars().any(|c| VOWELS.contains(c)) {
-            phonemes.to_string()
-        } else {
-            format!("{}{}", SECONDARY_STRESS, phonemes)
-        }
-    } else if stress >= 1
-        && !phonemes.contains(PRIMARY_STRESS)
-        && phonemes.contains(SECONDARY_STRESS)
-    {
-        // Promote secondary to primary
-        phonemes.replace(SECONDARY_STRESS_STR, PRIMARY_STRESS_STR)
-    } else if stress > 1
-        && !phonemes.contains(PRIMARY_STRESS)
-        && !phonemes.contains(SECONDARY_STRESS)
+/// Install the JSON dictionaries from the source tree for unit tests
+///
+/// Does nothing unless built with `external-dictionaries`, where no
+/// dictionaries are embedded.
+#[cfg(test)]
+pub(crate) fn install_test_dictionaries() {
+    #[cfg(feature = "external-dictionaries")]
     {
-        // Add primary stress
-        if !phonemes.chars().any(|c| VOWELS.contains(c)) {
-            phonemes.to_string()
-        } else {
-            format!("{}{}", PRIMARY_STRESS, phonemes)
-        }
-    } else {
-        phonemes.to_string()
+        static INSTALL: std::sync::Once = std::sync::Once::new();
+        INSTALL.call_once(|| {
+            let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("dictionaries");
+            for (british, dialect) in [(false, "us"), (true, "gb")] {
+                let gold = dir.join(format!("{}_gold.json", dialect));
+                let silver = dir.join(format!("{}_silver.json", dialect));
+                Lexicon::from_path(british, gold, silver).unwrap().install();
+            }
+        });
     }
 }
 
@@ -660,6 +1120,7 @@ mod tests {
 
     #[test]
     fn test_lexicon_lookup() {
+        install_test_dictionaries();
         let lex = Lexicon::new(false);
         let result = lex.get_word("hello", None);
         assert!(result.is_some());
@@ -670,6 +1131,7 @@ mod tests {
 
     #[test]
     fn test_lexicon_ref() {
+        install_test_dictionaries();
         let lex = Lexicon::new_static(false);
         let result = lex.get_word("world", None);
         assert!(result.is_some());
@@ -677,6 +1139,7 @@ mod tests {
 
     #[test]
     fn test_stemming_s() {
+        install_test_dictionaries();
         let lex = Lexicon::new(false);
         let result = lex.get_word("cats", None);
         assert!(result.is_some());
@@ -684,6 +1147,7 @@ mod tests {
 
     #[test]
     fn test_stemming_ed() {
+        install_test_dictionaries();
         let lex = Lexicon::new(false);
         let result = lex.get_word("walked", None);
         assert!(result.is_some());
@@ -691,6 +1155,7 @@ mod tests {
 
     #[test]
     fn test_stemming_ing() {
+        install_test_dictionaries();
         let lex = Lexicon::new(false);
         let result = lex.get_word("walking", None);
         assert!(result.is_some());
@@ -703,8 +1168,114 @@ mod tests {
         assert_eq!(apply_stress(ps, Some(2)), "ˈhɛlO");
     }
 
+    #[test]
+    fn test_user_lexicon_priority() {
+        let mut lex = Lexicon::new_static(false);
+        let user = UserLexicon::new();
+        lex.set_user_lexicon(user.clone());
+
+        // Added after the lexicon was created, and overrides gold
+        user.insert("hello", "hˈɛlO");
+        assert_eq!(lex.get_word("hello", None), Some(("hˈɛlO".to_string(), USER_RATING)));
+
+        user.insert("kokoro", "kəkˈɔɹO");
+        assert_eq!(lex.get_word("kokoros", None).unwrap().0, "kəkˈɔɹOz");
+    }
+
+    #[test]
+    fn test_user_lexicon_tsv() {
+        let user = UserLexicon::new();
+        let tsv = "# brand names\nKokoro\tkəkˈɔɹO\n\nread\tɹˈɛd\tVBD\n";
+        assert_eq!(user.load_tsv_str(tsv).unwrap(), 2);
+        assert_eq!(user.lookup("kokoro", None).unwrap(), "kəkˈɔɹO");
+        assert_eq!(user.lookup("read", Some("VBD")).unwrap(), "ɹˈɛd");
+        assert_eq!(user.lookup("read", None), None);
+
+        assert!(matches!(
+            user.load_tsv_str("missing phonemes"),
+            Err(G2PError::DictionaryLoad(_))
+        ));
+    }
+
+    #[test]
+    fn test_user_lexicon_json() {
+        let user = UserLexicon::new();
+        let json = r#"{"GitHub": "ɡˈɪthʌb", "lead": {"NN": "lˈɛd", "DEFAULT": "lˈid"}}"#;
+        assert_eq!(user.load_json_str(json).unwrap(), 2);
+        assert_eq!(user.lookup("GitHub", None).unwrap(), "ɡˈɪthʌb");
+        assert_eq!(user.lookup("lead", Some("NN")).unwrap(), "lˈɛd");
+        assert_eq!(user.lookup("lead", None).unwrap(), "lˈid");
+
+        user.insert_tagged("GitHub", "VB", "ɡˈɪt");
+        assert_eq!(user.lookup("GitHub", None).unwrap(), "ɡˈɪthʌb");
+        assert!(user.load_json_str("[1, 2]").is_err());
+    }
+
+    #[test]
+    fn test_check_loaded() {
+        install_test_dictionaries();
+        assert!(Lexicon::check_loaded(false).is_ok());
+        assert!(Lexicon::try_new_static(true).is_ok());
+    }
+
+    #[test]
+    fn test_load_invalid_dictionary() {
+        let err = CompactDictionary::from_static(b"not a dictionary").unwrap_err();
+        assert!(matches!(err, G2PError::DictionaryLoad(_)));
+
+        let mut truncated = CompactDictionary::compile_json(r#"{"hello": "həlˈO"}"#).unwrap();
+        truncated.pop();
+        assert!(CompactDictionary::from_vec(truncated).is_err());
+    }
+
+    #[test]
+    fn test_compact_dictionary() {
+        let json = r#"{
+            "hello": "həlˈO",
+            "Paris": "pˈæɹɪs",
+            "a": "ɐ",
+            "read": {"VBD": "ɹˈɛd", "DEFAULT": "ɹˈid"},
+            "use": {"NOUN": "jˈus", "VERB": "jˈuz", "None": null}
+        }"#;
+        let dict = CompactDictionary::from_vec(CompactDictionary::compile_json(json).unwrap()).unwrap();
+        assert_eq!(dict.len(), 5);
+        assert_eq!(dict.get("hello", None), Some("həlˈO"));
+        assert_eq!(dict.get("read", Some("VBD")), Some("ɹˈɛd"));
+        assert_eq!(dict.get("read", Some("NN")), Some("ɹˈid"));
+        assert_eq!(dict.get("use", Some("VBZ")), Some("jˈuz"));
+        assert_eq!(dict.get("use", None), None);
+        assert_eq!(dict.get("missing", None), None);
+
+        // Case variants are resolved at lookup time
+        assert_eq!(dict.get("Hello", None), Some("həlˈO"));
+        assert_eq!(dict.get("paris", None), Some("pˈæɹɪs"));
+        assert!(!dict.contains("HELLO"));
+        assert!(!dict.contains("A"));
+
+        let path = std::env::temp_dir().join(format!("kokoro_g2p_dict_{}.lex", std::process::id()));
+        std::fs::write(&path, CompactDictionary::compile_json(json).unwrap()).unwrap();
+        let opened = CompactDictionary::open(&path).unwrap();
+        assert_eq!(opened.get("read", Some("VBD")), Some("ɹˈɛd"));
+        drop(opened);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_lexicon_from_bytes() {
+        let gold = r#"{"kokoro": "kəkˈɔɹO"}"#.as_bytes().to_vec();
+        let silver = CompactDictionary::compile_json(r#"{"tts": "tˌitˌiˈɛs"}"#).unwrap();
+        let lex = Lexicon::from_bytes(false, gold, silver).unwrap();
+        assert_eq!(lex.lookup("Kokoro", None), Some(("kəkˈɔɹO".to_string(), GOLD_RATING)));
+        assert_eq!(lex.lookup("tts", None).unwrap().1, SILVER_RATING);
+        assert_eq!(lex.get_word("hello", None), None);
+
+        assert!(Lexicon::from_bytes(false, b"\xff".to_vec(), Vec::new()).is_err());
+        assert!(Lexicon::from_path(false, "missing.json", "missing.lex").is_err());
+    }
+
     #[test]
     fn test_case_insensitive() {
+        install_test_dictionaries();
         let lex = Lexicon::new(false);
         let lower = lex.get_word("hello", None);
         let upper = lex.get_word("HELLO", None);
@@ -0,0 +1,118 @@
+//! Binary layout of compiled pronunciation dictionaries
+//!
+//! This file is also included by `build.rs`, which compiles the JSON
+//! dictionaries into this format at build time, so it must not depend on
+//! the rest of the crate.
+//!
+//! All integers are little-endian `u32`. A dictionary is a header followed by
+//! six sections:
+//!
+//! ```text
+//! magic "KLEX", version, entry_count, string_count, tagged_len, words_len, strings_len
+//! word_offsets    [entry_count + 1]   start of each word in `words`
+//! values          [entry_count]       string index, or TAGGED | offset into `tagged`
+//! string_offsets  [string_count + 1]  start of each string in `strings`
+//! tagged          [tagged_len]        per entry: pair count, then (tag, phonemes) string indices
+//! words           [words_len]         UTF-8 words, sorted bytewise, concatenated
+//! strings         [strings_len]       interned UTF-8 phoneme strings and tag names
+//! ```
+//!
+//! Words are looked up by binary search directly in the bytes. A phoneme
+//! index of [`NONE`] marks a tag whose pronunciation is `null`.
+
+use std::collections::{BTreeMap, HashMap};
+
+/// File signature
+pub const MAGIC: &[u8; 4] = b"KLEX";
+/// Format version
+pub const VERSION: u32 = 1;
+/// Size of the header in bytes
+pub const HEADER_LEN: usize = 28;
+/// Flag in `values` marking a tag-dependent entry
+pub const TAGGED: u32 = 1 << 31;
+/// String index of a missing pronunciation
+pub const NONE: u32 = u32::MAX;
+
+/// A dictionary entry before compilation
+#[derive(Debug, Clone, PartialEq)]
+pub enum RawEntry {
+    /// The same phonemes for every POS tag
+    Simple(String),
+    /// Phonemes per POS tag (`DEFAULT` for the fallback), `None` for no pronunciation
+    Tagged(Vec<(String, Option<String>)>),
+}
+
+/// Interns strings, assigning each distinct string an index
+#[derive(Default)]
+struct Interner {
+    indices: HashMap<String, u32>,
+    offsets: Vec<u32>,
+    bytes: Vec<u8>,
+}
+
+impl Interner {
+    fn intern(&mut self, s: &str) -> u32 {
+        if let Some(&index) = self.indices.get(s) {
+            return index;
+        }
+        let index = self.offsets.len() as u32;
+        self.offsets.push(self.bytes.len() as u32);
+        self.bytes.extend_from_slice(s.as_bytes());
+        self.indices.insert(s.to_string(), index);
+        index
+    }
+}
+
+/// Compile entries into the binary format
+pub fn encode(entries: &BTreeMap<String, RawEntry>) -> Vec<u8> {
+    let mut strings = Interner::default();
+    let mut word_offsets = Vec::with_capacity(entries.len() + 1);
+    let mut values = Vec::with_capacity(entries.len());
+    let mut tagged: Vec<u32> = Vec::new();
+    let mut words = Vec::new();
+
+    for (word, entry) in entries {
+        word_offsets.push(words.len() as u32);
+        words.extend_from_slice(word.as_bytes());
+        let value = match entry {
+            RawEntry::Simple(phonemes) => strings.intern(phonemes),
+            RawEntry::Tagged(pairs) => {
+                let offset = tagged.len() as u32;
+                tagged.push(pairs.len() as u32);
+                for (tag, phonemes) in pairs {
+                    tagged.push(strings.intern(tag));
+                    tagged.push(phonemes.as_deref().map_or(NONE, |p| strings.intern(p)));
+                }
+                TAGGED | offset
+            }
+        };
+        values.push(value);
+    }
+    word_offsets.push(words.len() as u32);
+    strings.offsets.push(strings.bytes.len() as u32);
+
+    let mut out = Vec::with_capacity(
+        HEADER_LEN + 4 * (word_offsets.len() + values.len() + strings.offsets.len() + tagged.len())
+            + words.len()
+            + strings.bytes.len(),
+    );
+    out.extend_from_slice(MAGIC);
+    for n in [
+        VERSION,
+        values.len() as u32,
+        strings.offsets.len() as u32 - 1,
+        tagged.len() as u32,
+        words.len() as u32,
+        strings.bytes.len() as u32,
+    ] {
+        out.extend_from_slice(&n.to_le_bytes());
+    }
+    for section in [&word_offsets, &values, &strings.offsets, &tagged] {
+        for n in section {
+            out.extend_from_slice(&n.to_le_bytes());
+        }
+    }
+    out.extend_from_slice(&words);
+    out.extend_from_slice(&strings.bytes);
+    out
+}
@@ -39,10 +39,20 @@
 #[cfg(feature = "english")]
 pub mod g2p;
 #[cfg(feature = "english")]
+pub mod letter_to_sound;
+#[cfg(feature = "english")]
 pub mod lexicon;
 #[cfg(feature = "english")]
+mod lexicon_format;
+#[cfg(feature = "english")]
 pub mod preprocessor;
+#[cfg(feature = "english")]
+pub mod tagger;
 
+pub mod alignment;
+pub mod engine;
+pub mod error;
+pub mod markup;
 pub mod tokenizer;
 
 #[cfg(feature = "chinese")]
@@ -75,7 +85,10 @@ pub mod ko;
 #[cfg(feature = "vietnamese")]
 pub mod vi;
 
+pub mod langid;
 pub mod pipeline;
+pub mod script;
+pub mod ssml;
 
 /// Safely truncate a string at character boundary (not byte boundary)
 /// Returns a substring containing at most `max_bytes` bytes, ending at a valid char boundary
@@ -94,8 +107,13 @@ pub fn safe_truncate(s: &str, max_bytes: usize) -> &str {
 // Re-export main functions
 #[cfg(feature = "english")]
 pub use g2p::{text_to_phoneme_string as text_to_phonemes_en, G2P};
-pub use tokenizer::{phonemes_to_tokens, tokens_to_phonemes, MAX_TOKENS, PAD_TOKEN};
-pub use pipeline::KPipeline;
+#[cfg(feature = "english")]
+pub use lexicon::{Lexicon, UserLexicon};
+pub use tokenizer::{phonemes_to_tokens, tokens_to_phonemes, CoverageReport, Vocabulary, MAX_TOKENS, PAD_TOKEN};
+pub use alignment::{Alignment, WordSegment};
+pub use engine::{EngineCapabilities, EngineRegistry, G2PEngine};
+pub use error::G2PError;
+pub use pipeline::{enabled_features, InputMode, KPipeline, Language};
 
 /// Convert text to token IDs with automatic language detection or explicit language
 ///
@@ -107,6 +125,7 @@ pub use pipeline::KPipeline;
 /// - `"id"`, `"indonesian"` - Indonesian (requires `indonesian` feature)
 /// - `"tr"`, `"turkish"` - Turkish (requires `turkish` feature)
 /// - `"it"`, `"italian"` - Italian (requires `italian` feature)
+/// - `"auto"` - detect the language among the compiled-in ones (see [`langid`])
 pub fn text_to_tokens(text: &str, language: &str) -> Vec<i64> {
     let lang_lower = language.to_lowercase();
 
@@ -159,6 +178,10 @@ pub fn text_to_tokens(text: &str, language: &str) -> Vec<i64> {
         "vi" | "vietnamese" | "tiếng việt" => {
             vi::text_to_tokens(text)
         }
+        "auto" => match langid::detect(text) {
+            Some(detection) => text_to_tokens(text, detection.language.code()),
+            None => vec![PAD_TOKEN, PAD_TOKEN],
+        },
         #[cfg(feature = "english")]
         _ => {
             g2p::text_to_tokens(text, language)
@@ -185,10 +208,11 @@ pub fn text_to_tokens(text: &str, language: &str) -> Vec<i64> {
 /// - `"pt"`, `"portuguese"` - Portuguese (requires `portuguese` feature)
 /// - `"ko"`, `"korean"` - Korean (requires `korean` feature)
 /// - `"vi"`, `"vietnamese"` - Vietnamese (requires `vietnamese` feature)
+/// - `"auto"` - detect the language among the compiled-in ones (see [`langid`])
 pub fn text_to_phonemes(text: &str, language: &str) -> String {
     let lang_lower = language.to_lowercase();
 
-    match lang_lower.as_str() {
+    let phonemes = match lang_lower.as_str() {
         #[cfg(feature = "chinese")]
         "zh" | "zh-cn" | "chinese" | "mandarin" | "cmn" => {
             zh::text_to_phonemes(text)
@@ -229,6 +253,12 @@ pub fn text_to_phonemes(text: &str, language: &str) -> String {
         "vi" | "vietnamese" | "tiếng việt" => {
             vi::text_to_phonemes(text)
         }
+        "auto" => {
+            return match langid::detect(text) {
+                Some(detection) => text_to_phonemes(text, detection.language.code()),
+                None => String::new(),
+            }
+        }
         #[cfg(feature = "english")]
         _ => {
             g2p::text_to_phoneme_string(text, language)
@@ -238,9 +268,119 @@ pub fn text_to_phonemes(text: &str, language: &str) -> String {
             log::warn!("Language '{}' not supported without 'english' feature", language);
             String::new()
         }
+    };
+
+    // Same fallbacks as KPipeline, so both APIs produce mappable phonemes
+    match Language::parse(&lang_lower) {
+        Some(lang) => tokenizer::apply_fallbacks(lang.code(), &phonemes),
+        None => phonemes,
     }
 }
 
+/// Convert text to token IDs, reporting why conversion failed
+///
+/// Unlike [`text_to_tokens`], unknown language codes, disabled features,
+/// dictionary load failures, unmappable phonemes and over-long input are
+/// returned as a [`G2PError`] instead of producing empty or truncated output.
+pub fn try_text_to_tokens(text: &str, language: &str) -> Result<Vec<i64>, G2PError> {
+    Ok(KPipeline::try_new(language)?.try_process(text)?.tokens)
+}
+
+/// Convert text to phoneme string, reporting why conversion failed
+///
+/// See [`try_text_to_tokens`] for the errors reported.
+pub fn try_text_to_phonemes(text: &str, language: &str) -> Result<String, G2PError> {
+    Ok(KPipeline::try_new(language)?.try_process(text)?.phonemes)
+}
+
+/// Convert text to token IDs for a model with its own vocabulary, reporting why conversion failed
+///
+/// See [`Vocabulary::from_config_path`] for loading a model's vocabulary.
+pub fn try_text_to_tokens_with_vocabulary<V: Into<std::sync::Arc<Vocabulary>>>(
+    text: &str,
+    language: &str,
+    vocabulary: V,
+) -> Result<Vec<i64>, G2PError> {
+    let mut pipeline = KPipeline::try_new(language)?;
+    pipeline.set_vocabulary(vocabulary);
+    Ok(pipeline.try_process(text)?.tokens)
+}
+
+/// Parse an English dialect code into whether it is British
+fn english_dialect(language: &str) -> Result<bool, G2PError> {
+    let british = match Language::try_from_str(language)? {
+        Language::EnglishUS => false,
+        Language::EnglishGB => true,
+        _ => return Err(G2PError::InvalidInput(format!("'{}' is not an English dialect", language))),
+    };
+    if cfg!(feature = "english") {
+        Ok(british)
+    } else {
+        Err(G2PError::FeatureNotEnabled {
+            language: language.to_string(),
+            feature: "english",
+        })
+    }
+}
+
+/// Load gold and silver dictionaries for an English dialect from files and install them
+///
+/// `language` is `"en-us"` or `"en-gb"`; see [`Lexicon::from_path`] for the
+/// file formats. Required before converting English text when built with the
+/// `external-dictionaries` feature, and replaces the embedded dictionaries
+/// otherwise.
+pub fn load_english_dictionaries<P: AsRef<std::path::Path>, Q: AsRef<std::path::Path>>(
+    language: &str,
+    gold: P,
+    silver: Q,
+) -> Result<(), G2PError> {
+    let british = english_dialect(language)?;
+    #[cfg(feature = "english")]
+    Lexicon::from_path(british, gold, silver)?.install();
+    #[cfg(not(feature = "english"))]
+    let _ = (british, gold.as_ref(), silver.as_ref());
+    Ok(())
+}
+
+/// Like [`load_english_dictionaries`], from JSON or compiled dictionary bytes
+pub fn load_english_dictionaries_from_bytes(language: &str, gold: Vec<u8>, silver: Vec<u8>) -> Result<(), G2PError> {
+    let british = english_dialect(language)?;
+    #[cfg(feature = "english")]
+    Lexicon::from_bytes(british, gold, silver)?.install();
+    #[cfg(not(feature = "english"))]
+    let _ = (british, gold, silver);
+    Ok(())
+}
+
+/// Check that dictionaries are available for an English dialect
+///
+/// Fails with [`G2PError::DictionaryNotLoaded`] until
+/// [`load_english_dictionaries`] has been called in an
+/// `external-dictionaries` build.
+pub fn check_english_dictionaries(language: &str) -> Result<(), G2PError> {
+    let british = english_dialect(language)?;
+    #[cfg(feature = "english")]
+    return Lexicon::check_loaded(british);
+    #[cfg(not(feature = "english"))]
+    {
+        let _ = british;
+        Ok(())
+    }
+}
+
+/// Convert an SSML document to a phoneme string
+///
+/// `language` is used for text outside `<lang>` elements. See [`ssml`] for
+/// the supported markup.
+pub fn ssml_to_phonemes(ssml: &str, language: &str) -> String {
+    KPipeline::new(language).process_ssml(ssml).phonemes
+}
+
+/// Convert an SSML document to token IDs
+pub fn ssml_to_tokens(ssml: &str, language: &str) -> Vec<i64> {
+    KPipeline::new(language).process_ssml(ssml).tokens
+}
+
 /// Library version
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -250,10 +390,10 @@ pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[cfg(feature = "jni")]
 mod jni_interface {
-    use jni::objects::{JClass, JString};
-    use jni::sys::jlongArray;
+    use jni::objects::{JByteArray, JClass, JString};
+    use jni::sys::{jboolean, jlong, jlongArray};
     use jni::JNIEnv;
-    use std::sync::Once;
+    use std::sync::{Arc, Once};
     use crate::safe_truncate;
 
     static INIT: Once = Once::new();
@@ -365,49 +505,257 @@ mod jni_interface {
         }
     }
 
-    /// JNI entry point for checking enabled features (diagnostic)
+    /// Throw a Java exception describing a G2P error
+    fn throw_g2p_error(env: &mut JNIEnv, err: &crate::G2PError) {
+        let class = match err {
+            crate::G2PError::UnsupportedLanguage(_)
+            | crate::G2PError::FeatureNotEnabled { .. }
+            | crate::G2PError::InvalidInput(_)
+            | crate::G2PError::InvalidVocabulary(_) => "java/lang/IllegalArgumentException",
+            _ => "java/lang/IllegalStateException",
+        };
+        let message = format!("G2PError {}: {}", err.code(), err);
+        if let Err(e) = env.throw_new(class, message) {
+            log::error!("Failed to throw Java exception: {:?}", e);
+        }
+    }
+
+    /// Read the text and language arguments, throwing on failure
+    fn get_args(env: &mut JNIEnv, text: &JString, language: &JString) -> Option<(String, String)> {
+        let args = env.get_string(text).map(String::from).and_then(|text| {
+            env.get_string(language).map(|language| (text, String::from(language)))
+        });
+        match args {
+            Ok(args) => Some(args),
+            Err(e) => {
+                throw_g2p_error(env, &crate::G2PError::InvalidInput(e.to_string()));
+                None
+            }
+        }
+    }
+
+    /// JNI entry point for tokenization that throws on failure
+    ///
+    /// Throws `IllegalArgumentException` for unsupported languages and invalid
+    /// input, and `IllegalStateException` for conversion failures.
     #[no_mangle]
-    pub extern "system" fn Java_com_openvoice_app_engine_KokoroTokenizer_getEnabledFeatures<'a>(
+    pub extern "system" fn Java_com_openvoice_app_engine_KokoroTokenizer_tryTokenizeWithLanguage(
+        mut env: JNIEnv,
+        _class: JClass,
+        text: JString,
+        language: JString,
+    ) -> jlongArray {
+        init_logger();
+
+        let (text, language) = match get_args(&mut env, &text, &language) {
+            Some(args) => args,
+            None => return std::ptr::null_mut(),
+        };
+
+        let result = std::panic::catch_unwind(|| crate::try_text_to_tokens(&text, &language))
+            .unwrap_or_else(|_| Err(crate::G2PError::InvalidInput("panic during tokenization".to_string())));
+
+        match result {
+            Ok(tokens) => match env.new_long_array(tokens.len() as i32) {
+                Ok(output) => {
+                    let _ = env.set_long_array_region(&output, 0, &tokens);
+                    output.into_raw()
+                }
+                Err(e) => {
+                    log::error!("Failed to create output array: {:?}", e);
+                    std::ptr::null_mut()
+                }
+            },
+            Err(err) => {
+                throw_g2p_error(&mut env, &err);
+                std::ptr::null_mut()
+            }
+        }
+    }
+
+    /// JNI entry point for phonemization that throws on failure
+    #[no_mangle]
+    pub extern "system" fn Java_com_openvoice_app_engine_KokoroTokenizer_tryTextToPhonemes<'a>(
         mut env: JNIEnv<'a>,
         _class: JClass,
+        text: JString,
+        language: JString,
     ) -> JString<'a> {
         init_logger();
 
-        let mut features = Vec::new();
+        let (text, language) = match get_args(&mut env, &text, &language) {
+            Some(args) => args,
+            None => return JString::default(),
+        };
+
+        match crate::try_text_to_phonemes(&text, &language) {
+            Ok(phonemes) => env.new_string(phonemes).unwrap_or_default(),
+            Err(err) => {
+                throw_g2p_error(&mut env, &err);
+                JString::default()
+            }
+        }
+    }
+
+    /// JNI entry point for loading a model vocabulary from its `config.json`
+    ///
+    /// Returns a handle for `tryTokenizeWithVocabulary`, which must be released
+    /// with `freeVocabulary`. Throws `IllegalArgumentException` on failure.
+    #[no_mangle]
+    pub extern "system" fn Java_com_openvoice_app_engine_KokoroTokenizer_loadVocabulary(
+        mut env: JNIEnv,
+        _class: JClass,
+        path: JString,
+    ) -> jlong {
+        init_logger();
+
+        let result = env
+            .get_string(&path)
+            .map_err(|e| crate::G2PError::InvalidInput(e.to_string()))
+            .and_then(|path| crate::Vocabulary::from_config_path(String::from(path)));
 
-        #[cfg(feature = "english")]
-        features.push("english");
+        match result {
+            Ok(vocabulary) => Arc::into_raw(Arc::new(vocabulary)) as jlong,
+            Err(err) => {
+                throw_g2p_error(&mut env, &err);
+                0
+            }
+        }
+    }
 
-        #[cfg(feature = "chinese")]
-        features.push("chinese");
+    /// JNI entry point for releasing a vocabulary handle
+    #[no_mangle]
+    pub extern "system" fn Java_com_openvoice_app_engine_KokoroTokenizer_freeVocabulary(
+        _env: JNIEnv,
+        _class: JClass,
+        handle: jlong,
+    ) {
+        // SAFETY: non-zero handles come from `loadVocabulary`
+        unsafe { crate::kokoro_vocabulary_free(handle as *const crate::Vocabulary) }
+    }
 
-        #[cfg(feature = "japanese")]
-        features.push("japanese");
+    /// JNI entry point for tokenization with a model vocabulary that throws on failure
+    ///
+    /// A handle of 0 selects the built-in vocabulary.
+    #[no_mangle]
+    pub extern "system" fn Java_com_openvoice_app_engine_KokoroTokenizer_tryTokenizeWithVocabulary(
+        mut env: JNIEnv,
+        _class: JClass,
+        text: JString,
+        language: JString,
+        handle: jlong,
+    ) -> jlongArray {
+        init_logger();
 
-        #[cfg(feature = "spanish")]
-        features.push("spanish");
+        let (text, language) = match get_args(&mut env, &text, &language) {
+            Some(args) => args,
+            None => return std::ptr::null_mut(),
+        };
+        // SAFETY: non-zero handles come from `loadVocabulary` and have not been freed
+        let vocabulary = unsafe { crate::vocabulary_arg(handle as *const crate::Vocabulary) };
+
+        match crate::try_text_to_tokens_with_vocabulary(&text, &language, vocabulary) {
+            Ok(tokens) => match env.new_long_array(tokens.len() as i32) {
+                Ok(output) => {
+                    let _ = env.set_long_array_region(&output, 0, &tokens);
+                    output.into_raw()
+                }
+                Err(e) => {
+                    log::error!("Failed to create output array: {:?}", e);
+                    std::ptr::null_mut()
+                }
+            },
+            Err(err) => {
+                throw_g2p_error(&mut env, &err);
+                std::ptr::null_mut()
+            }
+        }
+    }
 
-        #[cfg(feature = "indonesian")]
-        features.push("indonesian");
+    /// JNI entry point for loading and installing English dictionaries from files
+    ///
+    /// Throws `IllegalArgumentException` for a bad language or path, and
+    /// `IllegalStateException` if a file cannot be loaded.
+    #[no_mangle]
+    pub extern "system" fn Java_com_openvoice_app_engine_KokoroTokenizer_loadEnglishDictionaries(
+        mut env: JNIEnv,
+        _class: JClass,
+        language: JString,
+        gold_path: JString,
+        silver_path: JString,
+    ) {
+        init_logger();
 
-        #[cfg(feature = "turkish")]
-        features.push("turkish");
+        let args = [&language, &gold_path, &silver_path]
+            .into_iter()
+            .map(|s| env.get_string(s).map(String::from))
+            .collect::<Result<Vec<String>, _>>()
+            .map_err(|e| crate::G2PError::InvalidInput(e.to_string()));
+        let result = args.and_then(|args| crate::load_english_dictionaries(&args[0], &args[1], &args[2]));
 
-        #[cfg(feature = "italian")]
-        features.push("italian");
+        if let Err(err) = result {
+            throw_g2p_error(&mut env, &err);
+        }
+    }
 
-        #[cfg(feature = "german")]
-        features.push("german");
+    /// JNI entry point for loading and installing English dictionaries from memory
+    ///
+    /// Each array holds JSON or a compiled dictionary.
+    #[no_mangle]
+    pub extern "system" fn Java_com_openvoice_app_engine_KokoroTokenizer_loadEnglishDictionariesFromBytes(
+        mut env: JNIEnv,
+        _class: JClass,
+        language: JString,
+        gold: JByteArray,
+        silver: JByteArray,
+    ) {
+        init_logger();
 
-        #[cfg(feature = "portuguese")]
-        features.push("portuguese");
+        let args = env.get_string(&language).map(String::from).and_then(|language| {
+            Ok((language, env.convert_byte_array(&gold)?, env.convert_byte_array(&silver)?))
+        });
+        let result = args
+            .map_err(|e| crate::G2PError::InvalidInput(e.to_string()))
+            .and_then(|(language, gold, silver)| crate::load_english_dictionaries_from_bytes(&language, gold, silver));
 
-        #[cfg(feature = "korean")]
-        features.push("korean");
+        if let Err(err) = result {
+            throw_g2p_error(&mut env, &err);
+        }
+    }
 
-        #[cfg(feature = "vietnamese")]
-        features.push("vietnamese");
+    /// JNI entry point for checking whether English dictionaries are available
+    ///
+    /// Returns false when none have been loaded yet; the reason is logged.
+    #[no_mangle]
+    pub extern "system" fn Java_com_openvoice_app_engine_KokoroTokenizer_hasEnglishDictionaries(
+        mut env: JNIEnv,
+        _class: JClass,
+        language: JString,
+    ) -> jboolean {
+        init_logger();
+
+        let result = env
+            .get_string(&language)
+            .map_err(|e| crate::G2PError::InvalidInput(e.to_string()))
+            .and_then(|language| crate::check_english_dictionaries(&String::from(language)));
+        match result {
+            Ok(()) => 1,
+            Err(err) => {
+                log::info!("English dictionaries unavailable: {}", err);
+                0
+            }
+        }
+    }
+
+    /// JNI entry point for checking enabled features (diagnostic)
+    #[no_mangle]
+    pub extern "system" fn Java_com_openvoice_app_engine_KokoroTokenizer_getEnabledFeatures<'a>(
+        mut env: JNIEnv<'a>,
+        _class: JClass,
+    ) -> JString<'a> {
+        init_logger();
 
+        let features = crate::pipeline::enabled_features();
         let result = features.join(",");
         log::info!("Enabled features: {}", &result);
         match env.new_string(&result) {
@@ -577,6 +925,291 @@ pub unsafe extern "C" fn kokoro_free_string(s: *mut std::ffi::c_char) {
     }
 }
 
+thread_local! {
+    static LAST_ERROR: std::cell::RefCell<Option<std::ffi::CString>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Record the error for `kokoro_last_error_message` and return its code
+fn set_last_error(err: G2PError) -> i32 {
+    let message = std::ffi::CString::new(err.to_string()).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+    err.code()
+}
+
+/// Read a C string argument as UTF-8
+unsafe fn c_str_arg<'a>(s: *const std::ffi::c_char, name: &str) -> Result<&'a str, G2PError> {
+    if s.is_null() {
+        return Err(G2PError::InvalidInput(format!("{} is null", name)));
+    }
+    std::ffi::CStr::from_ptr(s)
+        .to_str()
+        .map_err(|_| G2PError::InvalidInput(format!("{} is not valid UTF-8", name)))
+}
+
+/// Convert text to tokens, reporting errors (C API)
+///
+/// Returns 0 on success and fills `out`, which must then be freed using
+/// `kokoro_free_tokens`. On failure returns the `G2PError` code, leaves `out`
+/// untouched, and the message is available from `kokoro_last_error_message`.
+///
+/// # Safety
+///
+/// The caller must ensure that:
+/// - `text` and `language` are valid null-terminated strings
+/// - `out` points to a writable `CTokenArray`
+#[no_mangle]
+pub unsafe extern "C" fn kokoro_try_text_to_tokens(
+    text: *const std::ffi::c_char,
+    language: *const std::ffi::c_char,
+    out: *mut CTokenArray,
+) -> i32 {
+    if out.is_null() {
+        return set_last_error(G2PError::InvalidInput("out is null".to_string()));
+    }
+
+    let result = c_str_arg(text, "text")
+        .and_then(|text| Ok((text, c_str_arg(language, "language")?)))
+        .and_then(|(text, language)| try_text_to_tokens(text, language));
+
+    match result {
+        Ok(mut tokens) => {
+            *out = CTokenArray {
+                data: tokens.as_mut_ptr(),
+                len: tokens.len(),
+                capacity: tokens.capacity(),
+            };
+            std::mem::forget(tokens);
+            0
+        }
+        Err(err) => set_last_error(err),
+    }
+}
+
+/// Get phoneme string, reporting errors (C API)
+///
+/// Returns 0 on success and stores a string in `out`, which must then be freed
+/// using `kokoro_free_string`. On failure returns the `G2PError` code and the
+/// message is available from `kokoro_last_error_message`.
+///
+/// # Safety
+///
+/// The caller must ensure that:
+/// - `text` and `language` are valid null-terminated strings
+/// - `out` points to a writable `char*`
+#[no_mangle]
+pub unsafe extern "C" fn kokoro_try_text_to_phonemes(
+    text: *const std::ffi::c_char,
+    language: *const std::ffi::c_char,
+    out: *mut *mut std::ffi::c_char,
+) -> i32 {
+    if out.is_null() {
+        return set_last_error(G2PError::InvalidInput("out is null".to_string()));
+    }
+
+    let result = c_str_arg(text, "text")
+        .and_then(|text| Ok((text, c_str_arg(language, "language")?)))
+        .and_then(|(text, language)| try_text_to_phonemes(text, language))
+        .and_then(|phonemes| {
+            std::ffi::CString::new(phonemes)
+                .map_err(|_| G2PError::InvalidInput("phonemes contain a null byte".to_string()))
+        });
+
+    match result {
+        Ok(phonemes) => {
+            *out = phonemes.into_raw();
+            0
+        }
+        Err(err) => set_last_error(err),
+    }
+}
+
+/// Load a model vocabulary from its `config.json` file (C API)
+///
+/// Returns 0 on success and stores a handle in `out`, which must be freed
+/// using `kokoro_vocabulary_free`. On failure returns the `G2PError` code and
+/// the message is available from `kokoro_last_error_message`.
+///
+/// # Safety
+///
+/// The caller must ensure that:
+/// - `path` is a valid null-terminated string
+/// - `out` points to a writable pointer
+#[no_mangle]
+pub unsafe extern "C" fn kokoro_vocabulary_load(
+    path: *const std::ffi::c_char,
+    out: *mut *const Vocabulary,
+) -> i32 {
+    if out.is_null() {
+        return set_last_error(G2PError::InvalidInput("out is null".to_string()));
+    }
+
+    match c_str_arg(path, "path").and_then(Vocabulary::from_config_path) {
+        Ok(vocabulary) => {
+            *out = std::sync::Arc::into_raw(std::sync::Arc::new(vocabulary));
+            0
+        }
+        Err(err) => set_last_error(err),
+    }
+}
+
+/// Free a vocabulary loaded by `kokoro_vocabulary_load`
+///
+/// # Safety
+///
+/// The caller must ensure that `vocabulary` was returned by
+/// `kokoro_vocabulary_load` and has not been freed before.
+#[no_mangle]
+pub unsafe extern "C" fn kokoro_vocabulary_free(vocabulary: *const Vocabulary) {
+    if !vocabulary.is_null() {
+        drop(std::sync::Arc::from_raw(vocabulary));
+    }
+}
+
+/// Take a new reference to a vocabulary handle, or the built-in vocabulary for null
+unsafe fn vocabulary_arg(vocabulary: *const Vocabulary) -> std::sync::Arc<Vocabulary> {
+    if vocabulary.is_null() {
+        return Vocabulary::shared_builtin();
+    }
+    std::sync::Arc::increment_strong_count(vocabulary);
+    std::sync::Arc::from_raw(vocabulary)
+}
+
+/// Convert text to tokens with a model vocabulary, reporting errors (C API)
+///
+/// Behaves like `kokoro_try_text_to_tokens`; a null `vocabulary` selects the
+/// built-in vocabulary.
+///
+/// # Safety
+///
+/// The caller must ensure that:
+/// - `text` and `language` are valid null-terminated strings
+/// - `vocabulary` is null or was returned by `kokoro_vocabulary_load` and not freed
+/// - `out` points to a writable `CTokenArray`
+#[no_mangle]
+pub unsafe extern "C" fn kokoro_try_text_to_tokens_with_vocabulary(
+    text: *const std::ffi::c_char,
+    language: *const std::ffi::c_char,
+    vocabulary: *const Vocabulary,
+    out: *mut CTokenArray,
+) -> i32 {
+    if out.is_null() {
+        return set_last_error(G2PError::InvalidInput("out is null".to_string()));
+    }
+
+    let result = c_str_arg(text, "text")
+        .and_then(|text| Ok((text, c_str_arg(language, "language")?)))
+        .and_then(|(text, language)| {
+            try_text_to_tokens_with_vocabulary(text, language, vocabulary_arg(vocabulary))
+        });
+
+    match result {
+        Ok(mut tokens) => {
+            *out = CTokenArray {
+                data: tokens.as_mut_ptr(),
+                len: tokens.len(),
+                capacity: tokens.capacity(),
+            };
+            std::mem::forget(tokens);
+            0
+        }
+        Err(err) => set_last_error(err),
+    }
+}
+
+/// Read a byte buffer argument
+unsafe fn bytes_arg(data: *const u8, len: usize, name: &str) -> Result<Vec<u8>, G2PError> {
+    if data.is_null() {
+        return Err(G2PError::InvalidInput(format!("{} is null", name)));
+    }
+    Ok(std::slice::from_raw_parts(data, len).to_vec())
+}
+
+/// Load and install English dictionaries from files (C API)
+///
+/// `language` is "en-us" or "en-gb". Files ending in `.json` use the JSON
+/// dictionary format, anything else the compiled format. Returns 0 on success,
+/// or the `G2PError` code with the message available from
+/// `kokoro_last_error_message`.
+///
+/// # Safety
+///
+/// The caller must ensure that `language`, `gold_path` and `silver_path` are
+/// valid null-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn kokoro_load_english_dictionaries(
+    language: *const std::ffi::c_char,
+    gold_path: *const std::ffi::c_char,
+    silver_path: *const std::ffi::c_char,
+) -> i32 {
+    let result = c_str_arg(language, "language").and_then(|language| {
+        load_english_dictionaries(language, c_str_arg(gold_path, "gold_path")?, c_str_arg(silver_path, "silver_path")?)
+    });
+    match result {
+        Ok(()) => 0,
+        Err(err) => set_last_error(err),
+    }
+}
+
+/// Load and install English dictionaries from memory (C API)
+///
+/// Like `kokoro_load_english_dictionaries`; each buffer holds JSON or a
+/// compiled dictionary and is copied, so it can be freed afterwards.
+///
+/// # Safety
+///
+/// The caller must ensure that:
+/// - `language` is a valid null-terminated string
+/// - `gold` and `silver` point to at least `gold_len` and `silver_len` readable bytes
+#[no_mangle]
+pub unsafe extern "C" fn kokoro_load_english_dictionaries_from_bytes(
+    language: *const std::ffi::c_char,
+    gold: *const u8,
+    gold_len: usize,
+    silver: *const u8,
+    silver_len: usize,
+) -> i32 {
+    let result = c_str_arg(language, "language").and_then(|language| {
+        load_english_dictionaries_from_bytes(
+            language,
+            bytes_arg(gold, gold_len, "gold")?,
+            bytes_arg(silver, silver_len, "silver")?,
+        )
+    });
+    match result {
+        Ok(()) => 0,
+        Err(err) => set_last_error(err),
+    }
+}
+
+/// Check that dictionaries are available for an English dialect (C API)
+///
+/// Returns 0 if they are, or the `G2PError` code (`DictionaryNotLoaded` when
+/// none have been loaded yet) with the message available from
+/// `kokoro_last_error_message`.
+///
+/// # Safety
+///
+/// The caller must ensure that `language` is a valid null-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn kokoro_check_english_dictionaries(language: *const std::ffi::c_char) -> i32 {
+    match c_str_arg(language, "language").and_then(check_english_dictionaries) {
+        Ok(()) => 0,
+        Err(err) => set_last_error(err),
+    }
+}
+
+/// Get the message for the last error on this thread (C API)
+///
+/// Returns null if no error has occurred. The pointer is valid until the
+/// next failing `kokoro_try_*` call on the same thread and must not be freed.
+#[no_mangle]
+pub extern "C" fn kokoro_last_error_message() -> *const std::ffi::c_char {
+    LAST_ERROR.with(|last| match last.borrow().as_ref() {
+        Some(message) => message.as_ptr(),
+        None => std::ptr::null(),
+    })
+}
+
 /// Get library version (C API)
 #[no_mangle]
 pub extern "C" fn kokoro_version() -> *const std::ffi::c_char {
@@ -656,6 +1289,103 @@ mod tests {
         assert!(phonemes.chars().all(|c| c != '❓'));
     }
 
+    #[test]
+    fn test_try_unsupported_language() {
+        let err = try_text_to_tokens("hello", "klingon").unwrap_err();
+        assert_eq!(err, G2PError::UnsupportedLanguage("klingon".to_string()));
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_try_text_to_tokens() {
+        crate::lexicon::install_test_dictionaries();
+        let tokens = try_text_to_tokens("Hello, world!", "en-us").unwrap();
+        assert_eq!(tokens, text_to_tokens("Hello, world!", "en-us"));
+        assert!(try_text_to_phonemes("Hello, world!", "en-us").is_ok());
+    }
+
+    #[test]
+    fn test_c_try_text_to_tokens() {
+        let text = std::ffi::CString::new("hello").unwrap();
+        let language = std::ffi::CString::new("klingon").unwrap();
+        let mut out = CTokenArray {
+            data: std::ptr::null_mut(),
+            len: 0,
+            capacity: 0,
+        };
+
+        let code = unsafe { kokoro_try_text_to_tokens(text.as_ptr(), language.as_ptr(), &mut out) };
+        assert_eq!(code, G2PError::UnsupportedLanguage(String::new()).code());
+        assert!(out.data.is_null());
+
+        let message = unsafe { std::ffi::CStr::from_ptr(kokoro_last_error_message()) };
+        assert!(message.to_str().unwrap().contains("klingon"));
+
+        let code = unsafe { kokoro_try_text_to_tokens(std::ptr::null(), language.as_ptr(), &mut out) };
+        assert_eq!(code, G2PError::InvalidInput(String::new()).code());
+    }
+
+    #[test]
+    fn test_c_english_dictionaries() {
+        let spanish = std::ffi::CString::new("es").unwrap();
+        let path = std::ffi::CString::new("missing.lex").unwrap();
+        let code = unsafe { kokoro_load_english_dictionaries(spanish.as_ptr(), path.as_ptr(), path.as_ptr()) };
+        assert_ne!(code, 0);
+        let code = unsafe { kokoro_check_english_dictionaries(std::ptr::null()) };
+        assert_eq!(code, G2PError::InvalidInput(String::new()).code());
+
+        #[cfg(all(feature = "english", not(feature = "external-dictionaries")))]
+        {
+            let language = std::ffi::CString::new("en-gb").unwrap();
+            assert_eq!(unsafe { kokoro_check_english_dictionaries(language.as_ptr()) }, 0);
+            let code = unsafe { kokoro_load_english_dictionaries(language.as_ptr(), path.as_ptr(), path.as_ptr()) };
+            assert_eq!(code, G2PError::DictionaryLoad(String::new()).code());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_c_vocabulary() {
+        crate::lexicon::install_test_dictionaries();
+        // A checkpoint whose IDs are the built-in ones shifted by 1000
+        let entries: Vec<String> = tokenizer::get_vocabulary()
+            .into_iter()
+            .map(|c| format!("{}: {}", serde_json::to_string(&c.to_string()).unwrap(), tokenizer::phoneme_to_id(c).unwrap() + 1000))
+            .collect();
+        let path = std::env::temp_dir().join(format!("kokoro_g2p_vocab_{}.json", std::process::id()));
+        std::fs::write(&path, format!("{{\"vocab\": {{{}}}}}", entries.join(", "))).unwrap();
+
+        let c_path = std::ffi::CString::new(path.to_str().unwrap()).unwrap();
+        let text = std::ffi::CString::new("hello").unwrap();
+        let language = std::ffi::CString::new("en-us").unwrap();
+        let mut vocabulary = std::ptr::null();
+        let mut out = CTokenArray {
+            data: std::ptr::null_mut(),
+            len: 0,
+            capacity: 0,
+        };
+        unsafe {
+            assert_eq!(kokoro_vocabulary_load(c_path.as_ptr(), &mut vocabulary), 0);
+            let code = kokoro_try_text_to_tokens_with_vocabulary(text.as_ptr(), language.as_ptr(), vocabulary, &mut out);
+            assert_eq!(code, 0);
+            let tokens = std::slice::from_raw_parts(out.data, out.len).to_vec();
+            kokoro_free_tokens(out);
+            kokoro_vocabulary_free(vocabulary);
+
+            let expected: Vec<i64> = text_to_tokens("hello", "en-us")
+                .into_iter()
+                .map(|id| if id == PAD_TOKEN { id } else { id + 1000 })
+                .collect();
+            assert_eq!(tokens, expected);
+        }
+        std::fs::remove_file(&path).unwrap();
+
+        let mut vocabulary = std::ptr::null();
+        let code = unsafe { kokoro_vocabulary_load(c_path.as_ptr(), &mut vocabulary) };
+        assert_eq!(code, G2PError::InvalidVocabulary(String::new()).code());
+        assert!(vocabulary.is_null());
+    }
+
     // ========================================================================
     // Chinese G2P Tests
     // ========================================================================
@@ -754,7 +1484,7 @@ mod tests {
     #[test]
     #[cfg(feature = "chinese")]
     fn test_chinese_pipeline() {
-        let mut pipeline = KPipeline::new("zh");
+        let pipeline = KPipeline::new("zh");
         let result = pipeline.process("你好世界");
         assert!(!result.phonemes.is_empty());
         assert!(result.tokens.len() > 2);
@@ -789,6 +1519,14 @@ mod tests {
         assert_eq!(tokens_es, tokens_spanish);
     }
 
+    #[test]
+    #[cfg(feature = "spanish")]
+    fn test_spanish_auto_detection() {
+        let text = "¿Dónde está la biblioteca?";
+        assert_eq!(text_to_phonemes(text, "auto"), text_to_phonemes(text, "es"));
+        assert_eq!(text_to_tokens(text, "auto"), text_to_tokens(text, "es"));
+    }
+
     #[test]
     #[cfg(feature = "korean")]
     fn test_korean_tokens_in_range() {
@@ -0,0 +1,214 @@
+//! Inline pronunciation markup
+//!
+//! Misaki-style overrides that authors can write directly in the text:
+//! - `[Kokoro](/kˈOkəɹO/)` replaces the word's phonemes
+//! - `[word](-1)`, `[word](+2)`, ... adjust the word's stress (see [`adjust_stress`])
+//!
+//! Markup that doesn't match one of these forms is left as ordinary text.
+
+use crate::tokenizer;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::ops::Range;
+
+static MARKUP_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\[([^\[\]]+)\]\((?:/([^/()]+)/|([+-]?\d))\)").unwrap());
+
+const PRIMARY_STRESS: char = 'ˈ';
+const SECONDARY_STRESS: char = 'ˌ';
+
+/// Vowels for stress placement, across all supported languages
+const VOWELS: &str = "AIOQWYaeiouyæɑɐɒɔəɛɜɨɪɯʉʊʌøœɤᵻ";
+
+/// The pronunciation change requested by a piece of markup
+#[derive(Debug, Clone, PartialEq)]
+pub enum Override {
+    /// Use these phonemes verbatim
+    Phonemes(String),
+    /// Convert the word normally, then adjust its stress
+    Stress(i8),
+}
+
+/// A piece of markup found in text
+#[derive(Debug, Clone, PartialEq)]
+pub struct Markup<'a> {
+    /// Byte range of the whole markup in the text
+    pub range: Range<usize>,
+    /// The word (or words) inside the brackets
+    pub word: &'a str,
+    /// The requested change
+    pub over: Override,
+}
+
+impl Markup<'_> {
+    /// Phonemes for the marked word, converting it with `convert` if needed
+    pub fn phonemes<F: FnMut(&str) -> String>(&self, mut convert: F) -> String {
+        match &self.over {
+            Override::Phonemes(phonemes) => phonemes.clone(),
+            Override::Stress(stress) => adjust_stress(&convert(self.word), *stress),
+        }
+    }
+}
+
+/// Find all markup in `text`
+pub fn find(text: &str) -> Vec<Markup<'_>> {
+    MARKUP_PATTERN
+        .captures_iter(text)
+        .map(|cap| {
+            let over = match (cap.get(2), cap.get(3)) {
+                (Some(phonemes), _) => Override::Phonemes(phonemes.as_str().to_string()),
+                (_, Some(stress)) => Override::Stress(stress.as_str().parse().unwrap_or(0)),
+                _ => unreachable!("pattern requires phonemes or stress"),
+            };
+            Markup {
+                range: cap.get(0).unwrap().range(),
+                word: cap.get(1).unwrap().as_str(),
+                over,
+            }
+        })
+        .collect()
+}
+
+/// Check if `text` contains any markup
+pub fn has_markup(text: &str) -> bool {
+    text.contains("](") && MARKUP_PATTERN.is_match(text)
+}
+
+/// Apply `f` to the text between markup, keeping the markup itself verbatim
+pub fn map_plain<F: FnMut(&str) -> String>(text: &str, mut f: F) -> String {
+    let markup = find(text);
+    if markup.is_empty() {
+        return f(text);
+    }
+
+    let mut parts = Vec::new();
+    let mut last_end = 0;
+    for m in &markup {
+        parts.push(f(&text[last_end..m.range.start]));
+        parts.push(text[m.range.clone()].to_string());
+        last_end = m.range.end;
+    }
+    parts.push(f(&text[last_end..]));
+    join(parts)
+}
+
+/// Replace markup with the words it marks
+pub fn strip(text: &str) -> String {
+    MARKUP_PATTERN.replace_all(text, "$1").into_owned()
+}
+
+/// Convert text containing markup, using `convert` for everything else
+///
+/// Text between markup is converted in one piece so backends keep as much
+/// context as possible; stress-adjusted words are converted on their own.
+pub fn phonemize<F: FnMut(&str) -> String>(text: &str, mut convert: F) -> String {
+    let markup = find(text);
+    if markup.is_empty() {
+        return convert(text);
+    }
+
+    let mut parts = Vec::new();
+    let mut last_end = 0;
+    for m in &markup {
+        parts.push(convert(&text[last_end..m.range.start]));
+        parts.push(m.phonemes(&mut convert));
+        last_end = m.range.end;
+    }
+    parts.push(convert(&text[last_end..]));
+    join(parts)
+}
+
+/// Join converted pieces with spaces, attaching leading pause punctuation
+fn join(parts: Vec<String>) -> String {
+    let mut result = String::new();
+    for part in &parts {
+        tokenizer::append_phonemes(&mut result, part);
+    }
+    result
+}
+
+/// Adjust the stress of a word's phonemes
+///
+/// - `-2` or lower removes all stress
+/// - `-1` demotes primary stress to secondary
+/// - `0` demotes primary stress, or adds secondary stress to an unstressed word
+/// - `1` promotes secondary stress to primary, or adds secondary stress
+/// - `2` or higher also adds primary stress to an unstressed word
+pub fn adjust_stress(phonemes: &str, stress: i8) -> String {
+    let has_primary = phonemes.contains(PRIMARY_STRESS);
+    let has_secondary = phonemes.contains(SECONDARY_STRESS);
+    let has_vowel = phonemes.chars().any(|c| VOWELS.contains(c));
+
+    if stress < -1 {
+        // Remove all stress
+        phonemes
+            .chars()
+            .filter(|&c| c != PRIMARY_STRESS && c != SECONDARY_STRESS)
+            .collect()
+    } else if stress == -1 || (stress == 0 && has_primary) {
+        // Demote primary to secondary
+        phonemes
+            .replace(SECONDARY_STRESS, "")
+            .replace(PRIMARY_STRESS, &SECONDARY_STRESS.to_string())
+    } else if (stress == 0 || stress == 1) && !has_primary && !has_secondary {
+        // Add secondary stress
+        if has_vowel {
+            format!("{}{}", SECONDARY_STRESS, phonemes)
+        } else {
+            phonemes.to_string()
+        }
+    } else if stress >= 1 && !has_primary && has_secondary {
+        // Promote secondary to primary
+        phonemes.replace(SECONDARY_STRESS, &PRIMARY_STRESS.to_string())
+    } else if stress > 1 && !has_primary && !has_secondary {
+        // Add primary stress
+        if has_vowel {
+            format!("{}{}", PRIMARY_STRESS, phonemes)
+        } else {
+            phonemes.to_string()
+        }
+    } else {
+        phonemes.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find() {
+        let text = "Say [Kokoro](/kˈOkəɹO/) and [read](-1), not [this](foo)";
+        let markup = find(text);
+        assert_eq!(markup.len(), 2);
+        assert_eq!(markup[0].word, "Kokoro");
+        assert_eq!(markup[0].over, Override::Phonemes("kˈOkəɹO".to_string()));
+        assert_eq!(&text[markup[0].range.clone()], "[Kokoro](/kˈOkəɹO/)");
+        assert_eq!(markup[1].over, Override::Stress(-1));
+        assert!(!has_markup("[this](foo)"));
+    }
+
+    #[test]
+    fn test_phonemize() {
+        let upper = |s: &str| s.trim().to_uppercase();
+        assert_eq!(phonemize("no markup", upper), "NO MARKUP");
+        assert_eq!(phonemize("a [b](/bˈi/) c.", upper), "A bˈi C.");
+        assert_eq!(phonemize("[ab](+2)!", |s: &str| s.to_string()), "ˈab!");
+    }
+
+    #[test]
+    fn test_map_plain_and_strip() {
+        let text = "x [y](/why/) z";
+        assert_eq!(map_plain(text, |s| s.trim().to_uppercase()), "X [y](/why/) Z");
+        assert_eq!(strip(text), "x y z");
+    }
+
+    #[test]
+    fn test_adjust_stress() {
+        assert_eq!(adjust_stress("hɛlO", 1), "ˌhɛlO");
+        assert_eq!(adjust_stress("hɛlO", 2), "ˈhɛlO");
+        assert_eq!(adjust_stress("həlˈO", -1), "həlˌO");
+        assert_eq!(adjust_stress("ˌhəlˈO", -2), "həlO");
+        assert_eq!(adjust_stress("ˈola", 1), "ˈola");
+    }
+}
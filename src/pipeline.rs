@@ -3,40 +3,15 @@
 //! The KPipeline provides a unified interface for converting text to phonemes
 //! and tokens across different languages.
 
-use crate::tokenizer;
-
-#[cfg(feature = "english")]
-use crate::g2p::G2P;
-
-#[cfg(feature = "chinese")]
-use crate::zh::ChineseG2P;
-
-#[cfg(feature = "japanese")]
-use crate::ja::JapaneseG2P;
-
-#[cfg(feature = "spanish")]
-use crate::es::SpanishG2P;
-
-#[cfg(feature = "indonesian")]
-use crate::id::IndonesianG2P;
-
-#[cfg(feature = "turkish")]
-use crate::tr::TurkishG2P;
-
-#[cfg(feature = "italian")]
-use crate::it::ItalianG2P;
-
-#[cfg(feature = "german")]
-use crate::de::GermanG2P;
-
-#[cfg(feature = "portuguese")]
-use crate::pt::PortugueseG2P;
-
-#[cfg(feature = "korean")]
-use crate::ko::KoreanG2P;
-
-#[cfg(feature = "vietnamese")]
-use crate::vi::VietnameseG2P;
+use crate::alignment::{Alignment, WordSegment};
+use crate::engine::{EngineRegistry, G2PEngine};
+use crate::error::G2PError;
+use crate::langid::{self, Detection};
+use crate::script::{self, Script};
+use crate::ssml::{self, Segment};
+use crate::tokenizer::{self, Vocabulary, PAUSE_PUNCTUATION};
+use std::ops::Range;
+use std::sync::Arc;
 
 /// Result of G2P processing
 #[derive(Debug, Clone)]
@@ -45,6 +20,65 @@ pub struct G2PResult {
     pub phonemes: String,
     /// Token IDs for the TTS model
     pub tokens: Vec<i64>,
+    /// Byte range of the input text this result was produced from
+    pub span: Range<usize>,
+    /// Word-level alignment, if enabled with [`KPipeline::set_alignment`]
+    /// and supported by the engine
+    pub segments: Option<Vec<WordSegment>>,
+    /// The detected language and confidence, when the pipeline's language
+    /// is [`Language::Auto`]
+    pub detected: Option<Detection>,
+}
+
+impl G2PResult {
+    fn new(phonemes: String, tokens: Vec<i64>, span: Range<usize>, alignment: Option<Alignment>) -> Self {
+        Self {
+            phonemes,
+            tokens,
+            span,
+            segments: alignment.map(|a| a.segments),
+            detected: None,
+        }
+    }
+
+    fn with_detection(mut self, detected: Option<Detection>) -> Self {
+        self.detected = detected;
+        self
+    }
+
+    /// Convert the result to a JSON object
+    ///
+    /// Ranges are written as `[start, end]`; `segments` and `detected` are
+    /// `null` when not available.
+    pub fn to_json(&self) -> serde_json::Value {
+        let segments = self.segments.as_ref().map(|segments| {
+            segments
+                .iter()
+                .map(|s| {
+                    serde_json::json!({
+                        "word": s.word,
+                        "normalized": s.normalized,
+                        "phonemes": s.phonemes,
+                        "source": [s.source.start, s.source.end],
+                        "tokens": [s.tokens.start, s.tokens.end],
+                    })
+                })
+                .collect::<Vec<_>>()
+        });
+        let detected = self.detected.as_ref().map(|d| {
+            serde_json::json!({
+                "language": d.language.code(),
+                "confidence": d.confidence,
+            })
+        });
+        serde_json::json!({
+            "phonemes": self.phonemes,
+            "tokens": self.tokens,
+            "span": [self.span.start, self.span.end],
+            "segments": segments,
+            "detected": detected,
+        })
+    }
 }
 
 /// Language configuration for the pipeline
@@ -74,13 +108,29 @@ pub enum Language {
     Korean,
     /// Vietnamese
     Vietnamese,
+    /// Detect the language of each input among the compiled-in languages,
+    /// see [`crate::langid`]
+    Auto,
+    /// Language served by a custom engine registered at runtime
+    Custom(String),
 }
 
 impl Language {
     /// Parse a language string into a Language enum
     pub fn from_str(s: &str) -> Self {
+        Self::parse(s).unwrap_or(Language::EnglishUS) // Default to US English
+    }
+
+    /// Parse a built-in language code or alias, failing if unrecognized
+    pub fn try_from_str(s: &str) -> Result<Self, G2PError> {
+        Self::parse(s).ok_or_else(|| G2PError::UnsupportedLanguage(s.to_string()))
+    }
+
+    /// Parse a built-in language code or alias, returning `None` if unrecognized
+    pub(crate) fn parse(s: &str) -> Option<Self> {
         let lower = s.to_lowercase();
-        match lower.as_str() {
+        let lang = match lower.as_str() {
+            "en" | "en-us" | "english" | "american" | "us" => Language::EnglishUS,
             "en-gb" | "british" | "gb" => Language::EnglishGB,
             "zh" | "zh-cn" | "chinese" | "mandarin" | "cmn" => Language::Chinese,
             "ja" | "jp" | "japanese" | "日本語" => Language::Japanese,
@@ -92,12 +142,14 @@ impl Language {
             "pt" | "pt-br" | "pt-pt" | "portuguese" | "português" => Language::Portuguese,
             "ko" | "korean" | "한국어" => Language::Korean,
             "vi" | "vietnamese" | "tiếng việt" => Language::Vietnamese,
-            _ => Language::EnglishUS, // Default to US English
-        }
+            "auto" => Language::Auto,
+            _ => return None,
+        };
+        Some(lang)
     }
 
     /// Get the language code string
-    pub fn code(&self) -> &'static str {
+    pub fn code(&self) -> &str {
         match self {
             Language::EnglishUS => "en-us",
             Language::EnglishGB => "en-gb",
@@ -111,68 +163,98 @@ impl Language {
             Language::Portuguese => "pt",
             Language::Korean => "ko",
             Language::Vietnamese => "vi",
+            Language::Auto => "auto",
+            Language::Custom(code) => code,
         }
     }
+
+    /// Get the cargo feature that provides this language (`None` for auto-detection,
+    /// custom languages and Japanese, which has no engine yet)
+    pub fn feature(&self) -> Option<&'static str> {
+        let feature = match self {
+            Language::EnglishUS | Language::EnglishGB => "english",
+            Language::Chinese => "chinese",
+            Language::Spanish => "spanish",
+            Language::Indonesian => "indonesian",
+            Language::Turkish => "turkish",
+            Language::Italian => "italian",
+            Language::German => "german",
+            Language::Portuguese => "portuguese",
+            Language::Korean => "korean",
+            Language::Vietnamese => "vietnamese",
+            Language::Japanese | Language::Auto | Language::Custom(_) => return None,
+        };
+        Some(feature)
+    }
+
+    /// Check if this language's engine reads text in `script`
+    ///
+    /// Auto-detection and custom engines are assumed to read every script.
+    pub fn supports_script(&self, script: Script) -> bool {
+        match self {
+            Language::Chinese => script == Script::Han,
+            Language::Japanese => matches!(script, Script::Han | Script::Kana),
+            Language::Korean => script == Script::Hangul,
+            Language::Auto | Language::Custom(_) => true,
+            _ => script == Script::Latin,
+        }
+    }
+}
+
+/// How the pipeline interprets its input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputMode {
+    /// Plain text (the default)
+    #[default]
+    PlainText,
+    /// SSML markup, see [`crate::ssml`]
+    Ssml,
 }
 
 /// Unified G2P pipeline supporting multiple languages
 ///
-/// The KPipeline lazily initializes language-specific G2P engines as needed.
+/// The KPipeline dispatches to language engines through an [`EngineRegistry`],
+/// which lazily initializes each engine on first use. Custom engines can be
+/// registered to add or replace languages.
+///
+/// Configuration takes `&mut self`, but processing only needs `&self`: a
+/// configured pipeline is `Send + Sync` and can be shared between threads
+/// (e.g. in an `Arc`) and used concurrently. Call [`KPipeline::warm_up`] to
+/// create engines before the first request instead of on it.
 pub struct KPipeline {
     language: Language,
-    #[cfg(feature = "english")]
-    english_g2p: Option<G2P>,
-    #[cfg(feature = "chinese")]
-    chinese_g2p: Option<ChineseG2P>,
-    #[cfg(feature = "japanese")]
-    japanese_g2p: Option<JapaneseG2P>,
-    #[cfg(feature = "spanish")]
-    spanish_g2p: Option<SpanishG2P>,
-    #[cfg(feature = "indonesian")]
-    indonesian_g2p: Option<IndonesianG2P>,
-    #[cfg(feature = "turkish")]
-    turkish_g2p: Option<TurkishG2P>,
-    #[cfg(feature = "italian")]
-    italian_g2p: Option<ItalianG2P>,
-    #[cfg(feature = "german")]
-    german_g2p: Option<GermanG2P>,
-    #[cfg(feature = "portuguese")]
-    portuguese_g2p: Option<PortugueseG2P>,
-    #[cfg(feature = "korean")]
-    korean_g2p: Option<KoreanG2P>,
-    #[cfg(feature = "vietnamese")]
-    vietnamese_g2p: Option<VietnameseG2P>,
+    fallback: Option<Language>,
+    registry: EngineRegistry,
+    alignment: bool,
+    input_mode: InputMode,
+    vocabulary: Arc<Vocabulary>,
 }
 
 impl KPipeline {
     /// Create a new pipeline for the specified language
     pub fn new(language: &str) -> Self {
-        let lang = Language::from_str(language);
-        Self {
-            language: lang,
-            #[cfg(feature = "english")]
-            english_g2p: None,
-            #[cfg(feature = "chinese")]
-            chinese_g2p: None,
-            #[cfg(feature = "japanese")]
-            japanese_g2p: None,
-            #[cfg(feature = "spanish")]
-            spanish_g2p: None,
-            #[cfg(feature = "indonesian")]
-            indonesian_g2p: None,
-            #[cfg(feature = "turkish")]
-            turkish_g2p: None,
-            #[cfg(feature = "italian")]
-            italian_g2p: None,
-            #[cfg(feature = "german")]
-            german_g2p: None,
-            #[cfg(feature = "portuguese")]
-            portuguese_g2p: None,
-            #[cfg(feature = "korean")]
-            korean_g2p: None,
-            #[cfg(feature = "vietnamese")]
-            vietnamese_g2p: None,
-        }
+        Self::with_registry(language, EngineRegistry::with_builtin_engines())
+    }
+
+    /// Create a new pipeline that dispatches through the given registry
+    pub fn with_registry(language: &str, registry: EngineRegistry) -> Self {
+        let mut pipeline = Self {
+            language: Language::EnglishUS,
+            fallback: None,
+            registry,
+            alignment: false,
+            input_mode: InputMode::PlainText,
+            vocabulary: Vocabulary::shared_builtin(),
+        };
+        pipeline.set_language(language);
+        pipeline
+    }
+
+    /// Create a new pipeline, failing if the language is not supported
+    pub fn try_new(language: &str) -> Result<Self, G2PError> {
+        let mut pipeline = Self::new("en-us");
+        pipeline.try_set_language(language)?;
+        Ok(pipeline)
     }
 
     /// Create a new pipeline with default language (US English)
@@ -186,226 +268,719 @@ impl KPipeline {
     }
 
     /// Set the language for subsequent processing
+    ///
+    /// Built-in codes and aliases are recognized first; any other code is used
+    /// as-is if a custom engine is registered for it.
     pub fn set_language(&mut self, language: &str) {
-        self.language = Language::from_str(language);
+        self.language = self.resolve_language(language).unwrap_or(Language::EnglishUS); // Default to US English
     }
 
-    /// Process text and return both phonemes and tokens
-    pub fn process(&mut self, text: &str) -> G2PResult {
-        match &self.language {
-            #[cfg(feature = "chinese")]
-            Language::Chinese => {
-                if self.chinese_g2p.is_none() {
-                    self.chinese_g2p = Some(ChineseG2P::new());
-                }
-                let g2p = self.chinese_g2p.as_ref().unwrap();
-                let phonemes = g2p.text_to_phonemes(text);
-                let tokens = tokenizer::phonemes_to_tokens(&phonemes);
-                G2PResult { phonemes, tokens }
-            }
-            #[cfg(not(feature = "chinese"))]
-            Language::Chinese => {
-                log::warn!("Chinese language requested but 'chinese' feature not enabled");
-                G2PResult {
-                    phonemes: String::new(),
-                    tokens: vec![tokenizer::PAD_TOKEN, tokenizer::PAD_TOKEN],
-                }
-            }
-            #[cfg(feature = "spanish")]
-            Language::Spanish => {
-                if self.spanish_g2p.is_none() {
-                    self.spanish_g2p = Some(SpanishG2P::new());
-                }
-                let g2p = self.spanish_g2p.as_ref().unwrap();
-                let phonemes = g2p.text_to_phonemes(text);
-                let tokens = tokenizer::phonemes_to_tokens(&phonemes);
-                G2PResult { phonemes, tokens }
-            }
-            #[cfg(not(feature = "spanish"))]
-            Language::Spanish => {
-                log::warn!("Spanish language requested but 'spanish' feature not enabled");
-                G2PResult {
-                    phonemes: String::new(),
-                    tokens: vec![tokenizer::PAD_TOKEN, tokenizer::PAD_TOKEN],
-                }
+    /// Set the language, failing instead of falling back to US English
+    ///
+    /// Returns [`G2PError::UnsupportedLanguage`] for unknown codes and
+    /// [`G2PError::FeatureNotEnabled`] for built-in languages that were not compiled in.
+    pub fn try_set_language(&mut self, language: &str) -> Result<(), G2PError> {
+        let lang = self
+            .resolve_language(language)
+            .ok_or_else(|| G2PError::UnsupportedLanguage(language.to_string()))?;
+        self.check_available(&lang)?;
+        self.language = lang;
+        Ok(())
+    }
+
+    /// Get the fallback language used for mixed-language text
+    pub fn fallback_language(&self) -> Option<&Language> {
+        self.fallback.as_ref()
+    }
+
+    /// Set the fallback language for text in scripts the primary language doesn't read
+    ///
+    /// With a fallback set, input is split into script runs (see [`crate::script`])
+    /// and runs the primary language can't read, such as English brand names in
+    /// Chinese text, are converted by the fallback engine. `None` disables this.
+    /// Unknown codes are ignored with a warning. Word alignment is not
+    /// available for text that mixes languages.
+    pub fn set_fallback_language(&mut self, language: Option<&str>) {
+        self.fallback = language.and_then(|code| {
+            let lang = self.resolve_language(code);
+            if lang.is_none() {
+                log::warn!("Unknown fallback language '{}', ignoring", code);
             }
-            #[cfg(feature = "indonesian")]
-            Language::Indonesian => {
-                if self.indonesian_g2p.is_none() {
-                    self.indonesian_g2p = Some(IndonesianG2P::new());
-                }
-                let g2p = self.indonesian_g2p.as_ref().unwrap();
-                let phonemes = g2p.text_to_phonemes(text);
-                let tokens = tokenizer::phonemes_to_tokens(&phonemes);
-                G2PResult { phonemes, tokens }
+            lang
+        });
+    }
+
+    /// Set the fallback language, failing if it is not supported
+    pub fn try_set_fallback_language(&mut self, language: Option<&str>) -> Result<(), G2PError> {
+        self.fallback = match language {
+            Some(code) => {
+                let lang = self
+                    .resolve_language(code)
+                    .ok_or_else(|| G2PError::UnsupportedLanguage(code.to_string()))?;
+                self.check_available(&lang)?;
+                Some(lang)
             }
-            #[cfg(not(feature = "indonesian"))]
-            Language::Indonesian => {
-                log::warn!("Indonesian language requested but 'indonesian' feature not enabled");
-                G2PResult {
-                    phonemes: String::new(),
-                    tokens: vec![tokenizer::PAD_TOKEN, tokenizer::PAD_TOKEN],
-                }
+            None => None,
+        };
+        Ok(())
+    }
+
+    /// Resolve a built-in code or alias, or a code with a registered custom engine
+    fn resolve_language(&self, language: &str) -> Option<Language> {
+        match Language::parse(language) {
+            Some(lang) => Some(lang),
+            None if self.registry.contains(language) => Some(Language::Custom(language.to_lowercase())),
+            None => None,
+        }
+    }
+
+    /// Check that an engine is registered for a language
+    ///
+    /// Auto-detection is available if any built-in language was compiled in.
+    fn check_available(&self, language: &Language) -> Result<(), G2PError> {
+        let code = language.code();
+        if *language == Language::Auto {
+            if langid::available_languages().is_empty() {
+                return Err(G2PError::UnsupportedLanguage(code.to_string()));
             }
-            #[cfg(feature = "turkish")]
-            Language::Turkish => {
-                if self.turkish_g2p.is_none() {
-                    self.turkish_g2p = Some(TurkishG2P::new());
-                }
-                let g2p = self.turkish_g2p.as_ref().unwrap();
-                let phonemes = g2p.text_to_phonemes(text);
-                let tokens = tokenizer::phonemes_to_tokens(&phonemes);
-                G2PResult { phonemes, tokens }
+            return Ok(());
+        }
+        if self.registry.contains(code) {
+            return Ok(());
+        }
+        match language.feature() {
+            Some(feature) if !is_feature_enabled(feature) => Err(G2PError::FeatureNotEnabled {
+                language: code.to_string(),
+                feature,
+            }),
+            _ => Err(G2PError::UnsupportedLanguage(code.to_string())),
+        }
+    }
+
+    /// Enable or disable word-level alignment in results
+    ///
+    /// When enabled, engines that support alignment fill in
+    /// [`G2PResult::segments`].
+    pub fn set_alignment(&mut self, enabled: bool) {
+        self.alignment = enabled;
+    }
+
+    /// Check if word-level alignment is enabled
+    pub fn alignment(&self) -> bool {
+        self.alignment
+    }
+
+    /// Set how input text is interpreted (plain text by default)
+    pub fn set_input_mode(&mut self, mode: InputMode) {
+        self.input_mode = mode;
+    }
+
+    /// Get the input mode
+    pub fn input_mode(&self) -> InputMode {
+        self.input_mode
+    }
+
+    /// Set the vocabulary used to convert phonemes to token IDs
+    ///
+    /// Defaults to [`Vocabulary::builtin`]. Use the vocabulary of the model
+    /// the tokens are fed to, e.g. one loaded with [`KPipeline::load_vocabulary`].
+    pub fn set_vocabulary<V: Into<Arc<Vocabulary>>>(&mut self, vocabulary: V) {
+        self.vocabulary = vocabulary.into();
+    }
+
+    /// Get the vocabulary used to convert phonemes to token IDs
+    pub fn vocabulary(&self) -> &Vocabulary {
+        &self.vocabulary
+    }
+
+    /// Load and select the vocabulary from a model's `config.json`
+    pub fn load_vocabulary<P: AsRef<std::path::Path>>(&mut self, path: P) -> Result<(), G2PError> {
+        self.set_vocabulary(Vocabulary::from_config_path(path)?);
+        Ok(())
+    }
+
+    /// Register an engine factory for a language code
+    ///
+    /// Registering a built-in code (e.g. "es") replaces the built-in engine.
+    pub fn register_engine<F>(&mut self, code: &str, factory: F)
+    where
+        F: Fn() -> Box<dyn G2PEngine> + Send + Sync + 'static,
+    {
+        self.registry.register(code, factory);
+    }
+
+    /// Get the user lexicon consulted by the built-in English engines
+    ///
+    /// Entries take priority over the gold dictionary and apply immediately,
+    /// including to engines that were already created.
+    #[cfg(feature = "english")]
+    pub fn user_lexicon(&self) -> &crate::lexicon::UserLexicon {
+        self.registry.user_lexicon()
+    }
+
+    /// Load a JSON or TSV user lexicon file, returning the number of entries loaded
+    #[cfg(feature = "english")]
+    pub fn load_user_lexicon<P: AsRef<std::path::Path>>(&self, path: P) -> Result<usize, G2PError> {
+        self.user_lexicon().load_path(path)
+    }
+
+    /// Add or replace the pronunciation of an English word
+    #[cfg(feature = "english")]
+    pub fn add_user_word(&self, word: &str, phonemes: &str) {
+        self.user_lexicon().insert(word, phonemes);
+    }
+
+    /// Create the engines for `languages` and load their dictionaries
+    ///
+    /// Engines are otherwise created on first use, so the first text in each
+    /// language pays for dictionary decompression. [`Language::Auto`] warms
+    /// up every detectable language.
+    pub fn warm_up(&self, languages: &[Language]) -> Result<(), G2PError> {
+        for language in languages {
+            if *language == Language::Auto {
+                self.warm_up(&langid::available_languages())?;
+                continue;
             }
-            #[cfg(not(feature = "turkish"))]
-            Language::Turkish => {
-                log::warn!("Turkish language requested but 'turkish' feature not enabled");
-                G2PResult {
-                    phonemes: String::new(),
-                    tokens: vec![tokenizer::PAD_TOKEN, tokenizer::PAD_TOKEN],
-                }
+            self.check_available(language)?;
+            if let Some(engine) = self.registry.get(language.code()) {
+                engine.try_phonemize("")?;
             }
-            #[cfg(feature = "italian")]
-            Language::Italian => {
-                if self.italian_g2p.is_none() {
-                    self.italian_g2p = Some(ItalianG2P::new());
+        }
+        Ok(())
+    }
+
+    /// Get the engine registry
+    pub fn registry(&self) -> &EngineRegistry {
+        &self.registry
+    }
+
+    /// Get the engine registry for modification
+    pub fn registry_mut(&mut self) -> &mut EngineRegistry {
+        &mut self.registry
+    }
+
+    /// Process text and return both phonemes and tokens
+    pub fn process(&self, text: &str) -> G2PResult {
+        if self.input_mode == InputMode::Ssml {
+            return self.process_ssml(text);
+        }
+
+        let (language, detected) = self.detect_language(text);
+        let runs = self.language_runs(&language, text);
+        if runs.iter().any(|(_, lang)| *lang != language) {
+            let phonemes = self.phonemize_runs(text, &runs, false).unwrap_or_default();
+            let tokens = self.vocabulary.phonemes_to_tokens(&phonemes);
+            return G2PResult::new(phonemes, tokens, 0..text.len(), None).with_detection(detected);
+        }
+
+        let code = language.code();
+        match self.registry.get(code) {
+            Some(engine) => {
+                let mut aligned = if self.alignment {
+                    phonemize_aligned_with(engine, text, false).unwrap_or_default()
+                } else {
+                    None
+                };
+                if let Some(alignment) = aligned.as_mut() {
+                    alignment.retokenize(&self.vocabulary);
                 }
-                let g2p = self.italian_g2p.as_ref().unwrap();
-                let phonemes = g2p.text_to_phonemes(text);
-                let tokens = tokenizer::phonemes_to_tokens(&phonemes);
-                G2PResult { phonemes, tokens }
+                let phonemes = match &aligned {
+                    Some(a) => a.phonemes.clone(),
+                    None => phonemize_with(engine, text, false).unwrap_or_default(),
+                };
+                let tokens = self.vocabulary.phonemes_to_tokens(&phonemes);
+                G2PResult::new(phonemes, tokens, 0..text.len(), aligned).with_detection(detected)
             }
-            #[cfg(not(feature = "italian"))]
-            Language::Italian => {
-                log::warn!("Italian language requested but 'italian' feature not enabled");
+            None => {
+                log::warn!("Language '{}' requested but no engine is registered (feature not enabled?)", code);
                 G2PResult {
                     phonemes: String::new(),
                     tokens: vec![tokenizer::PAD_TOKEN, tokenizer::PAD_TOKEN],
+                    span: 0..text.len(),
+                    segments: None,
+                    detected,
                 }
             }
-            #[cfg(feature = "german")]
-            Language::German => {
-                if self.german_g2p.is_none() {
-                    self.german_g2p = Some(GermanG2P::new());
-                }
-                let g2p = self.german_g2p.as_ref().unwrap();
-                let phonemes = g2p.text_to_phonemes(text);
-                let tokens = tokenizer::phonemes_to_tokens(&phonemes);
-                G2PResult { phonemes, tokens }
+        }
+    }
+
+    /// Process text, reporting why no output could be produced
+    ///
+    /// Unlike [`KPipeline::process`], unknown phonemes and over-long input are
+    /// errors rather than being dropped or truncated.
+    pub fn try_process(&self, text: &str) -> Result<G2PResult, G2PError> {
+        self.try_process_with(text, &|phonemes| self.vocabulary.try_phonemes_to_tokens(phonemes))
+    }
+
+    /// Strict processing with a custom phoneme-to-token conversion
+    fn try_process_with(&self, text: &str, tokenize: &Tokenize) -> Result<G2PResult, G2PError> {
+        if self.input_mode == InputMode::Ssml {
+            return self.try_process_ssml_with(text, tokenize);
+        }
+
+        self.check_available(&self.language)?;
+        let (language, detected) = self.detect_language(text);
+        let runs = self.language_runs(&language, text);
+        if runs.iter().any(|(_, lang)| *lang != language) {
+            let phonemes = self.phonemize_runs(text, &runs, true)?;
+            let tokens = tokenize(&phonemes)?;
+            return Ok(G2PResult::new(phonemes, tokens, 0..text.len(), None).with_detection(detected));
+        }
+
+        let engine = self
+            .registry
+            .get(language.code())
+            .ok_or_else(|| G2PError::UnsupportedLanguage(language.code().to_string()))?;
+        let mut aligned = if self.alignment {
+            phonemize_aligned_with(engine, text, true)?
+        } else {
+            None
+        };
+        if let Some(alignment) = aligned.as_mut() {
+            alignment.retokenize(&self.vocabulary);
+        }
+        let phonemes = match &aligned {
+            Some(a) => a.phonemes.clone(),
+            None => phonemize_with(engine, text, true)?,
+        };
+        let tokens = tokenize(&phonemes)?;
+        Ok(G2PResult::new(phonemes, tokens, 0..text.len(), aligned).with_detection(detected))
+    }
+
+    /// Get the language to process `text` in, detecting it for [`Language::Auto`]
+    fn detect_language(&self, text: &str) -> (Language, Option<Detection>) {
+        if self.language != Language::Auto {
+            return (self.language.clone(), None);
+        }
+        match langid::detect(text) {
+            Some(detection) => (detection.language.clone(), Some(detection)),
+            None => (Language::Auto, None),
+        }
+    }
+
+    /// Process an SSML document regardless of the input mode
+    ///
+    /// Text outside `<lang>` elements uses the pipeline's language. Malformed
+    /// SSML is logged and produces empty output; word alignment is not
+    /// available for SSML input.
+    pub fn process_ssml(&self, ssml: &str) -> G2PResult {
+        let phonemes = match self.phonemize_ssml(ssml, false) {
+            Ok(phonemes) => phonemes,
+            Err(e) => {
+                log::warn!("Failed to process SSML: {}", e);
+                String::new()
             }
-            #[cfg(not(feature = "german"))]
-            Language::German => {
-                log::warn!("German language requested but 'german' feature not enabled");
-                G2PResult {
-                    phonemes: String::new(),
-                    tokens: vec![tokenizer::PAD_TOKEN, tokenizer::PAD_TOKEN],
+        };
+        let tokens = self.vocabulary.phonemes_to_tokens(&phonemes);
+        G2PResult::new(phonemes, tokens, 0..ssml.len(), None)
+    }
+
+    /// Process an SSML document, reporting malformed markup and unsupported languages
+    pub fn try_process_ssml(&self, ssml: &str) -> Result<G2PResult, G2PError> {
+        self.try_process_ssml_with(ssml, &|phonemes| self.vocabulary.try_phonemes_to_tokens(phonemes))
+    }
+
+    fn try_process_ssml_with(&self, ssml: &str, tokenize: &Tokenize) -> Result<G2PResult, G2PError> {
+        self.check_available(&self.language)?;
+        let phonemes = self.phonemize_ssml(ssml, true)?;
+        let tokens = tokenize(&phonemes)?;
+        Ok(G2PResult::new(phonemes, tokens, 0..ssml.len(), None))
+    }
+
+    /// Convert SSML segments with the engine for each segment's language
+    ///
+    /// In lenient mode, unknown `<lang>` codes fall back to the pipeline's language.
+    fn phonemize_ssml(&self, ssml: &str, strict: bool) -> Result<String, G2PError> {
+        let mut phonemes = String::new();
+        for segment in ssml::parse(ssml, self.language.code())? {
+            match segment {
+                Segment::Text { text, language } => {
+                    let lang = match self.resolve_language(&language) {
+                        Some(lang) if self.check_available(&lang).is_ok() => lang,
+                        Some(lang) if strict => return Err(self.check_available(&lang).unwrap_err()),
+                        None if strict => return Err(G2PError::UnsupportedLanguage(language)),
+                        _ => {
+                            log::warn!("SSML language '{}' is not available, using '{}'", language, self.language.code());
+                            self.language.clone()
+                        }
+                    };
+                    let runs = self.language_runs(&lang, &text);
+                    let part = self.phonemize_runs(&text, &runs, strict)?;
+                    tokenizer::append_phonemes(&mut phonemes, &part);
                 }
-            }
-            #[cfg(feature = "portuguese")]
-            Language::Portuguese => {
-                if self.portuguese_g2p.is_none() {
-                    self.portuguese_g2p = Some(PortugueseG2P::new());
+                Segment::Phonemes(part) => tokenizer::append_phonemes(&mut phonemes, &part),
+                Segment::Break(punct) => {
+                    let trimmed = phonemes.trim_end().len();
+                    phonemes.truncate(trimmed);
+                    // A break replaces any trailing punctuation from the text before it
+                    if phonemes.ends_with(PAUSE_PUNCTUATION) {
+                        phonemes.pop();
+                    }
+                    if !phonemes.is_empty() {
+                        phonemes.push(punct);
+                    }
                 }
-                let g2p = self.portuguese_g2p.as_ref().unwrap();
-                let phonemes = g2p.text_to_phonemes(text);
-                let tokens = tokenizer::phonemes_to_tokens(&phonemes);
-                G2PResult { phonemes, tokens }
             }
-            #[cfg(not(feature = "portuguese"))]
-            Language::Portuguese => {
-                log::warn!("Portuguese language requested but 'portuguese' feature not enabled");
-                G2PResult {
-                    phonemes: String::new(),
-                    tokens: vec![tokenizer::PAD_TOKEN, tokenizer::PAD_TOKEN],
-                }
+        }
+
+        Ok(phonemes)
+    }
+
+    /// Split text into runs for `primary` and the fallback language
+    ///
+    /// Without a fallback language, the whole text is one run for `primary`.
+    fn language_runs(&self, primary: &Language, text: &str) -> Vec<(Range<usize>, Language)> {
+        let Some(fallback) = &self.fallback else {
+            return vec![(0..text.len(), primary.clone())];
+        };
+
+        let mut runs: Vec<(Range<usize>, Language)> = Vec::new();
+        for (range, script) in script::split_runs(text) {
+            let lang = if !primary.supports_script(script) && fallback.supports_script(script) {
+                fallback
+            } else {
+                primary
+            };
+            match runs.last_mut() {
+                Some((last, last_lang)) if last_lang == lang => last.end = range.end,
+                _ => runs.push((range, lang.clone())),
             }
-            #[cfg(feature = "korean")]
-            Language::Korean => {
-                if self.korean_g2p.is_none() {
-                    self.korean_g2p = Some(KoreanG2P::new());
-                }
-                let g2p = self.korean_g2p.as_ref().unwrap();
-                let phonemes = g2p.text_to_phonemes(text);
-                let tokens = tokenizer::phonemes_to_tokens(&phonemes);
-                G2PResult { phonemes, tokens }
+        }
+        runs
+    }
+
+    /// Convert each run with its language's engine and join the results
+    ///
+    /// Runs in [`Language::Auto`] are converted in their detected language.
+    /// In lenient mode, runs whose engine is not available are skipped.
+    fn phonemize_runs(
+        &self,
+        text: &str,
+        runs: &[(Range<usize>, Language)],
+        strict: bool,
+    ) -> Result<String, G2PError> {
+        let mut phonemes = String::new();
+        for (range, lang) in runs {
+            let run = &text[range.clone()];
+            let lang = &match lang {
+                Language::Auto => langid::detect(run).map_or(Language::Auto, |d| d.language),
+                lang => lang.clone(),
+            };
+            if strict {
+                self.check_available(lang)?;
             }
-            #[cfg(not(feature = "korean"))]
-            Language::Korean => {
-                log::warn!("Korean language requested but 'korean' feature not enabled");
-                G2PResult {
-                    phonemes: String::new(),
-                    tokens: vec![tokenizer::PAD_TOKEN, tokenizer::PAD_TOKEN],
+            let part = match self.registry.get(lang.code()) {
+                Some(engine) => phonemize_with(engine, run, strict)?,
+                None => {
+                    log::warn!("No engine for '{}', skipping: {}", lang.code(), run);
+                    continue;
                 }
+            };
+            tokenizer::append_phonemes(&mut phonemes, &part);
+        }
+        Ok(phonemes)
+    }
+
+    /// Process text of any length, splitting it into chunks that fit the model
+    ///
+    /// Text is split at sentence boundaries first, then at clause punctuation,
+    /// then at whitespace, and never inside a word. Adjacent pieces are merged
+    /// back together as long as the result stays within `MAX_TOKENS`. Each
+    /// result's `span` is the byte range of its chunk in `text`. A single word
+    /// whose phonemes exceed the budget is split inside its phonemes, giving
+    /// several results with the word's span.
+    ///
+    /// SSML input is converted as a whole, then its phonemes are split at
+    /// pauses, spaces or, failing those, anywhere; every chunk has the
+    /// document's span.
+    pub fn process_chunked(&self, text: &str) -> Vec<G2PResult> {
+        // Lenient conversion never fails
+        self.chunk_with(text, &|text| Ok(self.process(text))).unwrap_or_default()
+    }
+
+    /// Process text of any length in chunks, reporting why conversion failed
+    ///
+    /// Chunks are formed as in [`KPipeline::process_chunked`]. Unavailable
+    /// languages, unknown phonemes and malformed SSML are errors, as in
+    /// [`KPipeline::try_process`]; input length is not, since it is split to
+    /// fit.
+    pub fn try_process_chunked(&self, text: &str) -> Result<Vec<G2PResult>, G2PError> {
+        self.check_available(&self.language)?;
+        // Length is handled by chunking, so only check that every phoneme maps
+        let tokenize = |phonemes: &str| match self.vocabulary.validate(phonemes).unmapped.first() {
+            Some(&(position, phoneme)) => Err(G2PError::UnmappablePhoneme { phoneme, position }),
+            None => Ok(self.vocabulary.phonemes_to_tokens(phonemes)),
+        };
+        self.chunk_with(text, &|text| self.try_process_with(text, &tokenize))
+    }
+
+    /// Split text into chunks that fit the model, converting each with `convert`
+    fn chunk_with(&self, text: &str, convert: &Convert) -> Result<Vec<G2PResult>, G2PError> {
+        if self.input_mode == InputMode::Ssml {
+            return Ok(self.split_phonemes(convert(text)?));
+        }
+
+        let mut pieces = Vec::new();
+        self.split_to_fit(text, 0..text.len(), BoundaryLevel::Sentence, convert, &mut pieces)?;
+
+        // Take as many adjacent pieces as their token counts allow, with one
+        // separator token between pieces, and process them once as a whole
+        // so that context across piece boundaries is kept. If the joined
+        // result turns out longer than the sum of its pieces, drop pieces
+        // from the end until it fits and start the next chunk with them.
+        let counts: Vec<usize> = pieces.iter().map(|piece| self.token_count(piece)).collect();
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        while start < pieces.len() {
+            let mut end = start + 1;
+            let mut tokens = counts[start];
+            while end < pieces.len() && tokens + 1 + counts[end] <= tokenizer::MAX_TOKENS {
+                tokens += 1 + counts[end];
+                end += 1;
             }
-            #[cfg(feature = "japanese")]
-            Language::Japanese => {
-                if self.japanese_g2p.is_none() {
-                    self.japanese_g2p = Some(JapaneseG2P::new());
+            let chunk = loop {
+                if end == start + 1 {
+                    break pieces[start].clone();
                 }
-                let g2p = self.japanese_g2p.as_ref().unwrap();
-                let phonemes = g2p.text_to_phonemes(text);
-                let tokens = tokenizer::phonemes_to_tokens(&phonemes);
-                G2PResult { phonemes, tokens }
-            }
-            #[cfg(not(feature = "japanese"))]
-            Language::Japanese => {
-                log::warn!("Japanese language requested but 'japanese' feature not enabled");
-                G2PResult {
-                    phonemes: String::new(),
-                    tokens: vec![tokenizer::PAD_TOKEN, tokenizer::PAD_TOKEN],
+                let merged = self.process_span(text, pieces[start].span.start..pieces[end - 1].span.end, convert)?;
+                if self.fits(&merged) {
+                    break merged;
                 }
+                end -= 1;
+            };
+            chunks.push(chunk);
+            start = end;
+        }
+        Ok(chunks)
+    }
+
+    /// Convert a byte range of `text`, recording it as the result's span
+    fn process_span(&self, text: &str, span: Range<usize>, convert: &Convert) -> Result<G2PResult, G2PError> {
+        let mut result = convert(&text[span.clone()])?;
+        for segment in result.segments.iter_mut().flatten() {
+            segment.source = segment.source.start + span.start..segment.source.end + span.start;
+        }
+        result.span = span;
+        Ok(result)
+    }
+
+    /// Split `range` into pieces that each fit the token budget, processing
+    /// each piece once
+    fn split_to_fit(
+        &self,
+        text: &str,
+        range: Range<usize>,
+        level: BoundaryLevel,
+        convert: &Convert,
+        pieces: &mut Vec<G2PResult>,
+    ) -> Result<(), G2PError> {
+        for piece in split_at_boundaries(text, range, level) {
+            let result = self.process_span(text, piece.clone(), convert)?;
+            match level.finer() {
+                Some(finer) if !self.fits(&result) => self.split_to_fit(text, piece, finer, convert, pieces)?,
+                _ => pieces.extend(self.split_phonemes(result)),
             }
-            #[cfg(feature = "vietnamese")]
-            Language::Vietnamese => {
-                if self.vietnamese_g2p.is_none() {
-                    self.vietnamese_g2p = Some(VietnameseG2P::new());
-                }
-                let g2p = self.vietnamese_g2p.as_ref().unwrap();
-                let phonemes = g2p.text_to_phonemes(text);
-                let tokens = tokenizer::phonemes_to_tokens(&phonemes);
-                G2PResult { phonemes, tokens }
+        }
+        Ok(())
+    }
+
+    /// Split a result whose phonemes exceed the token budget into results
+    /// that each fit, keeping its span and detection but not its alignment
+    fn split_phonemes(&self, result: G2PResult) -> Vec<G2PResult> {
+        if self.fits(&result) {
+            return vec![result];
+        }
+        let mut pieces = Vec::new();
+        let mut rest = result.phonemes.as_str();
+        while !rest.is_empty() {
+            let (piece, tail) = rest.split_at(self.phoneme_cut(rest));
+            let piece = piece.trim_end();
+            if !piece.is_empty() {
+                let tokens = self.vocabulary.phonemes_to_tokens(piece);
+                let chunk = G2PResult::new(piece.to_string(), tokens, result.span.clone(), None);
+                pieces.push(chunk.with_detection(result.detected.clone()));
             }
-            #[cfg(not(feature = "vietnamese"))]
-            Language::Vietnamese => {
-                log::warn!("Vietnamese language requested but 'vietnamese' feature not enabled");
-                G2PResult {
-                    phonemes: String::new(),
-                    tokens: vec![tokenizer::PAD_TOKEN, tokenizer::PAD_TOKEN],
+            rest = tail.trim_start();
+        }
+        pieces
+    }
+
+    /// Byte offset at which to cut `phonemes` so that the part before it fits
+    /// the token budget: after the last sentence, clause or word boundary
+    /// within the budget, or at the budget itself if there is none
+    fn phoneme_cut(&self, phonemes: &str) -> usize {
+        const LEVELS: [BoundaryLevel; 3] = [BoundaryLevel::Sentence, BoundaryLevel::Clause, BoundaryLevel::Word];
+        let mut last_boundary = [None; 3];
+        let mut count = 0;
+        let mut chars = phonemes.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            if self.vocabulary.contains(c) {
+                if count == tokenizer::MAX_TOKENS {
+                    return last_boundary.into_iter().flatten().next().unwrap_or(i);
                 }
+                count += 1;
             }
-            #[cfg(feature = "english")]
-            Language::EnglishUS | Language::EnglishGB => {
-                let british = matches!(self.language, Language::EnglishGB);
-                if self.english_g2p.is_none() || self.english_g2p.as_ref().map(|g| g.is_british()) != Some(british) {
-                    self.english_g2p = Some(G2P::new(british));
-                }
-                let g2p = self.english_g2p.as_ref().unwrap();
-                let phonemes = g2p.text_to_phonemes(text);
-                let tokens = tokenizer::phonemes_to_tokens(&phonemes);
-                G2PResult { phonemes, tokens }
-            }
-            #[cfg(not(feature = "english"))]
-            Language::EnglishUS | Language::EnglishGB => {
-                log::warn!("English language requested but 'english' feature not enabled");
-                G2PResult {
-                    phonemes: String::new(),
-                    tokens: vec![tokenizer::PAD_TOKEN, tokenizer::PAD_TOKEN],
+            let next = chars.peek().map(|&(_, next)| next);
+            for (boundary, level) in last_boundary.iter_mut().zip(LEVELS) {
+                if level.is_boundary(c, next) {
+                    *boundary = Some(i + c.len_utf8());
                 }
             }
         }
+        phonemes.len()
+    }
+
+    /// Number of phonemes in a result that map to tokens
+    fn token_count(&self, result: &G2PResult) -> usize {
+        result
+            .phonemes
+            .chars()
+            .filter(|&c| self.vocabulary.contains(c))
+            .count()
+    }
+
+    /// Check if a result's phonemes fit in the token budget without truncation
+    fn fits(&self, result: &G2PResult) -> bool {
+        self.token_count(result) <= tokenizer::MAX_TOKENS
     }
 
     /// Convert text to phonemes only
-    pub fn to_phonemes(&mut self, text: &str) -> String {
+    pub fn to_phonemes(&self, text: &str) -> String {
         self.process(text).phonemes
     }
 
     /// Convert text to tokens only
-    pub fn to_tokens(&mut self, text: &str) -> Vec<i64> {
+    pub fn to_tokens(&self, text: &str) -> Vec<i64> {
         self.process(text).tokens
     }
 }
 
+/// Phoneme-to-token conversion used by strict processing
+type Tokenize<'a> = dyn Fn(&str) -> Result<Vec<i64>, G2PError> + 'a;
+
+/// Text-to-result conversion used when chunking
+type Convert<'a> = dyn Fn(&str) -> Result<G2PResult, G2PError> + 'a;
+
+/// Convert text with `engine`, replacing phonemes the built-in vocabulary
+/// lacks with the fallbacks for the engine's language
+///
+/// In lenient mode the engine's `phonemize` is used and this never fails.
+fn phonemize_with(engine: &dyn G2PEngine, text: &str, strict: bool) -> Result<String, G2PError> {
+    let phonemes = if strict {
+        engine.try_phonemize(text)?
+    } else {
+        engine.phonemize(text)
+    };
+    Ok(tokenizer::apply_fallbacks(engine.language_code(), &phonemes))
+}
+
+/// Like [`phonemize_with`], with word-level alignment
+fn phonemize_aligned_with(engine: &dyn G2PEngine, text: &str, strict: bool) -> Result<Option<Alignment>, G2PError> {
+    let mut aligned = if strict {
+        engine.try_phonemize_aligned(text)?
+    } else {
+        engine.phonemize_aligned(text)
+    };
+    if let Some(alignment) = aligned.as_mut() {
+        alignment.apply_fallbacks(engine.language_code());
+    }
+    Ok(aligned)
+}
+
+/// Boundaries used to split long input, from coarsest to finest
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BoundaryLevel {
+    Sentence,
+    Clause,
+    Word,
+}
+
+impl BoundaryLevel {
+    fn finer(self) -> Option<Self> {
+        match self {
+            BoundaryLevel::Sentence => Some(BoundaryLevel::Clause),
+            BoundaryLevel::Clause => Some(BoundaryLevel::Word),
+            BoundaryLevel::Word => None,
+        }
+    }
+
+    /// Check if a split may be made after `c`, given the character that follows it
+    fn is_boundary(self, c: char, next: Option<char>) -> bool {
+        let followed_by_space = next.is_none_or(char::is_whitespace);
+        match self {
+            BoundaryLevel::Sentence => match c {
+                '。' | '！' | '？' | '\n' => true,
+                '.' | '!' | '?' | '…' => followed_by_space,
+                _ => false,
+            },
+            BoundaryLevel::Clause => match c {
+                '，' | '；' | '：' | '、' => true,
+                ',' | ';' | ':' | '—' => followed_by_space,
+                _ => false,
+            },
+            BoundaryLevel::Word => c.is_whitespace(),
+        }
+    }
+}
+
+/// Split `range` of `text` after each boundary, trimming surrounding whitespace
+fn split_at_boundaries(text: &str, range: Range<usize>, level: BoundaryLevel) -> Vec<Range<usize>> {
+    let mut pieces = Vec::new();
+    let mut start = range.start;
+    let mut chars = text[range.clone()].char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let next = chars.peek().map(|&(_, n)| n);
+        if level.is_boundary(c, next) {
+            let end = range.start + i + c.len_utf8();
+            push_trimmed(text, start..end, &mut pieces);
+            start = end;
+        }
+    }
+    push_trimmed(text, start..range.end, &mut pieces);
+    pieces
+}
+
+/// Add a range with leading and trailing whitespace removed, skipping blank ranges
+fn push_trimmed(text: &str, range: Range<usize>, pieces: &mut Vec<Range<usize>>) {
+    let slice = &text[range.clone()];
+    let trimmed = slice.trim();
+    if !trimmed.is_empty() {
+        let start = range.start + (slice.len() - slice.trim_start().len());
+        pieces.push(start..start + trimmed.len());
+    }
+}
+
+/// Language features and whether each was compiled in
+const LANGUAGE_FEATURES: &[(&str, bool)] = &[
+    ("english", cfg!(feature = "english")),
+    ("chinese", cfg!(feature = "chinese")),
+    ("spanish", cfg!(feature = "spanish")),
+    ("indonesian", cfg!(feature = "indonesian")),
+    ("turkish", cfg!(feature = "turkish")),
+    ("italian", cfg!(feature = "italian")),
+    ("german", cfg!(feature = "german")),
+    ("portuguese", cfg!(feature = "portuguese")),
+    ("korean", cfg!(feature = "korean")),
+    ("vietnamese", cfg!(feature = "vietnamese")),
+];
+
+/// Names of the language features compiled into this build
+pub fn enabled_features() -> Vec<&'static str> {
+    LANGUAGE_FEATURES
+        .iter()
+        .filter(|&&(_, enabled)| enabled)
+        .map(|&(name, _)| name)
+        .collect()
+}
+
+/// Check whether a language feature was compiled in
+pub(crate) fn is_feature_enabled(feature: &str) -> bool {
+    LANGUAGE_FEATURES
+        .iter()
+        .any(|&(name, enabled)| name == feature && enabled)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -413,7 +988,7 @@ mod tests {
     #[test]
     #[cfg(feature = "english")]
     fn test_pipeline_english() {
-        let mut pipeline = KPipeline::new("en-us");
+        let pipeline = KPipeline::new("en-us");
         let result = pipeline.process("Hello, world!");
         assert!(!result.phonemes.is_empty());
         assert!(result.tokens.len() > 2);
@@ -422,7 +997,7 @@ mod tests {
     #[test]
     #[cfg(feature = "chinese")]
     fn test_pipeline_chinese() {
-        let mut pipeline = KPipeline::new("zh");
+        let pipeline = KPipeline::new("zh");
         let result = pipeline.process("你好");
         assert!(!result.phonemes.is_empty());
         assert!(result.tokens.len() > 2);
@@ -450,13 +1025,14 @@ mod tests {
         assert_eq!(Language::from_str("korean"), Language::Korean);
         assert_eq!(Language::from_str("vi"), Language::Vietnamese);
         assert_eq!(Language::from_str("vietnamese"), Language::Vietnamese);
+        assert_eq!(Language::from_str("auto"), Language::Auto);
         assert_eq!(Language::from_str("unknown"), Language::EnglishUS);
     }
 
     #[test]
     #[cfg(feature = "spanish")]
     fn test_pipeline_spanish() {
-        let mut pipeline = KPipeline::new("es");
+        let pipeline = KPipeline::new("es");
         let result = pipeline.process("hola mundo");
         assert!(!result.phonemes.is_empty());
         assert!(result.tokens.len() > 2);
@@ -465,7 +1041,7 @@ mod tests {
     #[test]
     #[cfg(feature = "indonesian")]
     fn test_pipeline_indonesian() {
-        let mut pipeline = KPipeline::new("id");
+        let pipeline = KPipeline::new("id");
         let result = pipeline.process("selamat pagi");
         assert!(!result.phonemes.is_empty());
         assert!(result.tokens.len() > 2);
@@ -474,7 +1050,7 @@ mod tests {
     #[test]
     #[cfg(feature = "turkish")]
     fn test_pipeline_turkish() {
-        let mut pipeline = KPipeline::new("tr");
+        let pipeline = KPipeline::new("tr");
         let result = pipeline.process("merhaba dünya");
         assert!(!result.phonemes.is_empty());
         assert!(result.tokens.len() > 2);
@@ -483,7 +1059,7 @@ mod tests {
     #[test]
     #[cfg(feature = "italian")]
     fn test_pipeline_italian() {
-        let mut pipeline = KPipeline::new("it");
+        let pipeline = KPipeline::new("it");
         let result = pipeline.process("ciao mondo");
         assert!(!result.phonemes.is_empty());
         assert!(result.tokens.len() > 2);
@@ -498,10 +1074,332 @@ mod tests {
         assert_eq!(pipeline.language(), &Language::Chinese);
     }
 
+    #[test]
+    fn test_custom_engine() {
+        struct UpperEngine;
+
+        impl G2PEngine for UpperEngine {
+            fn language_code(&self) -> &str {
+                "x-upper"
+            }
+
+            fn phonemize(&self, text: &str) -> String {
+                text.to_uppercase()
+            }
+        }
+
+        let mut pipeline = KPipeline::new("en-us");
+        pipeline.register_engine("x-upper", || Box::new(UpperEngine));
+        pipeline.set_language("x-upper");
+        assert_eq!(pipeline.language(), &Language::Custom("x-upper".to_string()));
+
+        let result = pipeline.process("aio");
+        assert_eq!(result.phonemes, "AIO");
+        assert_eq!(result.tokens.len(), 5);
+    }
+
+    #[test]
+    fn test_unregistered_language() {
+        let pipeline = KPipeline::with_registry("es", EngineRegistry::new());
+        let result = pipeline.process("hola");
+        assert!(result.phonemes.is_empty());
+        assert_eq!(result.tokens, vec![tokenizer::PAD_TOKEN, tokenizer::PAD_TOKEN]);
+    }
+
+    #[test]
+    fn test_try_unsupported_language() {
+        assert_eq!(
+            Language::try_from_str("klingon"),
+            Err(G2PError::UnsupportedLanguage("klingon".to_string()))
+        );
+        assert!(matches!(
+            KPipeline::try_new("klingon"),
+            Err(G2PError::UnsupportedLanguage(_))
+        ));
+        // No feature provides a Japanese engine yet
+        assert_eq!(
+            KPipeline::try_new("ja").err(),
+            Some(G2PError::UnsupportedLanguage("ja".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_try_feature_not_enabled() {
+        let mut pipeline = KPipeline::with_registry("en-us", EngineRegistry::new());
+        let err = pipeline.try_set_language("vi").unwrap_err();
+        if cfg!(feature = "vietnamese") {
+            assert_eq!(err, G2PError::UnsupportedLanguage("vi".to_string()));
+        } else {
+            assert_eq!(
+                err,
+                G2PError::FeatureNotEnabled { language: "vi".to_string(), feature: "vietnamese" }
+            );
+        }
+    }
+
+    #[test]
+    fn test_try_process_unmappable() {
+        let mut pipeline = KPipeline::new("en-us");
+        pipeline.register_engine("x-bad", || {
+            struct BadEngine;
+            impl G2PEngine for BadEngine {
+                fn language_code(&self) -> &str {
+                    "x-bad"
+                }
+                fn phonemize(&self, _text: &str) -> String {
+                    "a❓".to_string()
+                }
+            }
+            Box::new(BadEngine)
+        });
+        pipeline.try_set_language("x-bad").unwrap();
+        assert_eq!(
+            pipeline.try_process("anything").unwrap_err(),
+            G2PError::UnmappablePhoneme { phoneme: '❓', position: 1 }
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_try_process_english() {
+        crate::lexicon::install_test_dictionaries();
+        let pipeline = KPipeline::try_new("en-us").unwrap();
+        let result = pipeline.try_process("Hello, world!").unwrap();
+        assert_eq!(result.tokens, pipeline.process("Hello, world!").tokens);
+
+        let long_text = "word ".repeat(300);
+        assert!(matches!(
+            pipeline.try_process(&long_text),
+            Err(G2PError::InputTooLong { .. })
+        ));
+    }
+
+    #[test]
+    fn test_split_at_boundaries() {
+        let text = "Dr. Who? Yes.  It costs 3.50, or 4; fine";
+        let sentences: Vec<&str> = split_at_boundaries(text, 0..text.len(), BoundaryLevel::Sentence)
+            .into_iter()
+            .map(|r| &text[r])
+            .collect();
+        assert_eq!(sentences, vec!["Dr.", "Who?", "Yes.", "It costs 3.50, or 4; fine"]);
+
+        let clauses: Vec<&str> = split_at_boundaries(text, 15..text.len(), BoundaryLevel::Clause)
+            .into_iter()
+            .map(|r| &text[r])
+            .collect();
+        assert_eq!(clauses, vec!["It costs 3.50,", "or 4;", "fine"]);
+    }
+
+    #[test]
+    fn test_process_chunked() {
+        let mut pipeline = KPipeline::new("x-echo");
+        pipeline.register_engine("x-echo", || {
+            struct EchoEngine;
+            impl G2PEngine for EchoEngine {
+                fn language_code(&self) -> &str {
+                    "x-echo"
+                }
+                fn phonemize(&self, text: &str) -> String {
+                    text.to_string()
+                }
+            }
+            Box::new(EchoEngine)
+        });
+        pipeline.set_language("x-echo");
+
+        let sentence = format!("{}.", "abc ".repeat(60).trim_end());
+        let text = format!("{} {} {}", sentence, sentence, sentence);
+        let results = pipeline.process_chunked(&text);
+
+        assert_eq!(results.len(), 2);
+        for result in &results {
+            assert!(result.tokens.len() <= tokenizer::MAX_TOKENS + 2);
+            assert_eq!(result.phonemes, &text[result.span.clone()]);
+        }
+        assert_eq!(results[0].span, 0..sentence.len() * 2 + 1);
+
+        // A single sentence over budget is split at whitespace, never inside a word
+        let long = "abcd ".repeat(150);
+        for result in pipeline.process_chunked(&long) {
+            assert!(result.tokens.len() <= tokenizer::MAX_TOKENS + 2);
+            assert!(result.phonemes.split(' ').all(|w| w == "abcd"));
+        }
+
+        assert!(pipeline.process_chunked("   ").is_empty());
+
+        // A word over budget is split inside its phonemes
+        let word = "a".repeat(tokenizer::MAX_TOKENS * 2 + 10);
+        let results = pipeline.process_chunked(&word);
+        assert_eq!(results.len(), 3);
+        for result in &results {
+            assert!(result.tokens.len() <= tokenizer::MAX_TOKENS + 2);
+            assert_eq!(result.span, 0..word.len());
+        }
+        assert_eq!(results.iter().map(|r| r.phonemes.as_str()).collect::<String>(), word);
+
+        // Strict chunking reports unmappable phonemes but not input length
+        let strict: Vec<String> = pipeline.try_process_chunked(&text).unwrap().into_iter().map(|r| r.phonemes).collect();
+        let lenient: Vec<String> = pipeline.process_chunked(&text).into_iter().map(|r| r.phonemes).collect();
+        assert_eq!(strict, lenient);
+        assert!(matches!(pipeline.try_process(&text), Err(G2PError::InputTooLong { .. })));
+        assert!(matches!(
+            pipeline.try_process_chunked(&format!("{} 🚀", text)),
+            Err(G2PError::UnmappablePhoneme { phoneme: '🚀', .. })
+        ));
+    }
+
+    #[test]
+    fn test_process_chunked_regroups_greedily() {
+        // Joining more than 50 words adds phonemes, so a merged group of
+        // words has to shrink rather than fall apart into single words
+        struct InflatingEngine;
+        impl G2PEngine for InflatingEngine {
+            fn language_code(&self) -> &str {
+                "x-inflate"
+            }
+            fn phonemize(&self, text: &str) -> String {
+                match text.split_whitespace().count() {
+                    0..=50 => text.to_string(),
+                    _ => format!("{} {}", text, "x".repeat(tokenizer::MAX_TOKENS)),
+                }
+            }
+        }
+
+        let mut pipeline = KPipeline::new("x-inflate");
+        pipeline.register_engine("x-inflate", || Box::new(InflatingEngine));
+        pipeline.set_language("x-inflate");
+
+        let long = "abcd ".repeat(300);
+        let results = pipeline.process_chunked(&long);
+        for result in &results {
+            assert!(result.tokens.len() <= tokenizer::MAX_TOKENS + 2);
+            assert_eq!(result.phonemes.split(' ').count(), 50);
+        }
+        assert_eq!(results.len(), 6);
+    }
+
+    #[test]
+    fn test_process_chunked_ssml() {
+        let mut pipeline = KPipeline::new("x-echo");
+        pipeline.register_engine("x-echo", || {
+            struct EchoEngine;
+            impl G2PEngine for EchoEngine {
+                fn language_code(&self) -> &str {
+                    "x-echo"
+                }
+                fn phonemize(&self, text: &str) -> String {
+                    text.to_string()
+                }
+            }
+            Box::new(EchoEngine)
+        });
+        pipeline.set_language("x-echo");
+        pipeline.set_input_mode(InputMode::Ssml);
+
+        let ssml = format!("<speak>{}</speak>", "abc abc. ".repeat(200));
+        let results = pipeline.process_chunked(&ssml);
+        assert!(results.len() > 1);
+        for result in &results {
+            assert!(result.tokens.len() <= tokenizer::MAX_TOKENS + 2);
+            assert!(result.phonemes.ends_with('.'));
+            assert_eq!(result.span, 0..ssml.len());
+        }
+        let words: usize = results.iter().map(|r| r.phonemes.split(' ').count()).sum();
+        assert_eq!(words, 400);
+    }
+
+    #[test]
+    fn test_process_chunked_phonemizes_pieces_once() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        struct CountingEngine(Arc<AtomicUsize>);
+        impl G2PEngine for CountingEngine {
+            fn language_code(&self) -> &str {
+                "x-count"
+            }
+            fn phonemize(&self, text: &str) -> String {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                text.to_string()
+            }
+        }
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut pipeline = KPipeline::new("x-count");
+        let counter = calls.clone();
+        pipeline.register_engine("x-count", move || Box::new(CountingEngine(counter.clone())));
+        pipeline.set_language("x-count");
+
+        // One sentence of 300 words, split at whitespace: each word is
+        // phonemized once, plus the sentence, clause and merged chunks
+        let long = "abcd ".repeat(300);
+        let results = pipeline.process_chunked(&long);
+        assert!(results.len() > 1);
+        assert!(calls.load(Ordering::SeqCst) <= 300 + 2 + results.len());
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_alignment_english() {
+        let mut pipeline = KPipeline::new("en-us");
+        assert!(pipeline.process("Hello world").segments.is_none());
+
+        pipeline.set_alignment(true);
+        let text = "I have 3 apples.";
+        let result = pipeline.process(text);
+        let segments = result.segments.unwrap();
+        assert_eq!(segments.len(), 4);
+
+        let three = &segments[2];
+        assert_eq!(&text[three.source.clone()], "3");
+        assert_eq!(three.normalized, "three");
+        assert!(result.phonemes.contains(&three.phonemes));
+        assert_eq!(
+            tokenizer::tokens_to_phonemes(&result.tokens[three.tokens.clone()]),
+            three.phonemes
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_user_lexicon() {
+        let mut pipeline = KPipeline::new("en-gb");
+        let before = pipeline.process("Kokoro").phonemes;
+        pipeline.add_user_word("Kokoro", "kəkˈɔːɹəʊ");
+        assert_ne!(pipeline.process("Kokoro").phonemes, before);
+        assert_eq!(pipeline.process("Kokoro").phonemes, "kəkˈɔːɹəʊ");
+
+        let path = std::env::temp_dir().join("kokoro_g2p_test_user_lexicon.tsv");
+        std::fs::write(&path, "Zyxq\tzˈɪks\n").unwrap();
+        assert_eq!(pipeline.load_user_lexicon(&path).unwrap(), 1);
+        std::fs::remove_file(&path).ok();
+
+        pipeline.set_language("en-us");
+        assert_eq!(pipeline.process("Zyxq").phonemes, "zˈɪks");
+        assert!(pipeline.load_user_lexicon("/nonexistent/lexicon.json").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "spanish")]
+    fn test_alignment_spanish() {
+        let mut pipeline = KPipeline::new("es");
+        pipeline.set_alignment(true);
+        let result = pipeline.process("hola mundo");
+        assert_eq!(result.phonemes, pipeline.registry.get("es").unwrap().phonemize("hola mundo"));
+
+        let segments = result.segments.unwrap();
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[1].source, 5..10);
+        assert_eq!(
+            tokenizer::tokens_to_phonemes(&result.tokens[segments[1].tokens.clone()]),
+            segments[1].phonemes
+        );
+    }
+
     #[test]
     #[cfg(feature = "german")]
     fn test_pipeline_german() {
-        let mut pipeline = KPipeline::new("de");
+        let pipeline = KPipeline::new("de");
         let result = pipeline.process("Guten Tag");
         assert!(!result.phonemes.is_empty());
         assert!(result.tokens.len() > 2);
@@ -510,7 +1408,7 @@ mod tests {
     #[test]
     #[cfg(feature = "portuguese")]
     fn test_pipeline_portuguese() {
-        let mut pipeline = KPipeline::new("pt");
+        let pipeline = KPipeline::new("pt");
         let result = pipeline.process("olá mundo");
         assert!(!result.phonemes.is_empty());
         assert!(result.tokens.len() > 2);
@@ -519,7 +1417,7 @@ mod tests {
     #[test]
     #[cfg(feature = "korean")]
     fn test_pipeline_korean() {
-        let mut pipeline = KPipeline::new("ko");
+        let pipeline = KPipeline::new("ko");
         let result = pipeline.process("안녕하세요");
         assert!(!result.phonemes.is_empty());
         assert!(result.tokens.len() > 2);
@@ -528,9 +1426,216 @@ mod tests {
     #[test]
     #[cfg(feature = "vietnamese")]
     fn test_pipeline_vietnamese() {
-        let mut pipeline = KPipeline::new("vi");
+        let pipeline = KPipeline::new("vi");
         let result = pipeline.process("xin chào");
         assert!(!result.phonemes.is_empty());
         assert!(result.tokens.len() > 2);
     }
+
+    #[test]
+    #[cfg(feature = "spanish")]
+    fn test_result_to_json() {
+        let mut pipeline = KPipeline::new("es");
+        pipeline.set_alignment(true);
+        let result = pipeline.process("hola mundo");
+        let json = result.to_json();
+        assert_eq!(json["phonemes"], result.phonemes.as_str());
+        assert_eq!(json["tokens"].as_array().unwrap().len(), result.tokens.len());
+        assert_eq!(json["span"], serde_json::json!([0, 10]));
+        assert_eq!(json["segments"][1]["word"], "mundo");
+        assert_eq!(json["segments"][1]["source"], serde_json::json!([5, 10]));
+        assert!(json["detected"].is_null());
+    }
+
+    #[test]
+    fn test_pipeline_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<KPipeline>();
+    }
+
+    #[test]
+    #[cfg(feature = "spanish")]
+    fn test_shared_pipeline() {
+        let pipeline = std::sync::Arc::new(KPipeline::new("es"));
+        assert!(!pipeline.registry().is_initialized("es"));
+        pipeline.warm_up(&[Language::Spanish]).unwrap();
+        assert!(pipeline.registry().is_initialized("es"));
+
+        let expected = pipeline.process("hola mundo").tokens;
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let pipeline = std::sync::Arc::clone(&pipeline);
+                std::thread::spawn(move || pipeline.process("hola mundo").tokens)
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "chinese"))]
+    fn test_warm_up_unavailable() {
+        let pipeline = KPipeline::with_registry("x-none", EngineRegistry::new());
+        assert!(matches!(
+            pipeline.warm_up(&[Language::Chinese]),
+            Err(G2PError::FeatureNotEnabled { feature: "chinese", .. })
+        ));
+    }
+
+    #[test]
+    fn test_vocabulary_coverage() {
+        let samples = [
+            ("en-us", "The quick brown fox jumps over 12 lazy dogs!"),
+            ("zh", "你好，我们今天去北京。"),
+            ("es", "¿Dónde está la cigüeña? Mañana, sí."),
+            ("id", "Selamat pagi, apa kabar? Nyanyi dan ngopi."),
+            ("tr", "Merhaba dünya! Çok güzel bir ağaç."),
+            ("it", "Buongiorno, gli gnocchi e lo zucchero."),
+            ("de", "Die Häuser sind schön. Freude, Eier, Bäume und Pfeffer."),
+            ("pt", "Olá, coração! Pães e lhamas no ninho."),
+            ("ko", "안녕하세요 까치 빵 떡 쌀 짜다"),
+            ("vi", "Xin chào, tôi là người Việt Nam. Bạn ăn phở bò."),
+        ];
+        for (code, text) in samples {
+            let Ok(mut pipeline) = KPipeline::try_new(code) else {
+                continue;
+            };
+            let result = pipeline.process(text);
+            let report = Vocabulary::builtin().validate(&result.phonemes);
+            assert!(report.is_complete(), "{}: {:?} in {}", code, report.unmapped_chars(), result.phonemes);
+
+            // The plain function API and aligned output get the same fallbacks
+            let plain = crate::text_to_phonemes(text, code);
+            let report = Vocabulary::builtin().validate(&plain);
+            assert!(report.is_complete(), "{}: {:?} in {}", code, report.unmapped_chars(), plain);
+            assert_eq!(plain, result.phonemes, "{}", code);
+
+            pipeline.set_alignment(true);
+            let aligned = pipeline.process(text);
+            assert_eq!(aligned.phonemes, result.phonemes, "{}", code);
+            for segment in aligned.segments.iter().flatten() {
+                let report = Vocabulary::builtin().validate(&segment.phonemes);
+                assert!(report.is_complete(), "{}: {:?} in {}", code, report.unmapped_chars(), segment.phonemes);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_ssml_input_mode() {
+        let mut pipeline = KPipeline::new("en-us");
+        let ssml = r#"<speak>I say <phoneme alphabet="ipa" ph="təmˈɑɾO">tomato</phoneme><break strength="strong"/> <say-as interpret-as="characters">TTS</say-as></speak>"#;
+
+        // Plain text stays the default
+        assert_eq!(pipeline.input_mode(), InputMode::PlainText);
+        pipeline.set_input_mode(InputMode::Ssml);
+        let result = pipeline.process(ssml);
+        assert!(result.phonemes.contains("təmˈɑɾO."), "{}", result.phonemes);
+        assert!(!result.phonemes.contains("speak"));
+        assert_eq!(result.tokens, tokenizer::phonemes_to_tokens(&result.phonemes));
+
+        assert!(matches!(
+            pipeline.try_process("<speak>unclosed"),
+            Err(G2PError::InvalidInput(_))
+        ));
+        assert!(pipeline.process("<speak>unclosed").phonemes.is_empty());
+    }
+
+    #[test]
+    #[cfg(all(feature = "english", feature = "spanish"))]
+    fn test_ssml_lang_switch() {
+        let pipeline = KPipeline::new("en-us");
+        let result = pipeline.process_ssml(r#"Hello <lang xml:lang="es">hola</lang>"#);
+        let spanish = pipeline.registry.get("es").unwrap().phonemize("hola");
+        assert!(result.phonemes.ends_with(&spanish));
+
+        assert!(matches!(
+            pipeline.try_process_ssml(r#"<lang xml:lang="xx">hi</lang>"#),
+            Err(G2PError::UnsupportedLanguage(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(all(feature = "english", feature = "chinese"))]
+    fn test_code_switching() {
+        let mut pipeline = KPipeline::new("zh");
+        let english = pipeline.registry.get("en-us").unwrap().phonemize("iPhone");
+        assert!(!pipeline.process("我的iPhone坏了").phonemes.contains(&english));
+
+        pipeline.set_fallback_language(Some("en"));
+        assert_eq!(pipeline.fallback_language(), Some(&Language::EnglishUS));
+        let result = pipeline.process("我的iPhone坏了");
+        assert!(result.phonemes.contains(&english), "{}", result.phonemes);
+        assert!(result.phonemes.contains('ㄨ'));
+        assert_eq!(result.tokens, tokenizer::phonemes_to_tokens(&result.phonemes));
+        assert_eq!(pipeline.try_process("我的iPhone坏了").unwrap().phonemes, result.phonemes);
+
+        // All-Latin input goes entirely to the fallback
+        assert_eq!(pipeline.process("iPhone").phonemes, english);
+
+        // And the other way around
+        let mut pipeline = KPipeline::new("en-us");
+        pipeline.set_fallback_language(Some("zh"));
+        let chinese = pipeline.registry.get("zh").unwrap().phonemize("北京");
+        assert!(pipeline.process("I love 北京 food").phonemes.contains(&chinese));
+    }
+
+    #[test]
+    fn test_fallback_language_errors() {
+        let mut pipeline = KPipeline::new("en-us");
+        assert!(matches!(
+            pipeline.try_set_fallback_language(Some("xx")),
+            Err(G2PError::UnsupportedLanguage(_))
+        ));
+        pipeline.set_fallback_language(Some("xx"));
+        assert_eq!(pipeline.fallback_language(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "english")]
+    fn test_custom_vocabulary() {
+        crate::lexicon::install_test_dictionaries();
+        // A checkpoint without the primary stress marker
+        let ids = tokenizer::get_vocabulary()
+            .into_iter()
+            .filter(|&c| c != 'ˈ')
+            .map(|c| (c, tokenizer::phoneme_to_id(c).unwrap()))
+            .collect();
+        let mut pipeline = KPipeline::new("en-us");
+        pipeline.set_alignment(true);
+        pipeline.set_vocabulary(Vocabulary::from_map(ids));
+
+        let result = pipeline.process("two three");
+        assert_eq!(result.tokens, pipeline.vocabulary().phonemes_to_tokens(&result.phonemes));
+        assert!(!result.tokens.contains(&tokenizer::phoneme_to_id('ˈ').unwrap()));
+        let three = &result.segments.unwrap()[1];
+        assert_eq!(
+            pipeline.vocabulary().tokens_to_phonemes(&result.tokens[three.tokens.clone()]),
+            three.phonemes.replace('ˈ', "")
+        );
+
+        assert!(matches!(
+            pipeline.try_process("two"),
+            Err(G2PError::UnmappablePhoneme { phoneme: 'ˈ', .. })
+        ));
+    }
+
+    #[test]
+    #[cfg(all(feature = "english", feature = "spanish"))]
+    fn test_auto_language() {
+        let pipeline = KPipeline::try_new("auto").unwrap();
+        assert_eq!(pipeline.language(), &Language::Auto);
+
+        let result = pipeline.process("¿Dónde está la biblioteca?");
+        let detected = result.detected.unwrap();
+        assert_eq!(detected.language, Language::Spanish);
+        assert!(detected.confidence > 0.5);
+        assert_eq!(result.phonemes, KPipeline::new("es").to_phonemes("¿Dónde está la biblioteca?"));
+
+        let result = pipeline.try_process("Good morning, how are you today?").unwrap();
+        assert_eq!(result.detected.unwrap().language, Language::EnglishUS);
+
+        assert!(KPipeline::new("en-us").process("Hello").detected.is_none());
+    }
 }
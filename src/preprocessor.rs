@@ -1,12 +1,15 @@
 //! Text preprocessing for G2P conversion
 //!
 //! Handles normalization of:
-//! - Numbers (123 -> one hundred twenty three)
+//! - Numbers (123 -> one hundred twenty three), read as years, cardinals or
+//!   digit strings depending on context (see [`normalize_numbers`])
 //! - Currency ($123.45 -> one hundred twenty three dollars and forty five cents)
+//! - Fractions, ranges, scientific notation, phone numbers and versions
 //! - Time (2:30 PM -> two thirty PM)
 //! - Ordinals (1st -> first)
 //! - Abbreviations (Dr. -> Doctor)
 
+use crate::markup::{self, Override};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::collections::HashMap;
@@ -68,73 +71,244 @@ static ABBREVIATIONS: Lazy<HashMap<&str, &str>> = Lazy::new(|| {
     m
 });
 
-/// Currency symbols and names
-static CURRENCIES: Lazy<HashMap<char, (&str, &str)>> = Lazy::new(|| {
-    let mut m = HashMap::new();
-    m.insert('$', ("dollar", "cent"));
-    m.insert('£', ("pound", "pence"));
-    m.insert('€', ("euro", "cent"));
-    m.insert('¥', ("yen", "sen"));
-    m
-});
+/// Spoken names of a currency's unit and subunit, singular and plural
+///
+/// An empty subunit means amounts are not split into subunits.
+struct Currency {
+    unit: &'static str,
+    units: &'static str,
+    subunit: &'static str,
+    subunits: &'static str,
+}
+
+const fn currency(unit: &'static str, units: &'static str, subunit: &'static str, subunits: &'static str) -> Currency {
+    Currency { unit, units, subunit, subunits }
+}
+
+/// Currency symbols and ISO 4217 codes; the first entry is the fallback
+const CURRENCIES: &[(&str, Currency)] = &[
+    ("$", currency("dollar", "dollars", "cent", "cents")),
+    ("US$", currency("US dollar", "US dollars", "cent", "cents")),
+    ("A$", currency("Australian dollar", "Australian dollars", "cent", "cents")),
+    ("C$", currency("Canadian dollar", "Canadian dollars", "cent", "cents")),
+    ("NZ$", currency("New Zealand dollar", "New Zealand dollars", "cent", "cents")),
+    ("HK$", currency("Hong Kong dollar", "Hong Kong dollars", "cent", "cents")),
+    ("S$", currency("Singapore dollar", "Singapore dollars", "cent", "cents")),
+    ("£", currency("pound", "pounds", "penny", "pence")),
+    ("€", currency("euro", "euros", "cent", "cents")),
+    ("¥", currency("yen", "yen", "", "")),
+    ("¢", currency("cent", "cents", "", "")),
+    ("₹", currency("rupee", "rupees", "paisa", "paise")),
+    ("₩", currency("won", "won", "", "")),
+    ("₽", currency("ruble", "rubles", "kopek", "kopeks")),
+    ("₺", currency("lira", "lira", "", "")),
+    ("₦", currency("naira", "naira", "kobo", "kobo")),
+    ("₱", currency("peso", "pesos", "centavo", "centavos")),
+    ("₪", currency("shekel", "shekels", "agora", "agorot")),
+    ("₫", currency("dong", "dong", "", "")),
+    ("₿", currency("bitcoin", "bitcoins", "", "")),
+    ("USD", currency("dollar", "dollars", "cent", "cents")),
+    ("AUD", currency("Australian dollar", "Australian dollars", "cent", "cents")),
+    ("CAD", currency("Canadian dollar", "Canadian dollars", "cent", "cents")),
+    ("NZD", currency("New Zealand dollar", "New Zealand dollars", "cent", "cents")),
+    ("HKD", currency("Hong Kong dollar", "Hong Kong dollars", "cent", "cents")),
+    ("SGD", currency("Singapore dollar", "Singapore dollars", "cent", "cents")),
+    ("EUR", currency("euro", "euros", "cent", "cents")),
+    ("GBP", currency("pound", "pounds", "penny", "pence")),
+    ("JPY", currency("yen", "yen", "", "")),
+    ("CNY", currency("yuan", "yuan", "", "")),
+    ("RMB", currency("yuan", "yuan", "", "")),
+    ("INR", currency("rupee", "rupees", "paisa", "paise")),
+    ("CHF", currency("Swiss franc", "Swiss francs", "centime", "centimes")),
+    ("KRW", currency("won", "won", "", "")),
+    ("RUB", currency("ruble", "rubles", "kopek", "kopeks")),
+    ("BRL", currency("real", "reais", "centavo", "centavos")),
+    ("MXN", currency("peso", "pesos", "centavo", "centavos")),
+    ("ZAR", currency("rand", "rand", "cent", "cents")),
+    ("SEK", currency("krona", "kronor", "", "")),
+    ("NOK", currency("krone", "kroner", "", "")),
+    ("DKK", currency("krone", "kroner", "", "")),
+    ("TRY", currency("lira", "lira", "", "")),
+    ("BTC", currency("bitcoin", "bitcoins", "", "")),
+];
+
+/// Vulgar fraction characters, which NFKC would run together with a preceding digit
+const VULGAR_FRACTIONS: &[(char, &str)] = &[
+    ('½', "1/2"), ('⅓', "1/3"), ('⅔', "2/3"), ('¼', "1/4"), ('¾', "3/4"), ('⅕', "1/5"),
+    ('⅖', "2/5"), ('⅗', "3/5"), ('⅘', "4/5"), ('⅙', "1/6"), ('⅚', "5/6"), ('⅐', "1/7"),
+    ('⅛', "1/8"), ('⅜', "3/8"), ('⅝', "5/8"), ('⅞', "7/8"), ('⅑', "1/9"), ('⅒', "1/10"),
+];
+
+/// Words before a four-digit number that mark it as a year
+const YEAR_CUES: &[&str] = &[
+    "in", "since", "year", "until", "till", "circa", "during", "before", "after", "from",
+    "between", "summer", "winter", "spring", "autumn", "vintage", "class", "copyright",
+];
+
+/// Words before a number that mark it as a quantity
+const QUANTITY_CUES: &[&str] = &[
+    "about", "over", "under", "than", "nearly", "almost", "approximately", "roughly", "exactly",
+    "only", "just", "total", "page", "pages", "room", "number", "step", "level", "top",
+];
+
+/// Lowercase words after a four-digit number that still leave it a year
+///
+/// Any other lowercase word (usually a noun being counted, as in "1500
+/// people") makes the number a cardinal.
+const YEAR_FOLLOWERS: &[&str] = &[
+    "was", "is", "were", "saw", "had", "has", "will", "would", "marked", "brought", "began",
+    "ended", "became", "and", "or", "to", "through", "until", "when", "where", "which", "we",
+    "i", "you", "he", "she", "they", "it", "there", "the", "onwards", "election", "census",
+    "season", "edition", "model", "olympics", "budget",
+];
+
+/// Era markers after a year
+const ERAS: &[&str] = &["AD", "BC", "BCE", "CE"];
+
+/// Words before a number that mark it as a code read digit by digit
+const DIGIT_CUES: &[&str] = &[
+    "zip", "zipcode", "postcode", "pin", "code", "account", "acct", "card", "serial",
+    "extension", "ext", "id", "ref", "reference", "tracking",
+];
+
+/// Words that mark a following "555-1234" as a phone number rather than a range
+const PHONE_CUES: &[&str] = &[
+    "call", "calling", "dial", "phone", "telephone", "tel", "fax", "mobile", "cell",
+];
+
+/// A number with optional thousands separators and decimals
+const NUMBER: &str = r"\d{1,3}(?:,\d{3})+(?:\.\d+)?|\d+(?:\.\d+)?";
 
 /// Regex patterns
 static NUMBER_PATTERN: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(?i)(\$|£|€|¥)?(-?\d{1,3}(?:,\d{3})*(?:\.\d+)?|\d+(?:\.\d+)?)(st|nd|rd|th|s|'s)?").unwrap()
+    Regex::new(&format!(
+        r"(?P<sign>[-−+±])?(?P<number>{}|\.\d+)(?P<suffix>(?i:st|nd|rd|th)\b|['’]?s\b|%)?",
+        NUMBER
+    ))
+    .unwrap()
 });
 
 static TIME_PATTERN: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"(\d{1,2}):(\d{2})(?::(\d{2}))?\s*(AM|PM|am|pm|a\.m\.|p\.m\.)?").unwrap()
 });
 
-#[allow(dead_code)] // Reserved for future year detection
-static YEAR_PATTERN: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"\b(1[0-9]{3}|20[0-9]{2})\b").unwrap()
+/// North American phone numbers, optionally with a country code, and ZIP+4 codes
+static PHONE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:\+\d{1,3}[\s.-]?|\b1[\s.-])?(?:\(\d{3}\)\s?|\b\d{3}[\s.-])\d{3}[.-]\d{4}\b|\b\d{5}-\d{4}\b").unwrap()
+});
+
+/// Local seven-digit phone numbers ("555-1234"), which look like ranges
+static LOCAL_PHONE_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b(?P<exchange>\d{3})-(?P<line>\d{4})\b").unwrap());
+
+static DATE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b(?:\d{1,2}/\d{1,2}/\d{2,4}|\d{4}-\d{1,2}-\d{1,2})\b").unwrap()
+});
+
+/// Dotted version numbers with at least three parts, or two after a "v"
+static VERSION_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b[vV]?\d+(?:\.\d+){2,}\b|\bv\d+(?:\.\d+)?\b").unwrap()
+});
+
+static SCIENTIFIC_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?P<sign>[-−+])?(?P<mantissa>\d+(?:\.\d+)?)(?:[eE]|\s?[x×]\s?10\^)(?P<exponent>[-−+]?\d+)\b").unwrap()
+});
+
+/// Amounts with a currency symbol or code before or after them
+static CURRENCY_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    let mut symbols: Vec<&str> = CURRENCIES.iter().map(|(symbol, _)| *symbol).collect();
+    // Longest first, so "US$" wins over "$"
+    symbols.sort_by_key(|symbol| std::cmp::Reverse(symbol.len()));
+    let (codes, symbols): (Vec<&str>, Vec<&str>) = symbols
+        .into_iter()
+        .partition(|symbol| symbol.bytes().all(|b| b.is_ascii_uppercase()));
+    let symbols = symbols.iter().map(|s| regex::escape(s)).collect::<Vec<_>>().join("|");
+    let codes = codes.join("|");
+    Regex::new(&format!(
+        r"(?P<sign>[-−])?(?:(?P<symbol>{symbols})\s?|\b(?P<code>{codes})\s?)(?P<amount>{number})(?:\s(?P<scale>thousand|million|billion|trillion)\b|(?P<abbr>k|K|m|M|bn|b|B|tn)\b)?|(?P<amount_first>{number})\s?(?:(?P<symbol_after>{symbols})|(?P<code_after>{codes})\b)",
+        symbols = symbols,
+        codes = codes,
+        number = NUMBER,
+    ))
+    .unwrap()
+});
+
+/// Fractions, optionally after a whole number ("1 1/2")
+static FRACTION_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:\b(?P<whole>\d+)\s)?\b(?P<numerator>\d+)[/⁄](?P<denominator>\d+)").unwrap()
+});
+
+static RANGE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"(?P<from>{number})(?:\s?[–—]\s?|\s-\s|-)(?P<to>{number})(?P<percent>%)?",
+        number = NUMBER
+    ))
+    .unwrap()
+});
+
+/// Abbreviated decades ("'80s")
+static DECADE_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"['’](?P<decade>[1-9]0)s\b").unwrap());
+
+/// A month name, optionally followed by a day, just before a year
+static MONTH_BEFORE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(?:january|february|march|april|may|june|july|august|september|october|november|december|jan|feb|mar|apr|jun|jul|aug|sept|sep|oct|nov|dec)\b\.?(?:\s+\d{1,2}(?:st|nd|rd|th)?,?)?\s+$",
+    )
+    .unwrap()
 });
 
 /// Convert a number to words
 pub fn number_to_words(n: i64) -> String {
+    number_to_words_dialect(n, false)
+}
+
+/// Convert a number to words, in British style if `british`
+///
+/// British English says "nought" for zero and puts "and" before the tens
+/// and units ("one hundred and five", "two thousand and ten").
+pub fn number_to_words_dialect(n: i64, british: bool) -> String {
     if n == 0 {
-        return "zero".to_string();
+        return if british { "nought" } else { "zero" }.to_string();
     }
-
-    let mut num = n;
-    let mut result = String::new();
-
-    if num < 0 {
-        result.push_str("minus ");
-        num = -num;
+    let words = cardinal_words(n.unsigned_abs(), british);
+    if n < 0 {
+        format!("minus {}", words)
+    } else {
+        words
     }
+}
 
-    let parts = [
+/// Spell out a positive number
+fn cardinal_words(n: u64, british: bool) -> String {
+    const SCALES: [(u64, &str); 4] = [
         (1_000_000_000_000, "trillion"),
         (1_000_000_000, "billion"),
         (1_000_000, "million"),
         (1_000, "thousand"),
-        (100, "hundred"),
     ];
 
-    for (divisor, name) in parts {
-        if num >= divisor {
-            let count = num / divisor;
-            num %= divisor;
-            if !result.is_empty() && !result.ends_with(' ') {
-                result.push(' ');
-            }
-            result.push_str(&small_number_to_words(count as i32));
-            result.push(' ');
-            result.push_str(name);
+    let mut parts = Vec::new();
+    let mut rest = n;
+    for (divisor, name) in SCALES {
+        if rest >= divisor {
+            parts.push(format!("{} {}", cardinal_words(rest / divisor, british), name));
+            rest %= divisor;
         }
     }
 
-    if num > 0 {
-        if !result.is_empty() && !result.ends_with(' ') {
-            result.push(' ');
+    if rest >= 100 {
+        parts.push(format!("{} hundred", small_number_to_words((rest / 100) as i32)));
+        rest %= 100;
+        if british && rest > 0 {
+            parts.push("and".to_string());
         }
-        result.push_str(&small_number_to_words(num as i32));
+    } else if british && rest > 0 && !parts.is_empty() {
+        parts.push("and".to_string());
+    }
+    if rest > 0 {
+        parts.push(small_number_to_words(rest as i32));
     }
 
-    result.trim().to_string()
+    parts.join(" ")
 }
 
 /// Convert numbers 0-99 to words
@@ -163,11 +337,15 @@ fn small_number_to_words(n: i32) -> String {
 
 /// Convert ordinal number to words
 pub fn ordinal_to_words(n: i64) -> String {
+    ordinal_words(n, false)
+}
+
+fn ordinal_words(n: i64, british: bool) -> String {
     if n == 0 {
         return "zeroth".to_string();
     }
 
-    let cardinal = number_to_words(n);
+    let cardinal = number_to_words_dialect(n, british);
 
     // Handle special cases
     if cardinal.ends_with("one") {
@@ -192,15 +370,26 @@ pub fn ordinal_to_words(n: i64) -> String {
 }
 
 /// Convert year to words (e.g., 1984 -> nineteen eighty four)
+///
+/// The first years of a millennium are read as cardinals (2000 -> two
+/// thousand, 2005 -> two thousand five).
 pub fn year_to_words(year: i64) -> String {
+    year_to_words_dialect(year, false)
+}
+
+/// Convert year to words, in British style if `british` (2005 -> two
+/// thousand and five)
+pub fn year_to_words_dialect(year: i64, british: bool) -> String {
     if year < 1000 || year >= 3000 {
-        return number_to_words(year);
+        return number_to_words_dialect(year, british);
     }
 
     let century = year / 100;
     let decade = year % 100;
 
-    if decade == 0 {
+    if century % 10 == 0 && decade < 10 {
+        number_to_words_dialect(year, british)
+    } else if decade == 0 {
         format!("{} hundred", small_number_to_words(century as i32))
     } else if decade < 10 {
         format!(
@@ -217,8 +406,102 @@ pub fn year_to_words(year: i64) -> String {
     }
 }
 
+const MONTHS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June", "July", "August", "September",
+    "October", "November", "December",
+];
+
+/// Expand a date such as "2024-03-05" or "3/5/2024" according to `format`,
+/// in British style if `british`
+///
+/// Supported formats are "mdy" (the default), "dmy", "ymd", "md", "dm" and "y".
+/// Four-digit leading fields are always treated as years.
+pub(crate) fn date_to_words(date: &str, format: Option<&str>, british: bool) -> Option<String> {
+    let fields: Vec<i64> = date
+        .split(['-', '/', '.', ' '])
+        .filter(|f| !f.is_empty())
+        .map(|f| f.parse().ok())
+        .collect::<Option<_>>()?;
+    let format = match format {
+        Some(f) => f.to_lowercase(),
+        None if date.len() >= 4 && date[..4].bytes().all(|b| b.is_ascii_digit()) => "ymd".to_string(),
+        None => "mdy".to_string(),
+    };
+    if format.len() != fields.len() {
+        return None;
+    }
+
+    let mut year = None;
+    let mut month = None;
+    let mut day = None;
+    for (field, value) in format.chars().zip(fields) {
+        match field {
+            'y' => year = Some(value),
+            'm' => month = Some(value),
+            'd' => day = Some(value),
+            _ => return None,
+        }
+    }
+
+    let month_name = match month {
+        Some(m @ 1..=12) => Some(MONTHS[m as usize - 1]),
+        Some(_) => return None,
+        None => None,
+    };
+    let mut words = match (month_name, day) {
+        (Some(month), Some(day)) if format.starts_with('d') => {
+            format!("the {} of {}", ordinal_words(day, british), month)
+        }
+        (Some(month), Some(day)) => format!("{} {}", month, ordinal_words(day, british)),
+        (Some(month), None) => month.to_string(),
+        (None, _) => String::new(),
+    };
+    if let Some(year) = year {
+        if !words.is_empty() {
+            words.push_str(", ");
+        }
+        words.push_str(&year_to_words_dialect(year, british));
+    }
+    Some(words)
+}
+
 /// Convert time to words (e.g., 2:30 -> two thirty)
 pub fn time_to_words(hours: i32, minutes: i32, seconds: Option<i32>, period: Option<&str>) -> String {
+    time_to_words_dialect(hours, minutes, seconds, period, false)
+}
+
+/// Convert time to words, in British style if `british`
+///
+/// British English reads whole hours and five-minute steps relative to the
+/// hour (2:00 -> two o'clock, 2:15 -> quarter past two, 2:40 -> twenty to
+/// three); other times are read like American ones.
+pub fn time_to_words_dialect(
+    hours: i32,
+    minutes: i32,
+    seconds: Option<i32>,
+    period: Option<&str>,
+    british: bool,
+) -> String {
+    if british && minutes % 5 == 0 && seconds.unwrap_or(0) == 0 {
+        let clock_hour = |h: i32| match h % 12 {
+            0 => "twelve".to_string(),
+            h => small_number_to_words(h),
+        };
+        let mut result = match minutes {
+            0 => format!("{} o'clock", clock_hour(hours)),
+            15 => format!("quarter past {}", clock_hour(hours)),
+            30 => format!("half past {}", clock_hour(hours)),
+            45 => format!("quarter to {}", clock_hour(hours + 1)),
+            m if m < 30 => format!("{} past {}", small_number_to_words(m), clock_hour(hours)),
+            m => format!("{} to {}", small_number_to_words(60 - m), clock_hour(hours + 1)),
+        };
+        if let Some(p) = period {
+            result.push(' ');
+            result.push_str(p.to_uppercase().trim_matches('.').trim());
+        }
+        return result;
+    }
+
     let mut result = String::new();
 
     // Hours
@@ -266,59 +549,525 @@ pub fn time_to_words(hours: i32, minutes: i32, seconds: Option<i32>, period: Opt
 
 /// Convert currency amount to words
 pub fn currency_to_words(amount: f64, symbol: char) -> String {
-    let (unit, subunit) = CURRENCIES.get(&symbol).unwrap_or(&("dollar", "cent"));
+    let mut buf = [0; 4];
+    let currency = find_currency(symbol.encode_utf8(&mut buf)).unwrap_or(&CURRENCIES[0].1);
+    currency_words(amount < 0.0, &format!("{:.2}", amount.abs()), currency, None, false)
+}
+
+fn find_currency(symbol: &str) -> Option<&'static Currency> {
+    CURRENCIES.iter().find(|(s, _)| *s == symbol).map(|(_, currency)| currency)
+}
 
-    let whole = amount.abs().floor() as i64;
-    let frac = ((amount.abs() - whole as f64) * 100.0).round() as i64;
+/// Read an amount of money given as a string of digits with optional decimals
+///
+/// Amounts with a scale ("$2.5 million") or more than two decimals are read
+/// as decimals followed by the plural unit; others are split into units and
+/// subunits.
+fn currency_words(negative: bool, amount: &str, currency: &Currency, scale: Option<&str>, british: bool) -> String {
+    let (whole, frac) = amount.split_once('.').unwrap_or((amount, ""));
 
     let mut result = String::new();
-
-    if amount < 0.0 {
+    if negative {
         result.push_str("minus ");
     }
 
-    if whole > 0 {
-        result.push_str(&number_to_words(whole));
-        result.push(' ');
-        if whole == 1 {
-            result.push_str(unit);
-        } else {
-            result.push_str(unit);
-            result.push('s');
-        }
+    if let Some(scale) = scale {
+        result.push_str(&format!("{} {} {}", decimal_words(amount, british), scale, currency.units));
+        return result;
+    }
+    if frac.len() > 2 || (!frac.is_empty() && currency.subunit.is_empty()) {
+        let unit = if amount == "1" { currency.unit } else { currency.units };
+        result.push_str(&format!("{} {}", decimal_words(amount, british), unit));
+        return result;
     }
 
-    if frac > 0 {
-        if whole > 0 {
+    let whole_value = whole.parse::<u64>().ok();
+    let cents: u64 = format!("{:0<2}", frac).parse().unwrap_or(0);
+    if whole_value != Some(0) || cents == 0 {
+        let unit = if whole_value == Some(1) { currency.unit } else { currency.units };
+        let words = match whole_value {
+            Some(0) => "zero".to_string(),
+            _ => integer_words(whole, british),
+        };
+        result.push_str(&format!("{} {}", words, unit));
+    }
+    if cents > 0 {
+        if whole_value != Some(0) {
             result.push_str(" and ");
         }
-        result.push_str(&number_to_words(frac));
-        result.push(' ');
-        if frac == 1 {
-            result.push_str(subunit);
-        } else if *subunit == "pence" {
-            result.push_str(subunit);
-        } else {
-            result.push_str(subunit);
-            result.push('s');
+        let subunit = if cents == 1 { currency.subunit } else { currency.subunits };
+        result.push_str(&format!("{} {}", number_to_words_dialect(cents as i64, british), subunit));
+    }
+
+    result
+}
+
+/// Read a string of digits as a cardinal, falling back to digit by digit if it is too large
+fn integer_words(digits: &str, british: bool) -> String {
+    match digits.parse::<i64>() {
+        Ok(n) => number_to_words_dialect(n, british),
+        Err(_) => digit_words(digits),
+    }
+}
+
+/// Read every digit in `digits` on its own
+fn digit_words(digits: &str) -> String {
+    const DIGITS: [&str; 10] = ["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+
+    digits
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .map(|d| DIGITS[d as usize])
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Read a number with optional thousands separators and decimals
+///
+/// The decimals are read digit by digit from the string, so no precision is lost.
+fn decimal_words(number: &str, british: bool) -> String {
+    let number: String = number.chars().filter(|c| *c != ',').collect();
+    let (whole, frac) = number.split_once('.').unwrap_or((&number, ""));
+
+    let mut words = Vec::new();
+    if !whole.is_empty() {
+        words.push(integer_words(whole, british));
+    }
+    if !frac.is_empty() {
+        words.push("point".to_string());
+        for digit in frac.chars().filter_map(|c| c.to_digit(10)) {
+            words.push(number_to_words_dialect(digit as i64, british));
+        }
+    }
+    words.join(" ")
+}
+
+/// Read a fraction, using "a" for a numerator of one if `article`
+fn fraction_words(numerator: u64, denominator: u64, article: bool, british: bool) -> String {
+    let plural = numerator != 1;
+    let denominator = match (denominator, plural) {
+        (2, false) => "half".to_string(),
+        (2, true) => "halves".to_string(),
+        (4, false) => "quarter".to_string(),
+        (4, true) => "quarters".to_string(),
+        (d, false) => ordinal_words(d as i64, british),
+        (d, true) => format!("{}s", ordinal_words(d as i64, british)),
+    };
+    if article && !plural {
+        format!("a {}", denominator)
+    } else {
+        format!("{} {}", number_to_words_dialect(numerator as i64, british), denominator)
+    }
+}
+
+/// Make the last word of a spelled-out number plural ("nineteen nineties")
+fn pluralize(words: &str) -> String {
+    if let Some(stem) = words.strip_suffix('y') {
+        format!("{}ies", stem)
+    } else if words.ends_with('x') {
+        format!("{}es", words)
+    } else {
+        format!("{}s", words)
+    }
+}
+
+/// Whether a sign right after `before` stands on its own rather than joining a word
+/// to the number, as the hyphen in "COVID-19" does
+fn is_sign(before: &str) -> bool {
+    before
+        .chars()
+        .next_back()
+        .is_none_or(|c| c.is_whitespace() || "([{\"'".contains(c))
+}
+
+/// Prefix the spoken sign, or keep `sign` as it is if it is not one
+fn signed(sign: Option<&str>, before: &str, words: String) -> String {
+    let Some(sign) = sign else {
+        return words;
+    };
+    match sign {
+        _ if !is_sign(before) => format!("{}{}", sign, words),
+        "+" => format!("plus {}", words),
+        "±" => format!("plus or minus {}", words),
+        _ => format!("minus {}", words),
+    }
+}
+
+/// The last `n` bytes of `text`, moved forward to a character boundary
+fn tail(text: &str, n: usize) -> &str {
+    let mut start = text.len().saturating_sub(n);
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    &text[start..]
+}
+
+/// The word right before a number, skipping a separating ":" or "#"
+///
+/// Returns `None` at the start of a sentence or after other punctuation.
+fn previous_word(before: &str) -> Option<&str> {
+    let before = before.trim_end_matches(|c: char| c.is_whitespace() || c == ':' || c == '#');
+    let start = before
+        .char_indices()
+        .rev()
+        .find(|(_, c)| !c.is_alphanumeric())
+        .map_or(0, |(i, c)| i + c.len_utf8());
+    Some(&before[start..]).filter(|word| !word.is_empty())
+}
+
+/// The word right after a number, with abbreviation dots removed ("A.D." -> "AD")
+fn next_word(after: &str) -> String {
+    after
+        .trim_start()
+        .chars()
+        .take_while(|c| c.is_alphabetic() || *c == '.')
+        .filter(|c| *c != '.')
+        .collect()
+}
+
+/// Whether a four-digit number is a year, judging from the words around it
+fn reads_as_year(value: u64, before: &str, after: &str) -> bool {
+    if !(1000..=2099).contains(&value) {
+        return false;
+    }
+
+    let next = next_word(after);
+    if ERAS.contains(&next.as_str()) {
+        return true;
+    }
+    let previous = previous_word(before).map(str::to_lowercase);
+    if let Some(previous) = &previous {
+        if QUANTITY_CUES.contains(&previous.as_str()) {
+            return false;
         }
+        if YEAR_CUES.contains(&previous.as_str()) {
+            return true;
+        }
+    }
+    if MONTH_BEFORE_PATTERN.is_match(tail(before, 40)) {
+        return true;
+    }
+    // A counted noun follows: "1500 people"
+    if next.starts_with(|c: char| c.is_lowercase()) && !YEAR_FOLLOWERS.contains(&next.as_str()) {
+        return false;
     }
+    true
+}
 
-    if result.is_empty() {
-        result.push_str("zero ");
-        result.push_str(unit);
-        result.push('s');
+/// Whether an integer is a code read digit by digit, such as a ZIP code
+fn reads_as_digits(digits: &str, before: &str) -> bool {
+    if digits.len() > 15 || (digits.len() > 1 && digits.starts_with('0')) {
+        return true;
+    }
+    match previous_word(before) {
+        Some(word) if DIGIT_CUES.contains(&word.to_lowercase().as_str()) => true,
+        // A US state code: "CA 94103"
+        Some(word) => digits.len() == 5 && word.len() == 2 && word.bytes().all(|b| b.is_ascii_uppercase()),
+        None => false,
+    }
+}
+
+/// Read a plain number, deciding from the words around it between a year,
+/// a digit string and a cardinal
+fn number_words(number: &str, before: &str, after: &str, british: bool) -> String {
+    if number.contains([',', '.']) {
+        return decimal_words(number, british);
+    }
+    if reads_as_digits(number, before) {
+        return digit_words(number);
+    }
+    match number.parse::<u64>() {
+        Ok(value) if number.len() == 4 && reads_as_year(value, before, after) => year_to_words_dialect(value as i64, british),
+        _ => integer_words(number, british),
     }
+}
+
+/// Spell out the numbers in `text`, reading each one according to its context
+///
+/// Phone numbers and ZIP+4 codes are read digit by digit, then dates,
+/// versions ("v2.3.1"), scientific notation, currency, fractions ("1/2",
+/// "¾"), ranges ("5–10") and decades are expanded. Remaining numbers are
+/// read as years, digit strings or cardinals depending on the words around
+/// them, so "in 1500" is "fifteen hundred" but "1500 people" is "one
+/// thousand five hundred people".
+pub fn normalize_numbers(text: &str, british: bool) -> String {
+    let text = normalize_phone_numbers(text);
+    let text = normalize_dates(&text, british);
+    let text = normalize_versions(&text, british);
+    let text = normalize_scientific(&text, british);
+    let text = normalize_currency(&text, british);
+    let text = normalize_fractions(&text, british);
+    let text = normalize_ranges(&text, british);
+    let text = normalize_decades(&text);
+    normalize_plain_numbers(&text, british)
+}
 
+/// Split vulgar fractions into digits and a slash ("1½" -> "1 1/2")
+fn expand_vulgar_fractions(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut previous = None;
+    for c in text.chars() {
+        match VULGAR_FRACTIONS.iter().find(|(f, _)| *f == c) {
+            Some((_, fraction)) => {
+                if previous.is_some_and(|p: char| p.is_ascii_digit()) {
+                    result.push(' ');
+                }
+                result.push_str(fraction);
+            }
+            None => result.push(c),
+        }
+        previous = Some(c);
+    }
     result
 }
 
+fn normalize_phone_numbers(text: &str) -> String {
+    let text = PHONE_PATTERN.replace_all(text, |caps: &regex::Captures| phone_words(&caps[0]));
+    LOCAL_PHONE_PATTERN
+        .replace_all(&text, |caps: &regex::Captures| {
+            let whole = caps.get(0).unwrap();
+            if reads_as_phone(&caps["exchange"], &caps["line"], &text[..whole.start()]) {
+                phone_words(whole.as_str())
+            } else {
+                whole.as_str().to_string()
+            }
+        })
+        .to_string()
+}
+
+/// Read a phone number digit by digit, pausing between groups
+fn phone_words(phone: &str) -> String {
+    let groups = phone
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|group| !group.is_empty())
+        .map(digit_words)
+        .collect::<Vec<_>>()
+        .join(", ");
+    if phone.starts_with('+') {
+        format!("plus {}", groups)
+    } else {
+        groups
+    }
+}
+
+/// Whether "ddd-dddd" is a phone number: one of the few words before it is a
+/// phone cue ("call me at 555-1234"), or it cannot be a range because a
+/// part has a leading zero or the first part is not the smaller
+fn reads_as_phone(exchange: &str, line: &str, before: &str) -> bool {
+    let cued = before
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .rev()
+        .take(3)
+        .any(|word| PHONE_CUES.contains(&word.to_lowercase().as_str()));
+    cued || exchange.starts_with('0')
+        || line.starts_with('0')
+        || exchange.parse::<u32>().ok() >= line.parse::<u32>().ok()
+}
+
+fn normalize_dates(text: &str, british: bool) -> String {
+    DATE_PATTERN
+        .replace_all(text, |caps: &regex::Captures| {
+            let date = &caps[0];
+            // Slashed dates are month first in American and day first in British English
+            let format = (date.contains('/') && british).then_some("dmy");
+            date_to_words(date, format, british).unwrap_or_else(|| {
+                date.split(['/', '-'])
+                    .map(|field| integer_words(field, british))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+        })
+        .to_string()
+}
+
+fn normalize_versions(text: &str, british: bool) -> String {
+    VERSION_PATTERN
+        .replace_all(text, |caps: &regex::Captures| {
+            let version = &caps[0];
+            let (prefix, parts) = match version.strip_prefix(['v', 'V']) {
+                Some(parts) => ("version ", parts),
+                None => ("", version),
+            };
+            let parts: Vec<String> = parts.split('.').map(|part| integer_words(part, british)).collect();
+            format!("{}{}", prefix, parts.join(" point "))
+        })
+        .to_string()
+}
+
+fn normalize_scientific(text: &str, british: bool) -> String {
+    SCIENTIFIC_PATTERN
+        .replace_all(text, |caps: &regex::Captures| {
+            let before = &text[..caps.get(0).unwrap().start()];
+            let exponent = &caps["exponent"];
+            let (negative, digits) = match exponent.strip_prefix(['-', '−', '+']) {
+                Some(digits) => (!exponent.starts_with('+'), digits),
+                None => (false, exponent),
+            };
+            let mut words = format!("{} times ten to the power of ", decimal_words(&caps["mantissa"], british));
+            if negative {
+                words.push_str("minus ");
+            }
+            words.push_str(&integer_words(digits, british));
+            signed(caps.name("sign").map(|m| m.as_str()), before, words)
+        })
+        .to_string()
+}
+
+fn normalize_currency(text: &str, british: bool) -> String {
+    CURRENCY_PATTERN
+        .replace_all(text, |caps: &regex::Captures| {
+            let symbol = ["symbol", "code", "symbol_after", "code_after"]
+                .iter()
+                .find_map(|name| caps.name(name))
+                .map_or("$", |m| m.as_str());
+            let currency = find_currency(symbol).unwrap_or(&CURRENCIES[0].1);
+            let amount: String = caps
+                .name("amount")
+                .or_else(|| caps.name("amount_first"))
+                .map_or("", |m| m.as_str())
+                .chars()
+                .filter(|c| *c != ',')
+                .collect();
+            let scale = caps.name("scale").map(|m| m.as_str()).or_else(|| {
+                caps.name("abbr").map(|m| match m.as_str() {
+                    "k" | "K" => "thousand",
+                    "m" | "M" => "million",
+                    "tn" => "trillion",
+                    _ => "billion",
+                })
+            });
+            let before = &text[..caps.get(0).unwrap().start()];
+            match caps.name("sign") {
+                Some(sign) if !is_sign(before) => {
+                    format!("{}{}", sign.as_str(), currency_words(false, &amount, currency, scale, british))
+                }
+                sign => currency_words(sign.is_some(), &amount, currency, scale, british),
+            }
+        })
+        .to_string()
+}
+
+fn normalize_fractions(text: &str, british: bool) -> String {
+    FRACTION_PATTERN
+        .replace_all(text, |caps: &regex::Captures| {
+            let (Ok(numerator), Ok(denominator)) = (caps["numerator"].parse::<u64>(), caps["denominator"].parse::<u64>())
+            else {
+                return caps[0].to_string();
+            };
+            let whole = caps.name("whole").map(|m| integer_words(m.as_str(), british));
+
+            // "24/7" and "50/50" are not fractions
+            if denominator < 2 || (numerator >= denominator && denominator > 4) {
+                let pair = format!(
+                    "{} {}",
+                    integer_words(&caps["numerator"], british),
+                    integer_words(&caps["denominator"], british)
+                );
+                return whole.map_or(pair.clone(), |whole| format!("{} {}", whole, pair));
+            }
+            match whole {
+                Some(whole) if numerator < denominator => {
+                    format!("{} and {}", whole, fraction_words(numerator, denominator, true, british))
+                }
+                Some(whole) => format!("{} {}", whole, fraction_words(numerator, denominator, false, british)),
+                None => fraction_words(numerator, denominator, false, british),
+            }
+        })
+        .to_string()
+}
+
+fn normalize_ranges(text: &str, british: bool) -> String {
+    RANGE_PATTERN
+        .replace_all(text, |caps: &regex::Captures| {
+            let whole = caps.get(0).unwrap();
+            let (before, after) = (&text[..whole.start()], &text[whole.end()..]);
+            let (from, to) = (&caps["from"], &caps["to"]);
+
+            let years = match (from.parse::<u64>(), to.parse::<u64>()) {
+                (Ok(start), Ok(end)) if from.len() == 4 && reads_as_year(start, before, after) => match to.len() {
+                    4 if end > start => Some(year_to_words_dialect(end as i64, british)),
+                    2 if end > start % 100 => Some(match end {
+                        // "2005-08" is not "two thousand five to oh eight"
+                        _ if start / 100 % 10 == 0 => year_to_words_dialect((start - start % 100 + end) as i64, british),
+                        0..=9 => format!("oh {}", small_number_to_words(end as i32)),
+                        _ => small_number_to_words(end as i32),
+                    }),
+                    _ => None,
+                }
+                .map(|end| format!("{} to {}", year_to_words_dialect(start as i64, british), end)),
+                _ => None,
+            };
+            let mut words = years.unwrap_or_else(|| {
+                format!("{} to {}", decimal_words(from, british), decimal_words(to, british))
+            });
+            if caps.name("percent").is_some() {
+                words.push_str(" percent");
+            }
+            words
+        })
+        .to_string()
+}
+
+fn normalize_decades(text: &str) -> String {
+    DECADE_PATTERN
+        .replace_all(text, |caps: &regex::Captures| {
+            let decade: i32 = caps["decade"].parse().unwrap_or(0);
+            pluralize(&small_number_to_words(decade))
+        })
+        .to_string()
+}
+
+fn normalize_plain_numbers(text: &str, british: bool) -> String {
+    NUMBER_PATTERN
+        .replace_all(text, |caps: &regex::Captures| {
+            let whole = caps.get(0).unwrap();
+            let (before, after) = (&text[..whole.start()], &text[whole.end()..]);
+            let number = &caps["number"];
+            let integer = !number.contains('.');
+            let value = number.replace(',', "").parse::<u64>().ok();
+
+            let words = match caps.name("suffix").map(|m| m.as_str().to_lowercase()) {
+                Some(suffix) if integer && matches!(suffix.as_str(), "st" | "nd" | "rd" | "th") => {
+                    match value.and_then(|v| i64::try_from(v).ok()) {
+                        Some(n) => ordinal_words(n, british),
+                        None => format!("{}{}", decimal_words(number, british), suffix),
+                    }
+                }
+                // Decades: "1990s" -> "nineteen nineties", "2000s" -> "two thousands"
+                Some(suffix) if suffix == "s" => match value {
+                    Some(v) if number.len() == 4 && (1000..=2099).contains(&v) && v % 10 == 0 => {
+                        if v % 1000 == 0 {
+                            pluralize(&number_to_words_dialect(v as i64, british))
+                        } else {
+                            pluralize(&year_to_words_dialect(v as i64, british))
+                        }
+                    }
+                    _ => pluralize(&number_words(number, before, after, british)),
+                },
+                Some(suffix) if suffix == "%" => format!("{} percent", decimal_words(number, british)),
+                Some(suffix) if suffix.ends_with('s') => {
+                    format!("{}{}", number_words(number, before, after, british), &caps["suffix"])
+                }
+                Some(suffix) => format!("{}{}", decimal_words(number, british), suffix),
+                None => number_words(number, before, after, british),
+            };
+            signed(caps.name("sign").map(|m| m.as_str()), before, words)
+        })
+        .to_string()
+}
+
 /// Preprocess text for G2P conversion
 pub fn preprocess(text: &str) -> String {
+    preprocess_dialect(text, false)
+}
+
+/// Preprocess text for G2P conversion, spelling out numbers and times in British style if `british`
+pub fn preprocess_dialect(text: &str, british: bool) -> String {
     use unicode_normalization::UnicodeNormalization;
 
-    // Normalize unicode
-    let mut result: String = text.nfkc().collect();
+    // Normalize unicode, keeping vulgar fractions apart from preceding digits
+    let mut result: String = expand_vulgar_fractions(text).nfkc().collect();
 
     // Expand abbreviations
     for (abbr, expansion) in ABBREVIATIONS.iter() {
@@ -332,68 +1081,12 @@ pub fn preprocess(text: &str) -> String {
             let minutes: i32 = caps[2].parse().unwrap_or(0);
             let seconds: Option<i32> = caps.get(3).map(|m| m.as_str().parse().unwrap_or(0));
             let period = caps.get(4).map(|m| m.as_str());
-            time_to_words(hours, minutes, seconds, period)
+            time_to_words_dialect(hours, minutes, seconds, period, british)
         })
         .to_string();
 
     // Handle currency and numbers
-    result = NUMBER_PATTERN
-        .replace_all(&result, |caps: &regex::Captures| {
-            let currency = caps.get(1).and_then(|m| m.as_str().chars().next());
-            let num_str = &caps[2];
-            let suffix = caps.get(3).map(|m| m.as_str());
-
-            // Parse the number
-            let clean_num: String = num_str.chars().filter(|c| *c != ',').collect();
-            let num: f64 = clean_num.parse().unwrap_or(0.0);
-
-            // Handle currency
-            if let Some(sym) = currency {
-                return currency_to_words(num, sym);
-            }
-
-            // Handle ordinals
-            if let Some(suf) = suffix {
-                match suf.to_lowercase().as_str() {
-                    "st" | "nd" | "rd" | "th" => {
-                        return ordinal_to_words(num as i64);
-                    }
-                    "s" | "'s" => {
-                        return format!("{}{}", number_to_words(num as i64), suf);
-                    }
-                    _ => {}
-                }
-            }
-
-            // Check if it's a year
-            let year = num as i64;
-            if year >= 1000 && year <= 2100 && num.fract() == 0.0 {
-                // Heuristic: standalone 4-digit numbers in this range are years
-                return year_to_words(year);
-            }
-
-            // Regular number
-            if num.fract() == 0.0 {
-                number_to_words(num as i64)
-            } else {
-                // Decimal number
-                let whole = num.floor() as i64;
-                let frac_str = format!("{:.10}", num.fract());
-                let frac_digits = frac_str.trim_start_matches("0.").trim_end_matches('0');
-                let frac_digits = if frac_digits.is_empty() { "0" } else { frac_digits };
-
-                let mut result = number_to_words(whole);
-                result.push_str(" point");
-                for c in frac_digits.chars() {
-                    if let Some(digit) = c.to_digit(10) {
-                        result.push(' ');
-                        result.push_str(&small_number_to_words(digit as i32));
-                    }
-                }
-                result
-            }
-        })
-        .to_string();
+    result = normalize_numbers(&result, british);
 
     // Clean up whitespace
     let whitespace_re = Regex::new(r"\s+").unwrap();
@@ -409,6 +1102,10 @@ pub struct Token {
     pub whitespace: String,
     pub phonemes: Option<String>,
     pub is_punct: bool,
+    /// Penn Treebank part-of-speech tag, set by [`crate::tagger::tag`]
+    pub tag: Option<String>,
+    /// Stress adjustment from inline markup, see [`crate::lexicon::apply_stress`]
+    pub stress: Option<i8>,
 }
 
 impl Token {
@@ -418,6 +1115,8 @@ impl Token {
             whitespace: String::new(),
             phonemes: None,
             is_punct: false,
+            tag: None,
+            stress: None,
         }
     }
 
@@ -433,10 +1132,33 @@ impl Token {
 }
 
 /// Tokenize text into words and punctuation
+///
+/// Inline markup (`[word](/phonemes/)`, `[word](+1)`) becomes a single token
+/// carrying its phonemes or stress adjustment.
 pub fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut last_end = 0;
+
+    for m in markup::find(text) {
+        tokenize_plain(&text[last_end..m.range.start], &mut tokens);
+
+        let mut token = Token::new(m.word);
+        match m.over {
+            Override::Phonemes(phonemes) => token.phonemes = Some(phonemes),
+            Override::Stress(stress) => token.stress = Some(stress),
+        }
+        tokens.push(token);
+        last_end = m.range.end;
+    }
+    tokenize_plain(&text[last_end..], &mut tokens);
+
+    tokens
+}
+
+/// Tokenize text without markup, appending to `tokens`
+fn tokenize_plain(text: &str, tokens: &mut Vec<Token>) {
     let word_re = Regex::new(r"([a-zA-Z'']+(?:[''][a-zA-Z]+)*|[0-9]+(?:[.,][0-9]+)*|[^\s\w])").unwrap();
 
-    let mut tokens: Vec<Token> = Vec::new();
     let mut last_end = 0;
 
     for cap in word_re.captures_iter(text) {
@@ -468,8 +1190,6 @@ pub fn tokenize(text: &str) -> Vec<Token> {
             last.whitespace = " ".to_string();
         }
     }
-
-    tokens
 }
 
 #[cfg(test)]
@@ -489,6 +1209,16 @@ mod tests {
         assert_eq!(number_to_words(-5), "minus five");
     }
 
+    #[test]
+    fn test_number_to_words_british() {
+        assert_eq!(number_to_words_dialect(0, true), "nought");
+        assert_eq!(number_to_words_dialect(105, true), "one hundred and five");
+        assert_eq!(number_to_words_dialect(2010, true), "two thousand and ten");
+        assert_eq!(number_to_words_dialect(1234, true), "one thousand two hundred and thirty four");
+        assert_eq!(number_to_words_dialect(3_000_000, true), "three million");
+        assert_eq!(number_to_words(123_456), "one hundred twenty three thousand four hundred fifty six");
+    }
+
     #[test]
     fn test_ordinal_to_words() {
         assert_eq!(ordinal_to_words(1), "first");
@@ -503,9 +1233,13 @@ mod tests {
     #[test]
     fn test_year_to_words() {
         assert_eq!(year_to_words(1984), "nineteen eighty four");
-        assert_eq!(year_to_words(2001), "twenty oh one");
-        assert_eq!(year_to_words(2000), "twenty hundred");
+        assert_eq!(year_to_words(2001), "two thousand one");
+        assert_eq!(year_to_words(2000), "two thousand");
+        assert_eq!(year_to_words(2010), "twenty ten");
+        assert_eq!(year_to_words(1905), "nineteen oh five");
         assert_eq!(year_to_words(2024), "twenty twenty four");
+        assert_eq!(year_to_words_dialect(2005, true), "two thousand and five");
+        assert_eq!(year_to_words_dialect(1984, true), "nineteen eighty four");
     }
 
     #[test]
@@ -513,6 +1247,13 @@ mod tests {
         assert_eq!(time_to_words(2, 30, None, Some("PM")), "two thirty PM");
         assert_eq!(time_to_words(12, 0, None, None), "twelve");
         assert_eq!(time_to_words(3, 5, None, Some("am")), "three oh five AM");
+
+        assert_eq!(time_to_words_dialect(14, 15, None, None, true), "quarter past two");
+        assert_eq!(time_to_words_dialect(2, 30, None, Some("PM"), true), "half past two PM");
+        assert_eq!(time_to_words_dialect(11, 45, None, None, true), "quarter to twelve");
+        assert_eq!(time_to_words_dialect(12, 40, None, None, true), "twenty to one");
+        assert_eq!(time_to_words_dialect(9, 0, None, None, true), "nine o'clock");
+        assert_eq!(time_to_words_dialect(9, 7, None, None, true), "nine oh seven");
     }
 
     #[test]
@@ -529,6 +1270,81 @@ mod tests {
         assert!(result.contains("Doctor"));
         assert!(result.contains("dollars"));
         assert!(result.contains("thirty"));
+
+        assert_eq!(preprocess("0.05"), "zero point zero five");
+        assert_eq!(preprocess_dialect("0.05", true), "nought point nought five");
+        assert_eq!(
+            preprocess_dialect("Room 101 opens at 9:15.", true),
+            "Room one hundred and one opens at quarter past nine."
+        );
+    }
+
+    #[test]
+    fn test_normalize_numbers() {
+        let us = |text: &str| normalize_numbers(text, false);
+
+        // Years versus cardinals
+        assert_eq!(us("1500 people"), "one thousand five hundred people");
+        assert_eq!(us("in 1500"), "in fifteen hundred");
+        assert_eq!(us("1984 was a good year"), "nineteen eighty four was a good year");
+        assert_eq!(us("May 5, 1990"), "May five, nineteen ninety");
+        assert_eq!(us("over 2000 miles"), "over two thousand miles");
+        assert_eq!(us("the 1990s"), "the nineteen nineties");
+        assert_eq!(us("the '80s"), "the eighties");
+        assert_eq!(us("1990-1995"), "nineteen ninety to nineteen ninety five");
+        assert_eq!(us("1990–95"), "nineteen ninety to ninety five");
+        assert_eq!(us("in 2000"), "in two thousand");
+        assert_eq!(us("in 2009"), "in two thousand nine");
+        assert_eq!(us("1/1/2000"), "January first, two thousand");
+        assert_eq!(us("2000-2010"), "two thousand to twenty ten");
+        assert_eq!(us("2005-08"), "two thousand five to two thousand eight");
+        assert_eq!(normalize_numbers("in 2005", true), "in two thousand and five");
+        assert_eq!(normalize_numbers("2005-08", true), "two thousand and five to two thousand and eight");
+
+        // Decimals, signs and scientific notation
+        assert_eq!(us("3.14159265358979323846"), format!("three point {}", digit_words("14159265358979323846")));
+        assert_eq!(us("-5 and +3"), "minus five and plus three");
+        assert_eq!(us("COVID-19"), "COVID-nineteen");
+        assert_eq!(us("6.02e23"), "six point zero two times ten to the power of twenty three");
+        assert_eq!(us("1e-5"), "one times ten to the power of minus five");
+        assert_eq!(us("12,345,678,901,234"), number_to_words(12_345_678_901_234));
+
+        // Fractions and ranges
+        assert_eq!(us("1/2"), "one half");
+        assert_eq!(us("3/4"), "three quarters");
+        assert_eq!(us("2 1/2 cups"), "two and a half cups");
+        assert_eq!(us("5/8"), "five eighths");
+        assert_eq!(us("24/7"), "twenty four seven");
+        assert_eq!(us("5-10 minutes"), "five to ten minutes");
+        assert_eq!(us("5–10%"), "five to ten percent");
+        assert_eq!(us("Pages 5 - 10 are missing"), "Pages five to ten are missing");
+        assert_eq!(us("50%"), "fifty percent");
+
+        // Currency
+        assert_eq!(us("₹250"), "two hundred fifty rupees");
+        assert_eq!(us("€1.5"), "one euro and fifty cents");
+        assert_eq!(us("$2.5 million"), "two point five million dollars");
+        assert_eq!(us("$3bn"), "three billion dollars");
+        assert_eq!(us("100 CHF"), "one hundred Swiss francs");
+        assert_eq!(us("-$5"), "minus five dollars");
+
+        // Digit strings, phone numbers and versions
+        assert_eq!(us("(555) 123-4567"), "five five five, one two three, four five six seven");
+        assert_eq!(us("+1 555-123-4567"), "plus one, five five five, one two three, four five six seven");
+        assert_eq!(us("Call 555-1234 now"), "Call five five five, one two three four now");
+        assert_eq!(us("call me at 555-1234"), "call me at five five five, one two three four");
+        assert_eq!(us("555-0123"), "five five five, zero one two three");
+        assert_eq!(us("between 100-1000 people"), "between one hundred to one thousand people");
+        assert_eq!(us("prices 250-1500 dollars"), "prices two hundred fifty to one thousand five hundred dollars");
+        assert_eq!(us("Springfield, IL 62704"), "Springfield, IL six two seven zero four");
+        assert_eq!(us("02139"), "zero two one three nine");
+        assert_eq!(us("90210-1234"), "nine zero two one zero, one two three four");
+        assert_eq!(us("v2.3.1"), "version two point three point one");
+        assert_eq!(us("1.10.0"), "one point ten point zero");
+        assert_eq!(us("3/5/2024"), "March fifth, twenty twenty four");
+        assert_eq!(normalize_numbers("3/5/2024", true), "the third of May, twenty twenty four");
+
+        assert_eq!(preprocess("I need 1½ cups."), "I need one and a half cups.");
     }
 
     #[test]
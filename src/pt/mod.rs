@@ -7,6 +7,9 @@
 
 pub mod normalizer;
 
+use crate::alignment::{self, Alignment};
+use crate::engine::{EngineCapabilities, G2PEngine};
+use crate::markup;
 use crate::tokenizer;
 
 /// Portuguese G2P processor
@@ -24,6 +27,10 @@ impl PortugueseG2P {
     }
 
     pub fn text_to_phonemes(&self, text: &str) -> String {
+        if markup::has_markup(text) {
+            return markup::phonemize(text, |plain| self.text_to_phonemes(plain));
+        }
+
         let normalized = normalizer::normalize(text);
         let mut result = String::new();
         let words: Vec<&str> = normalized.split_whitespace().collect();
@@ -277,6 +284,34 @@ impl Default for PortugueseG2P {
     }
 }
 
+impl G2PEngine for PortugueseG2P {
+    fn language_code(&self) -> &str {
+        "pt"
+    }
+
+    fn phonemize(&self, text: &str) -> String {
+        self.text_to_phonemes(text)
+    }
+
+    fn phonemize_aligned(&self, text: &str) -> Option<Alignment> {
+        Some(alignment::align_words(text, normalizer::normalize, |word| {
+            if is_punctuation(word) {
+                word.to_string()
+            } else {
+                self.word_to_phonemes(word)
+            }
+        }))
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            normalization: true,
+            stress: true,
+            ..Default::default()
+        }
+    }
+}
+
 pub fn text_to_tokens(text: &str) -> Vec<i64> {
     PortugueseG2P::new().text_to_tokens(text)
 }
@@ -0,0 +1,115 @@
+//! Writing-system detection for mixed-language text
+//!
+//! Splits text into runs of a single script so each run can be sent to an
+//! engine that reads it. Digits, punctuation, whitespace and inline markup
+//! are neutral: they join the run before them (or the first run, at the start).
+
+use crate::markup;
+use std::ops::Range;
+
+/// A writing system
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Script {
+    /// Latin letters, including accented and Vietnamese letters
+    Latin,
+    /// Chinese characters (also used by Japanese)
+    Han,
+    /// Hiragana and katakana
+    Kana,
+    /// Korean Hangul
+    Hangul,
+    /// Letters of any other script (Cyrillic, Greek, Arabic, ...), or neutral-only text
+    Other,
+}
+
+/// Get the script of a character, or `None` for neutral characters
+pub fn script_of(c: char) -> Option<Script> {
+    let script = match c as u32 {
+        0x3005 | 0x3007 | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF | 0x20000..=0x2FA1F => Script::Han,
+        0x3040..=0x309F | 0x30A0..=0x30FF | 0x31F0..=0x31FF | 0xFF66..=0xFF9F => Script::Kana,
+        0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF => Script::Hangul,
+        _ if c.is_ascii_alphabetic() => Script::Latin,
+        0x00C0..=0x024F | 0x1E00..=0x1EFF if c.is_alphabetic() => Script::Latin,
+        _ if c.is_alphabetic() => Script::Other,
+        _ => return None,
+    };
+    Some(script)
+}
+
+/// Split text into maximal runs of one script
+///
+/// The runs cover the whole text. Text with no letters is a single
+/// [`Script::Other`] run.
+pub fn split_runs(text: &str) -> Vec<(Range<usize>, Script)> {
+    let markup: Vec<Range<usize>> = markup::find(text).into_iter().map(|m| m.range).collect();
+    let mut runs: Vec<(Range<usize>, Script)> = Vec::new();
+
+    for (i, c) in text.char_indices() {
+        let end = i + c.len_utf8();
+        let script = script_of(c).filter(|_| !markup.iter().any(|m| m.contains(&i)));
+        match (script, runs.last_mut()) {
+            (Some(script), Some((range, last))) if *last == script => range.end = end,
+            (Some(script), Some((range, _))) => {
+                let start = range.end;
+                runs.push((start..end, script));
+            }
+            // The first run also covers any neutral text before it
+            (Some(script), None) => runs.push((0..end, script)),
+            (None, Some((range, _))) => range.end = end,
+            (None, None) => {}
+        }
+    }
+
+    if runs.is_empty() && !text.is_empty() {
+        runs.push((0..text.len(), Script::Other));
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runs(text: &str) -> Vec<(&str, Script)> {
+        split_runs(text)
+            .into_iter()
+            .map(|(range, script)| (&text[range], script))
+            .collect()
+    }
+
+    #[test]
+    fn test_script_of() {
+        assert_eq!(script_of('a'), Some(Script::Latin));
+        assert_eq!(script_of('ệ'), Some(Script::Latin));
+        assert_eq!(script_of('中'), Some(Script::Han));
+        assert_eq!(script_of('カ'), Some(Script::Kana));
+        assert_eq!(script_of('ー'), Some(Script::Kana));
+        assert_eq!(script_of('한'), Some(Script::Hangul));
+        assert_eq!(script_of('Ж'), Some(Script::Other));
+        assert_eq!(script_of('3'), None);
+        assert_eq!(script_of('。'), None);
+    }
+
+    #[test]
+    fn test_split_runs() {
+        assert_eq!(
+            runs("我喜欢 New York 的3个披萨。"),
+            vec![
+                ("我喜欢 ", Script::Han),
+                ("New York ", Script::Latin),
+                ("的3个披萨。", Script::Han),
+            ]
+        );
+        assert_eq!(runs("  Apple手机"), vec![("  Apple", Script::Latin), ("手机", Script::Han)]);
+        assert_eq!(runs("123!"), vec![("123!", Script::Other)]);
+        assert!(runs("").is_empty());
+    }
+
+    #[test]
+    fn test_markup_is_neutral() {
+        assert_eq!(
+            runs("你好[Kokoro](/kˈOkəɹO/)世界"),
+            vec![("你好[Kokoro](/kˈOkəɹO/)世界", Script::Han)]
+        );
+    }
+}
@@ -0,0 +1,456 @@
+//! SSML input support
+//!
+//! Parses the subset of SSML that maps onto G2P: `<phoneme>`, `<say-as>`,
+//! `<sub>`, `<lang>` and `<break>`. Other elements (`<speak>`, `<p>`, `<s>`,
+//! `<prosody>`, ...) are transparent: their text is spoken, the markup ignored.
+//! The document is flattened into [`Segment`]s that the pipeline converts in order.
+
+use crate::error::G2PError;
+
+/// A piece of an SSML document ready for conversion
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    /// Text to convert with the engine for `language`
+    Text { text: String, language: String },
+    /// Phonemes to pass through verbatim (from `<phoneme ph="...">`)
+    Phonemes(String),
+    /// A pause, rendered as a punctuation mark (from `<break>`)
+    Break(char),
+}
+
+/// An open element and how it affects its content
+#[derive(Debug)]
+enum Frame {
+    /// Content is replaced by phonemes or an alias and not spoken
+    Replaced,
+    /// Content is collected and expanded on close
+    SayAs {
+        interpret_as: String,
+        format: Option<String>,
+        content: String,
+    },
+    /// Content is in another language
+    Lang,
+    /// Any other element
+    Transparent,
+}
+
+/// Parse an SSML document (or fragment) into segments
+///
+/// `language` is the language of text outside any `<lang>` element.
+pub fn parse(ssml: &str, language: &str) -> Result<Vec<Segment>, G2PError> {
+    let mut segments = Vec::new();
+    let mut stack: Vec<(String, Frame)> = Vec::new();
+    let mut languages = vec![language.to_lowercase()];
+    let mut rest = ssml;
+
+    while !rest.is_empty() {
+        let Some(open) = rest.find('<') else {
+            push_text(&mut segments, &mut stack, &languages, &decode_entities(rest));
+            break;
+        };
+        push_text(&mut segments, &mut stack, &languages, &decode_entities(&rest[..open]));
+        rest = &rest[open..];
+
+        // Comments, processing instructions and declarations
+        if let Some(after) = rest.strip_prefix("<!--") {
+            let end = after.find("-->").ok_or_else(|| invalid("unterminated comment"))?;
+            rest = &after[end + 3..];
+            continue;
+        }
+        if rest.starts_with("<?") || rest.starts_with("<!") {
+            let end = rest.find('>').ok_or_else(|| invalid("unterminated declaration"))?;
+            rest = &rest[end + 1..];
+            continue;
+        }
+
+        let end = rest.find('>').ok_or_else(|| invalid("unterminated tag"))?;
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim();
+            let (open_name, frame) = stack
+                .pop()
+                .ok_or_else(|| invalid(&format!("unexpected closing tag </{}>", name)))?;
+            if open_name != name {
+                return Err(invalid(&format!("expected </{}>, found </{}>", open_name, name)));
+            }
+            close_element(&mut segments, &mut stack, &mut languages, frame);
+            continue;
+        }
+
+        let self_closing = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/');
+        let (name, attributes) = parse_tag(tag)?;
+        let frame = open_element(&mut segments, &stack, &mut languages, &name, &attributes);
+        if self_closing {
+            close_element(&mut segments, &mut stack, &mut languages, frame);
+        } else {
+            stack.push((name, frame));
+        }
+    }
+
+    if let Some((name, _)) = stack.last() {
+        return Err(invalid(&format!("unclosed element <{}>", name)));
+    }
+    Ok(segments)
+}
+
+/// Handle an opening tag, returning the frame to push
+fn open_element(
+    segments: &mut Vec<Segment>,
+    stack: &[(String, Frame)],
+    languages: &mut Vec<String>,
+    name: &str,
+    attributes: &[(String, String)],
+) -> Frame {
+    let attribute = |key: &str| {
+        attributes
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+    };
+    let replaced = is_replaced(stack);
+
+    match name {
+        "phoneme" => {
+            let alphabet = attribute("alphabet").unwrap_or_else(|| "ipa".to_string());
+            match attribute("ph") {
+                Some(ph) if alphabet.eq_ignore_ascii_case("ipa") => {
+                    if !replaced {
+                        segments.push(Segment::Phonemes(ph));
+                    }
+                    Frame::Replaced
+                }
+                _ => {
+                    log::warn!("Unsupported phoneme alphabet '{}', speaking the text instead", alphabet);
+                    Frame::Transparent
+                }
+            }
+        }
+        "sub" => match attribute("alias") {
+            Some(alias) => {
+                if !replaced {
+                    push_text_segment(segments, languages, &alias);
+                }
+                Frame::Replaced
+            }
+            None => Frame::Transparent,
+        },
+        "say-as" => match attribute("interpret-as") {
+            Some(interpret_as) => Frame::SayAs {
+                interpret_as: interpret_as.to_lowercase(),
+                format: attribute("format"),
+                content: String::new(),
+            },
+            None => Frame::Transparent,
+        },
+        "lang" => match attribute("xml:lang") {
+            Some(lang) => {
+                languages.push(lang.to_lowercase());
+                Frame::Lang
+            }
+            None => Frame::Transparent,
+        },
+        "break" => {
+            if !replaced {
+                if let Some(punct) = break_punctuation(attribute("strength"), attribute("time")) {
+                    segments.push(Segment::Break(punct));
+                }
+            }
+            Frame::Transparent
+        }
+        _ => Frame::Transparent,
+    }
+}
+
+/// Handle a closing tag (or the end of a self-closing one)
+fn close_element(
+    segments: &mut Vec<Segment>,
+    stack: &mut [(String, Frame)],
+    languages: &mut Vec<String>,
+    frame: Frame,
+) {
+    match frame {
+        Frame::SayAs {
+            interpret_as,
+            format,
+            content,
+        } => {
+            let language = languages.last().map(String::as_str).unwrap_or("en");
+            let expanded = say_as(&content, &interpret_as, format.as_deref(), language);
+            push_text(segments, stack, languages, &format!(" {} ", expanded));
+        }
+        Frame::Lang => {
+            languages.pop();
+        }
+        Frame::Replaced | Frame::Transparent => {}
+    }
+}
+
+/// Route text to the innermost element that consumes it, or emit it
+fn push_text(segments: &mut Vec<Segment>, stack: &mut [(String, Frame)], languages: &[String], text: &str) {
+    if text.is_empty() || is_replaced(stack) {
+        return;
+    }
+    if let Some((_, Frame::SayAs { content, .. })) = stack.iter_mut().rev().find(|(_, f)| matches!(f, Frame::SayAs { .. })) {
+        content.push_str(text);
+        return;
+    }
+    push_text_segment(segments, languages, text);
+}
+
+/// Append text, merging with the previous segment if it has the same language
+fn push_text_segment(segments: &mut Vec<Segment>, languages: &[String], text: &str) {
+    let language = languages.last().cloned().unwrap_or_default();
+    if let Some(Segment::Text { text: last, language: last_lang }) = segments.last_mut() {
+        if *last_lang == language {
+            last.push_str(text);
+            return;
+        }
+    }
+    segments.push(Segment::Text {
+        text: text.to_string(),
+        language,
+    });
+}
+
+fn is_replaced(stack: &[(String, Frame)]) -> bool {
+    stack.iter().any(|(_, f)| matches!(f, Frame::Replaced))
+}
+
+/// Choose the punctuation mark for a `<break>`
+///
+/// An explicit `time` takes precedence over `strength`.
+fn break_punctuation(strength: Option<String>, time: Option<String>) -> Option<char> {
+    if let Some(ms) = time.as_deref().and_then(parse_duration_ms) {
+        return match ms {
+            0 => None,
+            1..=399 => Some(','),
+            400..=799 => Some('.'),
+            _ => Some('…'),
+        };
+    }
+    match strength.as_deref() {
+        Some("none") | Some("x-weak") => None,
+        Some("strong") => Some('.'),
+        Some("x-strong") => Some('…'),
+        _ => Some(','),
+    }
+}
+
+/// Parse an SSML duration such as "500ms" or "1.5s"
+fn parse_duration_ms(time: &str) -> Option<u64> {
+    let time = time.trim();
+    if let Some(ms) = time.strip_suffix("ms") {
+        ms.trim().parse::<f64>().ok().map(|v| v as u64)
+    } else if let Some(s) = time.strip_suffix('s') {
+        s.trim().parse::<f64>().ok().map(|v| (v * 1000.0) as u64)
+    } else {
+        None
+    }
+}
+
+/// Expand `<say-as>` content into words
+///
+/// Number, date and telephone expansion uses the English preprocessor and
+/// only applies to English text; other languages get the digits separated so
+/// their own normalizers can read them.
+#[cfg_attr(not(feature = "english"), allow(unused_variables))]
+fn say_as(content: &str, interpret_as: &str, format: Option<&str>, language: &str) -> String {
+    let content = content.trim();
+    let english = cfg!(feature = "english") && language.starts_with("en");
+
+    match interpret_as {
+        "characters" | "spell-out" | "verbatim" => spell_characters(content, english),
+        "telephone" => content
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|group| !group.is_empty())
+            .map(|group| spell_characters(group, english))
+            .collect::<Vec<_>>()
+            .join(", "),
+        #[cfg(feature = "english")]
+        "cardinal" | "number" if english => {
+            let digits: String = content.chars().filter(|c| *c != ',').collect();
+            match digits.parse::<i64>() {
+                Ok(n) => crate::preprocessor::number_to_words(n),
+                Err(_) => content.to_string(),
+            }
+        }
+        #[cfg(feature = "english")]
+        "ordinal" if english => {
+            let digits: String = content.chars().filter(|c| c.is_ascii_digit()).collect();
+            match digits.parse::<i64>() {
+                Ok(n) => crate::preprocessor::ordinal_to_words(n),
+                Err(_) => content.to_string(),
+            }
+        }
+        #[cfg(feature = "english")]
+        "date" if english => {
+            let british = language.eq_ignore_ascii_case("en-gb");
+            crate::preprocessor::date_to_words(content, format, british).unwrap_or_else(|| content.to_string())
+        }
+        _ => content.to_string(),
+    }
+}
+
+/// Spell out letters and digits one by one
+fn spell_characters(text: &str, english: bool) -> String {
+    const DIGITS: [&str; 10] = ["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .map(|c| match c.to_digit(10) {
+            Some(d) if english => DIGITS[d as usize].to_string(),
+            _ => c.to_uppercase().to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Split a tag body into its name and attributes
+fn parse_tag(tag: &str) -> Result<(String, Vec<(String, String)>), G2PError> {
+    let tag = tag.trim();
+    let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+    let name = tag[..name_end].to_string();
+    if name.is_empty() {
+        return Err(invalid("empty tag"));
+    }
+
+    let mut attributes = Vec::new();
+    let mut rest = tag[name_end..].trim_start();
+    while !rest.is_empty() {
+        let eq = rest
+            .find('=')
+            .ok_or_else(|| invalid(&format!("malformed attribute in <{}>", name)))?;
+        let key = rest[..eq].trim().to_string();
+        let value_part = rest[eq + 1..].trim_start();
+        let quote = value_part
+            .chars()
+            .next()
+            .filter(|c| *c == '"' || *c == '\'')
+            .ok_or_else(|| invalid(&format!("unquoted attribute '{}' in <{}>", key, name)))?;
+        let close = value_part[1..]
+            .find(quote)
+            .ok_or_else(|| invalid(&format!("unterminated attribute '{}' in <{}>", key, name)))?;
+        attributes.push((key, decode_entities(&value_part[1..close + 1])));
+        rest = value_part[close + 2..].trim_start();
+    }
+
+    Ok((name, attributes))
+}
+
+/// Decode the predefined XML entities and numeric character references
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        result.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest.find(';').and_then(|semi| {
+            let entity = &rest[1..semi];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => entity
+                    .strip_prefix("#x")
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, semi))
+        });
+        match decoded {
+            Some((c, semi)) => {
+                result.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+fn invalid(message: &str) -> G2PError {
+    G2PError::InvalidInput(format!("SSML: {}", message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(text: &str, language: &str) -> Segment {
+        Segment::Text {
+            text: text.to_string(),
+            language: language.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_phoneme_and_break() {
+        let segments = parse(
+            r#"<speak>Say <phoneme alphabet="ipa" ph="təmˈAɾO">tomato</phoneme><break time="500ms"/> now</speak>"#,
+            "en-us",
+        )
+        .unwrap();
+        assert_eq!(
+            segments,
+            vec![
+                text("Say ", "en-us"),
+                Segment::Phonemes("təmˈAɾO".to_string()),
+                Segment::Break('.'),
+                text(" now", "en-us"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lang_and_sub() {
+        let segments = parse(
+            r#"<speak><sub alias="World Wide Web">WWW</sub> <lang xml:lang="es">hola</lang></speak>"#,
+            "en-us",
+        )
+        .unwrap();
+        assert_eq!(segments, vec![text("World Wide Web ", "en-us"), text("hola", "es")]);
+    }
+
+    #[test]
+    fn test_say_as_characters() {
+        let segments = parse(r#"<say-as interpret-as="characters">abc</say-as>"#, "es").unwrap();
+        assert_eq!(segments, vec![text(" A B C ", "es")]);
+    }
+
+    #[cfg(feature = "english")]
+    #[test]
+    fn test_say_as_english() {
+        let expand = |kind: &str, content: &str, format: Option<&str>| say_as(content, kind, format, "en-us");
+        assert_eq!(expand("cardinal", "1,234", None), "one thousand two hundred thirty four");
+        assert_eq!(expand("ordinal", "3rd", None), "third");
+        assert_eq!(expand("telephone", "555-0123", None), "five five five, zero one two three");
+        assert_eq!(expand("date", "2024-03-05", None), "March fifth, twenty twenty four");
+        assert_eq!(expand("date", "05/03/2024", Some("dmy")), "the fifth of March, twenty twenty four");
+        assert_eq!(say_as("2005-03-05", "date", None, "en-gb"), "March fifth, two thousand and five");
+    }
+
+    #[test]
+    fn test_entities_and_errors() {
+        let segments = parse("fish &amp; chips &#233;", "en-us").unwrap();
+        assert_eq!(segments, vec![text("fish & chips é", "en-us")]);
+
+        assert!(matches!(parse("<speak>hi", "en-us"), Err(G2PError::InvalidInput(_))));
+        assert!(matches!(parse("<s>hi</p>", "en-us"), Err(G2PError::InvalidInput(_))));
+        assert!(matches!(parse("<phoneme ph=x>hi</phoneme>", "en-us"), Err(G2PError::InvalidInput(_))));
+    }
+}
@@ -0,0 +1,288 @@
+//! Lightweight rule-based part-of-speech tagger for English
+//!
+//! Assigns Penn Treebank tags to [`Token`]s so the lexicon can pick
+//! tag-dependent pronunciations of heteronyms ("record", "read", "live").
+//! Each word first gets a tag from a closed-class word list or its suffix,
+//! then a few contextual rules in the style of a Brill tagger revise it
+//! ("will record" → VB, "the record" → NN, "have read" → VBN).
+
+use crate::preprocessor::Token;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+
+/// Closed-class words and their tags
+static CLOSED_CLASS: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
+    let groups: &[(&str, &[&str])] = &[
+        ("DT", &[
+            "the", "a", "an", "this", "that", "these", "those", "every", "each", "some", "any", "no",
+            "another", "all", "both", "either", "neither",
+        ]),
+        ("PRP", &[
+            "i", "you", "he", "she", "it", "we", "they", "me", "him", "us", "them", "myself",
+            "yourself", "himself", "herself", "itself", "ourselves", "themselves",
+        ]),
+        ("PRP$", &["my", "your", "his", "her", "its", "our", "their"]),
+        ("MD", &[
+            "will", "would", "shall", "should", "can", "could", "may", "might", "must", "won't",
+            "can't", "cannot", "wouldn't", "shouldn't", "couldn't", "mustn't",
+        ]),
+        ("TO", &["to"]),
+        ("IN", &[
+            "of", "in", "on", "at", "for", "with", "by", "from", "about", "into", "onto", "over",
+            "under", "after", "before", "between", "through", "during", "without", "within",
+            "against", "among", "upon", "since", "until", "than", "because", "if", "whether",
+            "while", "though", "although", "as", "like", "near",
+        ]),
+        ("CC", &["and", "or", "but", "nor", "yet"]),
+        ("RB", &[
+            "not", "never", "very", "also", "always", "often", "just", "too", "quite", "really",
+            "already", "still", "soon", "now", "then", "here", "rather", "almost", "even", "ever",
+        ]),
+        ("EX", &["there"]),
+        ("WDT", &["which"]),
+        ("WP", &["what", "who", "whom"]),
+        ("WRB", &["where", "when", "why", "how"]),
+        ("CD", &[
+            "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+            "hundred", "thousand", "million", "billion",
+        ]),
+        ("UH", &["oh", "yes", "hello", "hey"]),
+        ("VB", &["be"]),
+        ("VBP", &["am", "are", "aren't", "have", "haven't", "do", "don't"]),
+        ("VBZ", &["is", "isn't", "has", "hasn't", "does", "doesn't"]),
+        ("VBD", &["was", "wasn't", "were", "weren't", "had", "hadn't", "did", "didn't"]),
+        ("VBN", &["been"]),
+        ("VBG", &["being", "having"]),
+    ];
+
+    groups
+        .iter()
+        .flat_map(|(tag, words)| words.iter().map(move |w| (*w, *tag)))
+        .collect()
+});
+
+const BE_FORMS: &[&str] = &[
+    "am", "is", "are", "was", "were", "be", "been", "being", "isn't", "aren't", "wasn't", "weren't",
+];
+const HAVE_FORMS: &[&str] = &["have", "has", "had", "having", "haven't", "hasn't", "hadn't"];
+const DO_FORMS: &[&str] = &["do", "does", "did", "don't", "doesn't", "didn't"];
+const SUBJECT_PRONOUNS: &[&str] = &["i", "you", "he", "she", "it", "we", "they"];
+
+/// Words whose "'s" contraction means "is" rather than a possessive
+const IS_CONTRACTION_BASES: &[&str] = &[
+    "it", "he", "she", "that", "there", "here", "what", "who", "where", "how",
+];
+
+/// Suffixes that mark adjectives
+const ADJECTIVE_SUFFIXES: &[&str] = &["ous", "ful", "ive", "able", "ible", "less", "ish", "ical"];
+
+/// Tag a token stream in place, setting `Token::tag` on every token
+pub fn tag(tokens: &mut [Token]) {
+    let words: Vec<Option<String>> = tokens
+        .iter()
+        .map(|t| (!t.is_punct).then(|| t.text.to_lowercase()))
+        .collect();
+
+    let sentence_start = |i: usize| {
+        tokens[..i]
+            .iter()
+            .rev()
+            .find(|t| !t.is_punct || is_sentence_end(&t.text))
+            .is_none_or(|t| t.is_punct)
+    };
+
+    let initial: Vec<&'static str> = tokens
+        .iter()
+        .zip(&words)
+        .enumerate()
+        .map(|(i, (token, word))| match word {
+            Some(word) => initial_tag(&token.text, word, sentence_start(i)),
+            None => punct_tag(&token.text),
+        })
+        .collect();
+
+    let mut tags = initial.clone();
+    for i in 0..tokens.len() {
+        let Some(word) = &words[i] else { continue };
+        let next = initial.get(i + 1).copied();
+
+        if word == "that" {
+            if matches!(next, Some("PRP" | "DT" | "EX" | "NNP" | "PRP$")) {
+                tags[i] = "IN";
+            }
+            continue;
+        }
+        if CLOSED_CLASS.contains_key(word.as_str()) || is_verb_contraction(word) {
+            continue;
+        }
+
+        // Previous word in the same clause, skipping adverbs ("will not record")
+        let prev = (0..i)
+            .rev()
+            .take_while(|&j| words[j].is_some())
+            .find(|&j| tags[j] != "RB")
+            .and_then(|j| words[j].as_deref().map(|w| (w, tags[j])));
+
+        tags[i] = revise(tags[i], prev, next, sentence_start(i));
+    }
+
+    for (token, tag) in tokens.iter_mut().zip(tags) {
+        token.tag = Some(tag.to_string());
+    }
+}
+
+/// Tag a word from the closed-class list, its contraction or its suffix
+fn initial_tag(text: &str, word: &str, sentence_start: bool) -> &'static str {
+    if let Some(tag) = CLOSED_CLASS.get(word) {
+        return tag;
+    }
+    if word.starts_with(|c: char| c.is_ascii_digit()) {
+        return "CD";
+    }
+
+    let apostrophe = word.rfind(['\'', '\u{2019}']);
+    if let Some(pos) = apostrophe {
+        let (base, suffix) = (&word[..pos], &word[pos + 1..]);
+        match suffix {
+            "s" if IS_CONTRACTION_BASES.contains(&base) => return "VBZ",
+            "s" => return "POS",
+            "re" | "m" | "ve" => return "VBP",
+            "ll" | "d" => return "MD",
+            _ => {}
+        }
+    }
+
+    if !sentence_start && text.starts_with(|c: char| c.is_uppercase()) {
+        return "NNP";
+    }
+
+    let len = word.len();
+    if len > 4 && word.ends_with("ly") {
+        "RB"
+    } else if len > 4 && word.ends_with("ing") {
+        "VBG"
+    } else if len > 3 && word.ends_with("ed") {
+        "VBD"
+    } else if len > 5 && ADJECTIVE_SUFFIXES.iter().any(|s| word.ends_with(s)) {
+        "JJ"
+    } else if len > 3 && word.ends_with('s') && !["ss", "us", "is"].iter().any(|s| word.ends_with(s)) {
+        "NNS"
+    } else {
+        "NN"
+    }
+}
+
+/// Revise an open-class tag using the previous word and the next tag
+fn revise(
+    tag: &'static str,
+    prev: Option<(&str, &'static str)>,
+    next: Option<&str>,
+    sentence_start: bool,
+) -> &'static str {
+    let is_noun = matches!(tag, "NN" | "VB" | "VBP");
+
+    // Imperatives: "Record the song", "Close your eyes"
+    if sentence_start && tag == "NN" && matches!(next, Some("DT" | "PRP$" | "PRP")) {
+        return "VB";
+    }
+
+    let Some((prev_word, prev_tag)) = prev else {
+        return tag;
+    };
+
+    if is_have(prev_word) && matches!(tag, "NN" | "VB" | "VBP" | "VBD") {
+        // "have read", "has recorded"
+        "VBN"
+    } else if is_be(prev_word) && tag == "VBD" {
+        // "was recorded"
+        "VBN"
+    } else if is_be(prev_word) && tag == "NN" {
+        // "is live", "was content"
+        "JJ"
+    } else if (DO_FORMS.contains(&prev_word) || matches!(prev_tag, "MD" | "TO"))
+        && matches!(tag, "NN" | "VBP" | "VBD")
+    {
+        // "will record", "to present", "didn't object"
+        "VB"
+    } else if SUBJECT_PRONOUNS.contains(&prev_word) && tag == "NN" {
+        // "they record"
+        "VBP"
+    } else if SUBJECT_PRONOUNS.contains(&prev_word) && tag == "NNS" {
+        // "she records"
+        "VBZ"
+    } else if matches!(prev_tag, "DT" | "PRP$" | "CD" | "POS") && is_noun {
+        // "the record", "my project"
+        "NN"
+    } else if matches!(prev_tag, "DT" | "PRP$" | "POS") && tag == "VBD" {
+        // "the closed door"
+        "JJ"
+    } else {
+        tag
+    }
+}
+
+fn is_be(word: &str) -> bool {
+    BE_FORMS.contains(&word) || word.ends_with("'re") || word.ends_with("'m") || is_is_contraction(word)
+}
+
+fn is_have(word: &str) -> bool {
+    HAVE_FORMS.contains(&word) || word.ends_with("'ve")
+}
+
+fn is_is_contraction(word: &str) -> bool {
+    word.strip_suffix("'s")
+        .or_else(|| word.strip_suffix("\u{2019}s"))
+        .is_some_and(|base| IS_CONTRACTION_BASES.contains(&base))
+}
+
+/// Contractions of a pronoun with a verb or modal ("they're", "I'll")
+fn is_verb_contraction(word: &str) -> bool {
+    is_is_contraction(word)
+        || ["'re", "'m", "'ve", "'ll", "'d"].iter().any(|s| word.ends_with(s))
+}
+
+fn is_sentence_end(punct: &str) -> bool {
+    matches!(punct, "." | "!" | "?")
+}
+
+/// Penn tags for punctuation: "." ends a sentence, everything else is ","
+fn punct_tag(punct: &str) -> &'static str {
+    if is_sentence_end(punct) {
+        "."
+    } else {
+        ","
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preprocessor::tokenize;
+
+    fn tags(text: &str) -> Vec<String> {
+        let mut tokens = tokenize(text);
+        tag(&mut tokens);
+        tokens.into_iter().map(|t| t.tag.unwrap()).collect()
+    }
+
+    #[test]
+    fn test_heteronym_contexts() {
+        assert_eq!(tags("I will record the record"), ["PRP", "MD", "VB", "DT", "NN"]);
+        assert_eq!(tags("They record it."), ["PRP", "VBP", "PRP", "."]);
+        assert_eq!(tags("She has read it"), ["PRP", "VBZ", "VBN", "PRP"]);
+        assert_eq!(tags("It was recorded"), ["PRP", "VBD", "VBN"]);
+        assert_eq!(tags("We will not object"), ["PRP", "MD", "RB", "VB"]);
+    }
+
+    #[test]
+    fn test_sentence_start() {
+        assert_eq!(tags("Close the door. Record it"), ["VB", "DT", "NN", ".", "VB", "PRP"]);
+    }
+
+    #[test]
+    fn test_contractions() {
+        assert_eq!(tags("it's live"), ["VBZ", "JJ"]);
+        assert_eq!(tags("John's project"), ["POS", "NN"]);
+        assert_eq!(tags("they'll present"), ["MD", "VB"]);
+    }
+}
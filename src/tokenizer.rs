@@ -1,10 +1,14 @@
 //! Phoneme to Token ID mapping for Kokoro TTS model
 //!
 //! This module provides the vocabulary mapping from phonemes to token IDs
-//! as expected by the Kokoro-82M model.
+//! as expected by the Kokoro-82M model. Other checkpoints can supply their
+//! own mapping as a [`Vocabulary`] loaded from the model's `config.json`.
 
+use crate::error::G2PError;
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
 
 /// Maximum number of tokens (excluding padding)
 pub const MAX_TOKENS: usize = 510;
@@ -12,7 +16,7 @@ pub const MAX_TOKENS: usize = 510;
 /// Padding token ID (used at start and end)
 pub const PAD_TOKEN: i64 = 0;
 
-/// The Kokoro vocabulary mapping phonemes/symbols to token IDs
+/// The built-in Kokoro vocabulary mapping phonemes/symbols to token IDs
 static VOCAB: Lazy<HashMap<char, i64>> = Lazy::new(|| {
     let mut m = HashMap::new();
     // Punctuation
@@ -27,8 +31,8 @@ static VOCAB: Lazy<HashMap<char, i64>> = Lazy::new(|| {
     m.insert('"', 11);   // straight quote
     m.insert('(', 12);
     m.insert(')', 13);
-    m.insert('"', 14);   // left double quote
-    m.insert('"', 15);   // right double quote
+    m.insert('\u{201C}', 14); // left double quote
+    m.insert('\u{201D}', 15); // right double quote
     m.insert(' ', 16);   // space
     m.insert('\u{0303}', 17); // combining tilde
 
@@ -149,14 +153,6 @@ static VOCAB: Lazy<HashMap<char, i64>> = Lazy::new(|| {
     // American reduced vowel
     m.insert('ᵻ', 177);  // between ə and ɪ
 
-    // =========================================================================
-    // Additional phonemes for Spanish, Indonesian, Turkish, Italian
-    // =========================================================================
-
-    // Close front rounded vowel (Turkish ü)
-    // Note: Using IPA 'y' at a new ID since lowercase 'y' is already used for letter y
-    m.insert('ʏ', 217);  // Near-close near-front rounded vowel (close to Turkish ü)
-
     // =========================================================================
     // Zhuyin (Bopomofo) characters for Chinese support
     // These map to the Kokoro Chinese model vocabulary
@@ -206,69 +202,331 @@ static VOCAB: Lazy<HashMap<char, i64>> = Lazy::new(|| {
     m
 });
 
-/// Reverse mapping from token ID to character
-static ID_TO_CHAR: Lazy<HashMap<i64, char>> = Lazy::new(|| {
-    VOCAB.iter().map(|(&c, &id)| (id, c)).collect()
-});
+static BUILTIN: Lazy<Arc<Vocabulary>> = Lazy::new(|| Arc::new(Vocabulary::from_map(VOCAB.clone())));
+
+/// A mapping between phoneme characters and a model's token IDs
+///
+/// [`Vocabulary::builtin`] is the Kokoro-82M v1.0 vocabulary used by the free
+/// functions in this module. Other checkpoints (kokoro-v1.1-zh, ...) can load
+/// theirs from the `vocab` object of the model's `config.json` and select it
+/// with [`KPipeline::set_vocabulary`](crate::KPipeline::set_vocabulary).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vocabulary {
+    ids: HashMap<char, i64>,
+    phonemes: HashMap<i64, char>,
+}
+
+impl Vocabulary {
+    /// The built-in Kokoro vocabulary
+    pub fn builtin() -> &'static Vocabulary {
+        &BUILTIN
+    }
+
+    /// A shared handle to the built-in vocabulary
+    pub(crate) fn shared_builtin() -> Arc<Vocabulary> {
+        Arc::clone(&BUILTIN)
+    }
+
+    /// Create a vocabulary from a phoneme to token ID map
+    pub fn from_map(ids: HashMap<char, i64>) -> Self {
+        let phonemes = ids.iter().map(|(&c, &id)| (id, c)).collect();
+        Self { ids, phonemes }
+    }
+
+    /// Load a vocabulary from the contents of a model's `config.json`
+    ///
+    /// Accepts either the whole config (`{"vocab": {";": 1, ...}, ...}`) or
+    /// just the vocab object. Every key must be a single character and every
+    /// ID a positive integer; ID 0 is reserved for padding.
+    pub fn from_config_json(json: &str) -> Result<Self, G2PError> {
+        let config: serde_json::Value = serde_json::from_str(json)
+            .map_err(|e| G2PError::InvalidVocabulary(format!("invalid JSON: {}", e)))?;
+        let vocab = config
+            .get("vocab")
+            .unwrap_or(&config)
+            .as_object()
+            .ok_or_else(|| G2PError::InvalidVocabulary("expected a vocab object".to_string()))?;
+
+        let mut ids = HashMap::with_capacity(vocab.len());
+        for (key, value) in vocab {
+            let mut chars = key.chars();
+            let (Some(c), None) = (chars.next(), chars.next()) else {
+                return Err(G2PError::InvalidVocabulary(format!("key {:?} is not a single character", key)));
+            };
+            let id = value
+                .as_i64()
+                .filter(|&id| id > PAD_TOKEN)
+                .ok_or_else(|| G2PError::InvalidVocabulary(format!("invalid token ID for {:?}: {}", key, value)))?;
+            ids.insert(c, id);
+        }
+        if ids.is_empty() {
+            return Err(G2PError::InvalidVocabulary("vocab is empty".to_string()));
+        }
+        Ok(Self::from_map(ids))
+    }
+
+    /// Load a vocabulary from a model's `config.json` file
+    pub fn from_config_path<P: AsRef<Path>>(path: P) -> Result<Self, G2PError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| G2PError::InvalidVocabulary(format!("{}: {}", path.display(), e)))?;
+        Self::from_config_json(&contents)
+    }
+
+    /// Number of phonemes in the vocabulary
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Check if the vocabulary has no phonemes
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Get the token ID of a phoneme character
+    #[inline]
+    pub fn id(&self, phoneme: char) -> Option<i64> {
+        self.ids.get(&phoneme).copied()
+    }
+
+    /// Get the phoneme character of a token ID
+    #[inline]
+    pub fn phoneme(&self, id: i64) -> Option<char> {
+        self.phonemes.get(&id).copied()
+    }
+
+    /// Check if a character has a token ID
+    #[inline]
+    pub fn contains(&self, phoneme: char) -> bool {
+        self.ids.contains_key(&phoneme)
+    }
+
+    /// All phoneme characters, ordered by token ID
+    pub fn phonemes(&self) -> Vec<char> {
+        let mut chars: Vec<char> = self.ids.keys().copied().collect();
+        chars.sort_by_key(|c| (self.ids[c], *c));
+        chars
+    }
+
+    /// Convert a phoneme string to token IDs padded with 0 at start and end
+    ///
+    /// Unknown characters are logged and dropped; output longer than
+    /// `MAX_TOKENS` is truncated.
+    pub fn phonemes_to_tokens(&self, phonemes: &str) -> Vec<i64> {
+        let mut tokens = Vec::with_capacity(phonemes.len() + 2);
+        tokens.push(PAD_TOKEN); // Start padding
+
+        for c in phonemes.chars() {
+            if let Some(id) = self.id(c) {
+                tokens.push(id);
+            } else {
+                // Log unknown character but don't crash
+                log::warn!("Unknown phoneme character: {:?} (U+{:04X})", c, c as u32);
+            }
+        }
+
+        tokens.push(PAD_TOKEN); // End padding
+
+        // Truncate if exceeding max length
+        if tokens.len() >= MAX_TOKENS + 2 {
+            tokens.truncate(MAX_TOKENS + 1);
+            tokens.push(PAD_TOKEN);
+        }
+
+        tokens
+    }
+
+    /// Convert a phoneme string to token IDs, failing instead of dropping or truncating
+    ///
+    /// Returns [`G2PError::UnmappablePhoneme`] for the first character without a
+    /// token ID, and [`G2PError::InputTooLong`] if the result exceeds `MAX_TOKENS`.
+    pub fn try_phonemes_to_tokens(&self, phonemes: &str) -> Result<Vec<i64>, G2PError> {
+        let mut tokens = Vec::with_capacity(phonemes.len() + 2);
+        tokens.push(PAD_TOKEN);
+
+        for (position, c) in phonemes.chars().enumerate() {
+            let id = self.id(c).ok_or(G2PError::UnmappablePhoneme { phoneme: c, position })?;
+            tokens.push(id);
+        }
+
+        let count = tokens.len() - 1;
+        if count > MAX_TOKENS {
+            return Err(G2PError::InputTooLong {
+                tokens: count,
+                max: MAX_TOKENS,
+            });
+        }
+
+        tokens.push(PAD_TOKEN);
+        Ok(tokens)
+    }
+
+    /// Check which characters of a phoneme string have no token ID
+    ///
+    /// Unlike [`Vocabulary::phonemes_to_tokens`], which drops such characters
+    /// with a log warning, this reports every one of them with its position.
+    pub fn validate(&self, phonemes: &str) -> CoverageReport {
+        let mut report = CoverageReport::default();
+        for (position, c) in phonemes.chars().enumerate() {
+            report.total += 1;
+            if !self.contains(c) {
+                report.unmapped.push((position, c));
+            }
+        }
+        report
+    }
+
+    /// Convert token IDs back to a phoneme string
+    pub fn tokens_to_phonemes(&self, tokens: &[i64]) -> String {
+        tokens
+            .iter()
+            .filter(|&&id| id != PAD_TOKEN)
+            .filter_map(|&id| self.phoneme(id))
+            .collect()
+    }
+}
+
+impl Default for Vocabulary {
+    fn default() -> Self {
+        Self::builtin().clone()
+    }
+}
+
+/// Result of checking a phoneme string against a [`Vocabulary`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoverageReport {
+    /// Number of characters checked
+    pub total: usize,
+    /// Characters without a token ID and their character positions
+    pub unmapped: Vec<(usize, char)>,
+}
+
+impl CoverageReport {
+    /// Check if every character has a token ID
+    pub fn is_complete(&self) -> bool {
+        self.unmapped.is_empty()
+    }
+
+    /// Distinct unmapped characters, in order of first occurrence
+    pub fn unmapped_chars(&self) -> Vec<char> {
+        let mut chars = Vec::new();
+        for &(_, c) in &self.unmapped {
+            if !chars.contains(&c) {
+                chars.push(c);
+            }
+        }
+        chars
+    }
+}
+
+/// Per-language substitutions for phonemes the built-in vocabulary lacks
+///
+/// Each engine emits the IPA it was written against; these map it to the
+/// closest Kokoro symbols before tokenization. Substitutions are applied in
+/// order, so longer sequences come first.
+static PHONEME_FALLBACKS: &[(&str, &[(&str, &str)])] = &[
+    (
+        "de",
+        &[
+            ("aɪ", "I"), // diphthongs use Kokoro's single-symbol forms
+            ("aʊ", "W"),
+            ("ɔʏ", "Y"),
+        ],
+    ),
+    (
+        "ko",
+        &[
+            // Tense consonants (U+0348) are glottalized
+            ("k\u{348}", "ʔk"),
+            ("t\u{348}", "ʔt"),
+            ("p\u{348}", "ʔp"),
+            ("s\u{348}", "ʔs"),
+            ("ʧ\u{348}", "ʔʧ"),
+            ("\u{348}", ""),
+        ],
+    ),
+    (
+        "vi",
+        &[
+            ("ɓ", "b"), // implosive
+            ("ă", "a"), // short a
+        ],
+    ),
+];
+
+/// Get the fallback substitutions for a language code
+pub fn phoneme_fallbacks(language: &str) -> &'static [(&'static str, &'static str)] {
+    PHONEME_FALLBACKS
+        .iter()
+        .find(|(code, _)| *code == language)
+        .map_or(&[], |(_, fallbacks)| fallbacks)
+}
+
+/// Replace phonemes outside the built-in vocabulary with their fallbacks
+pub fn apply_fallbacks(language: &str, phonemes: &str) -> String {
+    let mut result = phonemes.to_string();
+    for (from, to) in phoneme_fallbacks(language) {
+        if result.contains(from) {
+            result = result.replace(from, to);
+        }
+    }
+    result
+}
+
+/// Punctuation that attaches to the preceding word
+pub(crate) const PAUSE_PUNCTUATION: [char; 7] = [',', '.', '!', '?', ';', ':', '…'];
+
+/// Append a phoneme string to `result`, separated by a space unless it starts with pause punctuation
+pub(crate) fn append_phonemes(result: &mut String, part: &str) {
+    let part = part.trim();
+    if part.is_empty() {
+        return;
+    }
+    if !result.is_empty() && !result.ends_with(' ') && !part.starts_with(PAUSE_PUNCTUATION) {
+        result.push(' ');
+    }
+    result.push_str(part);
+}
 
 /// Convert a single phoneme character to its token ID
 #[inline]
 pub fn phoneme_to_id(phoneme: char) -> Option<i64> {
-    VOCAB.get(&phoneme).copied()
+    BUILTIN.id(phoneme)
 }
 
 /// Convert a token ID back to its phoneme character
 #[inline]
 pub fn id_to_phoneme(id: i64) -> Option<char> {
-    ID_TO_CHAR.get(&id).copied()
+    BUILTIN.phoneme(id)
 }
 
 /// Convert a phoneme string to a vector of token IDs
 /// Returns tokens padded with 0 at start and end
 pub fn phonemes_to_tokens(phonemes: &str) -> Vec<i64> {
-    let mut tokens = Vec::with_capacity(phonemes.len() + 2);
-    tokens.push(PAD_TOKEN); // Start padding
-
-    for c in phonemes.chars() {
-        if let Some(id) = phoneme_to_id(c) {
-            tokens.push(id);
-        } else {
-            // Log unknown character but don't crash
-            log::warn!("Unknown phoneme character: {:?} (U+{:04X})", c, c as u32);
-        }
-    }
-
-    tokens.push(PAD_TOKEN); // End padding
-
-    // Truncate if exceeding max length
-    if tokens.len() >= MAX_TOKENS + 2 {
-        tokens.truncate(MAX_TOKENS + 1);
-        tokens.push(PAD_TOKEN);
-    }
+    BUILTIN.phonemes_to_tokens(phonemes)
+}
 
-    tokens
+/// Convert a phoneme string to token IDs, failing instead of dropping or truncating
+///
+/// See [`Vocabulary::try_phonemes_to_tokens`].
+pub fn try_phonemes_to_tokens(phonemes: &str) -> Result<Vec<i64>, G2PError> {
+    BUILTIN.try_phonemes_to_tokens(phonemes)
 }
 
 /// Convert token IDs back to phoneme string
 pub fn tokens_to_phonemes(tokens: &[i64]) -> String {
-    tokens
-        .iter()
-        .filter(|&&id| id != PAD_TOKEN)
-        .filter_map(|&id| id_to_phoneme(id))
-        .collect()
+    BUILTIN.tokens_to_phonemes(tokens)
 }
 
 /// Check if a character is a valid phoneme in the vocabulary
 #[inline]
 pub fn is_valid_phoneme(c: char) -> bool {
-    VOCAB.contains_key(&c)
+    BUILTIN.contains(c)
 }
 
 /// Get all valid phoneme characters
 pub fn get_vocabulary() -> Vec<char> {
-    let mut chars: Vec<char> = VOCAB.keys().copied().collect();
-    chars.sort_by_key(|c| VOCAB.get(c).unwrap_or(&0));
-    chars
+    BUILTIN.phonemes()
 }
 
 #[cfg(test)]
@@ -309,6 +567,73 @@ mod tests {
         assert_eq!(*tokens.last().unwrap(), PAD_TOKEN);
     }
 
+    #[test]
+    fn test_try_phonemes_to_tokens() {
+        let tokens = try_phonemes_to_tokens("hˈɛlO").unwrap();
+        assert_eq!(tokens, phonemes_to_tokens("hˈɛlO"));
+
+        let err = try_phonemes_to_tokens("hɛ❓").unwrap_err();
+        assert_eq!(err, G2PError::UnmappablePhoneme { phoneme: '❓', position: 2 });
+
+        let err = try_phonemes_to_tokens(&"ə".repeat(600)).unwrap_err();
+        assert_eq!(err, G2PError::InputTooLong { tokens: 600, max: MAX_TOKENS });
+    }
+
+    #[test]
+    fn test_quotes_and_builtin_ids() {
+        assert_eq!(phoneme_to_id('"'), Some(11));
+        assert_eq!(phoneme_to_id('\u{201C}'), Some(14));
+        assert_eq!(phoneme_to_id('\u{201D}'), Some(15));
+        assert_eq!(phoneme_to_id('ʏ'), None);
+    }
+
+    #[test]
+    fn test_vocabulary_from_config() {
+        let config = r#"{"n_token": 4, "vocab": {"a": 1, "b": 2, "\u201c": 3}}"#;
+        let vocab = Vocabulary::from_config_json(config).unwrap();
+        assert_eq!(vocab.len(), 3);
+        assert_eq!(vocab.phonemes(), vec!['a', 'b', '\u{201C}']);
+        assert_eq!(vocab.phonemes_to_tokens("bxa"), vec![0, 2, 1, 0]);
+        assert_eq!(vocab.tokens_to_phonemes(&[0, 3, 1, 0]), "\u{201C}a");
+
+        // A bare vocab object works too
+        let bare = Vocabulary::from_config_json(r#"{"a": 1, "b": 2, "\u201c": 3}"#).unwrap();
+        assert_eq!(bare, vocab);
+    }
+
+    #[test]
+    fn test_invalid_vocabulary() {
+        for json in ["[1, 2]", r#"{"vocab": {"ab": 1}}"#, r#"{"vocab": {"a": 0}}"#, r#"{"vocab": {"a": "x"}}"#, "{}", "{"] {
+            assert!(
+                matches!(Vocabulary::from_config_json(json), Err(G2PError::InvalidVocabulary(_))),
+                "{}",
+                json
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let report = Vocabulary::builtin().validate("k\u{348}a ɓă");
+        assert_eq!(report.total, 6);
+        assert_eq!(report.unmapped, vec![(1, '\u{348}'), (4, 'ɓ'), (5, 'ă')]);
+        assert_eq!(report.unmapped_chars(), vec!['\u{348}', 'ɓ', 'ă']);
+        assert!(Vocabulary::builtin().validate("həlˈO").is_complete());
+    }
+
+    #[test]
+    fn test_fallbacks_cover_vocabulary() {
+        for (language, fallbacks) in PHONEME_FALLBACKS {
+            for (_, to) in fallbacks.iter() {
+                assert!(Vocabulary::builtin().validate(to).is_complete(), "{}: {}", language, to);
+            }
+        }
+        assert_eq!(apply_fallbacks("ko", "k\u{348}ap\u{348}"), "ʔkaʔp");
+        assert_eq!(apply_fallbacks("vi", "ɓăn"), "ban");
+        assert_eq!(apply_fallbacks("de", "ʃˈaɪnə"), "ʃˈInə");
+        assert_eq!(apply_fallbacks("es", "ɓ"), "ɓ");
+    }
+
     #[test]
     fn test_vocabulary_size() {
         // Kokoro has 178 tokens total (including PAD at 0)
@@ -5,6 +5,9 @@
 
 pub mod normalizer;
 
+use crate::alignment::{self, Alignment};
+use crate::engine::{EngineCapabilities, G2PEngine};
+use crate::markup;
 use crate::tokenizer;
 
 /// Turkish G2P processor
@@ -18,6 +21,10 @@ impl TurkishG2P {
 
     /// Convert Turkish text to phoneme string (IPA-based)
     pub fn text_to_phonemes(&self, text: &str) -> String {
+        if markup::has_markup(text) {
+            return markup::phonemize(text, |plain| self.text_to_phonemes(plain));
+        }
+
         // Step 1: Normalize text (numbers, dates, currency)
         let normalized = normalizer::normalize(text);
 
@@ -54,6 +61,34 @@ impl Default for TurkishG2P {
     }
 }
 
+impl G2PEngine for TurkishG2P {
+    fn language_code(&self) -> &str {
+        "tr"
+    }
+
+    fn phonemize(&self, text: &str) -> String {
+        self.text_to_phonemes(text)
+    }
+
+    fn phonemize_aligned(&self, text: &str) -> Option<Alignment> {
+        Some(alignment::align_words(text, normalizer::normalize, |word| {
+            if is_punctuation(word) {
+                word.to_string()
+            } else {
+                word_to_phonemes(word)
+            }
+        }))
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            normalization: true,
+            stress: true,
+            ..Default::default()
+        }
+    }
+}
+
 /// Convert Turkish text to token IDs (convenience function)
 pub fn text_to_tokens(text: &str) -> Vec<i64> {
     let g2p = TurkishG2P::new();
@@ -0,0 +1,109 @@
+//! Erhua (儿化) for Beijing-style Mandarin
+//!
+//! A word-final 儿 usually merges into the syllable before it as an
+//! r-coloured final: 一点儿 yī diǎnr, 哪儿 nǎr, 玩儿 wánr. Words where 儿 is a
+//! syllable of its own, mostly meaning "child" or "son" (女儿, 婴儿), are
+//! listed in `STANDALONE_ER`. Erhua syllables are written with a trailing
+//! "r" (e.g. "dianr" with tone 3).
+
+use super::pinyin::PinyinSyllable;
+
+/// Word endings where 儿 keeps its own syllable
+const STANDALONE_ER: &[&str] = &[
+    // Children and family
+    "女儿", "男儿", "孙儿", "侄儿", "妻儿", "婴儿", "幼儿", "孤儿", "患儿", "胎儿", "少儿", "健儿",
+    "宠儿", "弃儿", "乞儿", "聋儿", "新生儿", "早产儿", "混血儿", "流浪儿", "连体儿", "脑瘫儿",
+    // Names
+    "可儿", "美儿", "祖儿", "红孩儿", "蜜雪儿", "应采儿",
+];
+
+/// Whether the 儿 at the end of `word` is read as erhua
+pub fn is_erhua_word(word: &str) -> bool {
+    word.chars().count() >= 2 && word.ends_with('儿') && !STANDALONE_ER.iter().any(|ending| word.ends_with(ending))
+}
+
+/// Merge the final 儿 of an erhua word into the syllable before it
+pub fn merge(word: &str, syllables: &mut Vec<PinyinSyllable>) {
+    if !is_erhua_word(word) || syllables.len() < 2 || syllables[syllables.len() - 1].hanzi != Some('儿') {
+        return;
+    }
+    syllables.pop();
+    if let Some(last) = syllables.last_mut() {
+        last.syllable.push('r');
+    }
+}
+
+/// Whether a syllable has an r-coloured final
+pub fn is_erhua_syllable(syllable: &str) -> bool {
+    syllable.len() > 2 && syllable.ends_with('r')
+}
+
+/// Undo [`merge`], reading each erhua syllable as a full syllable followed
+/// by a standalone 儿, for Taiwan and broadcast-standard styles
+pub fn split(syllables: Vec<PinyinSyllable>) -> Vec<PinyinSyllable> {
+    let mut result = Vec::with_capacity(syllables.len());
+    for mut syl in syllables {
+        if is_erhua_syllable(&syl.syllable) {
+            syl.syllable.pop();
+            result.push(syl);
+            result.push(PinyinSyllable::new("er", 2).with_hanzi('儿'));
+        } else {
+            result.push(syl);
+        }
+    }
+    result
+}
+
+/// Join a standalone 儿 segment to the word before it, so that 玩 儿
+/// becomes the erhua word 玩儿
+pub fn merge_words(segments: Vec<(String, String)>) -> Vec<(String, String)> {
+    let mut result: Vec<(String, String)> = Vec::with_capacity(segments.len());
+    for (word, pos) in segments {
+        match result.last_mut() {
+            Some((prev, _)) if word == "儿" && prev.ends_with(super::segmenter::Segmenter::is_chinese_char) => {
+                prev.push_str(&word);
+            }
+            _ => result.push((word, pos)),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_erhua_word() {
+        assert!(is_erhua_word("一点儿"));
+        assert!(is_erhua_word("哪儿"));
+        assert!(!is_erhua_word("女儿"));
+        assert!(!is_erhua_word("小女儿"));
+        assert!(!is_erhua_word("儿子"));
+        assert!(!is_erhua_word("儿"));
+        assert!(is_erhua_word("花儿"));
+        assert!(is_erhua_word("鸟儿"));
+    }
+
+    #[test]
+    fn test_merge_and_split() {
+        let mut syllables = vec![
+            PinyinSyllable::new("yi", 1).with_hanzi('一'),
+            PinyinSyllable::new("dian", 3).with_hanzi('点'),
+            PinyinSyllable::new("er", 2).with_hanzi('儿'),
+        ];
+        merge("一点儿", &mut syllables);
+        assert_eq!(syllables.len(), 2);
+        assert_eq!(syllables[1].with_tone_number(), "dianr3");
+
+        let split = split(syllables);
+        let readings: Vec<String> = split.iter().map(|s| s.with_tone_number()).collect();
+        assert_eq!(readings, ["yi1", "dian3", "er2"]);
+    }
+
+    #[test]
+    fn test_merge_words() {
+        let segments = vec![("玩".to_string(), "v".to_string()), ("儿".to_string(), "n".to_string())];
+        assert_eq!(merge_words(segments), [("玩儿".to_string(), "v".to_string())]);
+    }
+}
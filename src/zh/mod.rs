@@ -5,10 +5,13 @@
 //! - Text normalization (numbers, dates, currency)
 //! - Word segmentation via jieba-rs
 //! - Hanzi to Pinyin conversion
-//! - Tone sandhi rules (3-3, 一, 不)
+//! - Tone sandhi rules (3-3, 一, 不, neutral tone) over prosodic units
 //! - Polyphone resolution
+//! - Erhua (儿化), which can be turned off for Taiwan/standard style
 //! - Pinyin to Zhuyin mapping for kokoro-v1.1-zh tokens
+//! - Punctuation kept as vocabulary pause marks, with spaces between words
 
+pub mod erhua;
 pub mod normalizer;
 pub mod phoneme_mapper;
 pub mod pinyin;
@@ -16,43 +19,106 @@ pub mod polyphone;
 pub mod segmenter;
 pub mod tone_sandhi;
 
+use crate::engine::{EngineCapabilities, G2PEngine};
+use crate::markup;
 use crate::tokenizer;
 
 /// Chinese G2P processor
 pub struct ChineseG2P {
     segmenter: segmenter::Segmenter,
+    /// Whether a word-final 儿 merges into the preceding syllable
+    erhua: bool,
 }
 
 impl ChineseG2P {
-    /// Create a new Chinese G2P processor
+    /// Create a new Chinese G2P processor with Beijing-style erhua
     pub fn new() -> Self {
+        Self::with_erhua(true)
+    }
+
+    /// Create a Chinese G2P processor, choosing whether word-final 儿 is read
+    /// as erhua (一点儿 yīdiǎnr) or as its own syllable (yīdiǎn ér), as in
+    /// Taiwan and broadcast-standard Mandarin
+    pub fn with_erhua(erhua: bool) -> Self {
         Self {
             segmenter: segmenter::Segmenter::new(),
+            erhua,
         }
     }
 
     /// Convert Chinese text to phoneme string (Zhuyin-based)
     pub fn text_to_phonemes(&self, text: &str) -> String {
-        // Step 1: Normalize text (numbers, dates, currency)
-        let normalized = normalizer::normalize(text);
+        if markup::has_markup(text) {
+            return markup::phonemize(text, |plain| self.text_to_phonemes(plain));
+        }
 
-        // Step 2: Segment into words with POS tagging
-        let segments = self.segmenter.segment_with_pos(&normalized);
+        // Steps 1 and 2: Normalize and segment into words with POS tagging
+        let segments = self.segment(text);
 
-        // Step 3: Convert each segment to pinyin with polyphone resolution
-        let mut pinyin_result = Vec::new();
-        for (word, pos) in &segments {
-            let pinyins = pinyin::to_pinyin_with_pos(word, pos);
-            pinyin_result.extend(pinyins);
+        // Step 3: Convert each word to pinyin with polyphone resolution,
+        // collecting words into phrases delimited by punctuation
+        let mut output = PhonemeWriter::default();
+        let mut phrase: Vec<tone_sandhi::Word> = Vec::new();
+        for (i, (word, pos)) in segments.iter().enumerate() {
+            let marks: Option<Vec<char>> = word.chars().map(phoneme_mapper::map_punctuation).collect();
+            match marks {
+                Some(marks) => {
+                    output.push_phrase(&mut phrase);
+                    for mark in marks {
+                        output.push_punctuation(mark);
+                    }
+                }
+                None => {
+                    let pinyins = self.word_pinyin(&segments, i);
+                    if !pinyins.is_empty() {
+                        phrase.push(tone_sandhi::Word::new(word, pos, pinyins));
+                    }
+                }
+            }
         }
+        output.push_phrase(&mut phrase);
+
+        output.text
+    }
 
-        // Step 4: Apply tone sandhi rules
-        let sandhi_applied = tone_sandhi::apply_tone_sandhi(&pinyin_result);
+    /// Convert Chinese text to tone-numbered pinyin with polyphones resolved,
+    /// before tone sandhi
+    pub fn text_to_pinyin(&self, text: &str) -> Vec<pinyin::PinyinSyllable> {
+        let segments = self.segment(text);
+        (0..segments.len()).flat_map(|i| self.word_pinyin(&segments, i)).collect()
+    }
 
-        // Step 5: Convert pinyin to Zhuyin phonemes
-        let phonemes = phoneme_mapper::pinyin_to_zhuyin(&sandhi_applied);
+    /// Normalize text (numbers, dates, currency) and segment it into words
+    /// with POS tags
+    fn segment(&self, text: &str) -> Vec<(String, String)> {
+        let normalized = normalizer::normalize(text);
+        let segments = self.segmenter.segment_with_pos(&normalized);
+        if self.erhua {
+            erhua::merge_words(segments)
+        } else {
+            segments
+        }
+    }
 
-        phonemes
+    /// Pinyin of the word at `index`, with polyphones read in the context of
+    /// the words next to it
+    fn word_pinyin(&self, segments: &[(String, String)], index: usize) -> Vec<pinyin::PinyinSyllable> {
+        let (word, pos) = &segments[index];
+        let prev = index.checked_sub(1).map(|i| &segments[i]);
+        let next = segments.get(index + 1);
+        let context = polyphone::Context {
+            pos,
+            prev_char: prev.and_then(|(word, _)| word.chars().last()),
+            prev_pos: prev.map_or("", |(_, pos)| pos),
+            next_char: next.and_then(|(word, _)| word.chars().next()),
+            next_pos: next.map_or("", |(_, pos)| pos),
+        };
+        let pinyins = pinyin::to_pinyin_in_context(word, &context);
+        if self.erhua {
+            pinyins
+        } else {
+            erhua::split(pinyins)
+        }
     }
 
     /// Convert Chinese text to token IDs
@@ -68,6 +134,76 @@ impl Default for ChineseG2P {
     }
 }
 
+impl G2PEngine for ChineseG2P {
+    fn language_code(&self) -> &str {
+        "zh"
+    }
+
+    fn phonemize(&self, text: &str) -> String {
+        self.text_to_phonemes(text)
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            dictionary: true,
+            normalization: true,
+            tones: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// Phoneme output with words separated by spaces and punctuation attached to
+/// the word it belongs to
+#[derive(Default)]
+struct PhonemeWriter {
+    text: String,
+    /// Whether a straight quote has been opened and not yet closed
+    quote_open: bool,
+}
+
+impl PhonemeWriter {
+    /// Whether the next word attaches to the output without a space
+    fn attaches_next(&self) -> bool {
+        self.text.is_empty() || self.text.ends_with(['(', '\u{201C}', ' ']) || (self.quote_open && self.text.ends_with('"'))
+    }
+
+    /// Apply tone sandhi to a phrase and write out its prosodic units
+    fn push_phrase(&mut self, words: &mut Vec<tone_sandhi::Word>) {
+        // Step 4: Group words into prosodic units and apply tone sandhi rules
+        let units = tone_sandhi::apply_word_sandhi(std::mem::take(words));
+
+        // Step 5: Convert pinyin to Zhuyin phonemes, one unit at a time
+        for unit in units {
+            if !self.attaches_next() {
+                self.text.push(' ');
+            }
+            self.text.push_str(&phoneme_mapper::word_to_zhuyin(&unit.syllables));
+        }
+    }
+
+    /// Write a punctuation mark, attaching closing marks to the preceding
+    /// word and opening marks to the following one
+    fn push_punctuation(&mut self, mark: char) {
+        // Runs such as …… and —— are read as a single mark
+        if matches!(mark, '…' | '—') && self.text.ends_with(mark) {
+            return;
+        }
+        let opening = match mark {
+            '(' | '\u{201C}' => true,
+            '"' => !self.quote_open,
+            _ => false,
+        };
+        if mark == '"' {
+            self.quote_open = !self.quote_open;
+        }
+        if opening && !self.attaches_next() {
+            self.text.push(' ');
+        }
+        self.text.push(mark);
+    }
+}
+
 /// Convert Chinese text to token IDs (convenience function)
 pub fn text_to_tokens(text: &str) -> Vec<i64> {
     let g2p = ChineseG2P::new();
@@ -100,9 +236,38 @@ mod tests {
         assert!(!phonemes.is_empty());
     }
 
+    #[test]
+    fn test_punctuation_and_word_boundaries() {
+        let g2p = ChineseG2P::new();
+        assert_eq!(g2p.text_to_phonemes("你好，世界！"), "ㄋㄧ↗ㄏㄠ↓, ㄕ↘ㄐㄧㄝ↘!");
+        assert_eq!(g2p.text_to_phonemes("等等……好吧"), "ㄉㄥ↗ㄉㄥ↓… ㄏㄠ↓ ㄅㄚ");
+        assert_eq!(g2p.text_to_phonemes("他说：「好。」"), "ㄊㄚ→ ㄕㄨㄛ→: \u{201C}ㄏㄠ↓.\u{201D}");
+    }
+
+    #[test]
+    fn test_erhua_switch() {
+        assert_eq!(ChineseG2P::new().text_to_phonemes("我们去玩儿。"), "ㄨㄛ↓ㄇㄣ ㄑㄩ↘ ㄨㄢㄦ↗.");
+        assert_eq!(ChineseG2P::with_erhua(false).text_to_phonemes("我们去玩儿。"), "ㄨㄛ↓ㄇㄣ ㄑㄩ↘ ㄨㄢ↗ㄦ↗.");
+        assert_eq!(ChineseG2P::new().text_to_phonemes("女儿"), "ㄋㄩ↓ㄦ↗");
+
+        let pinyin = |text: &str| -> Vec<String> { ChineseG2P::new().text_to_pinyin(text).iter().map(|s| s.with_tone_number()).collect() };
+        assert_eq!(pinyin("花儿"), ["huar1"]);
+    }
+
     #[test]
     fn test_tokens_not_empty() {
         let tokens = text_to_tokens("你好世界");
         assert!(tokens.len() > 2); // At least padding + some content
     }
+
+    #[test]
+    fn test_polyphones_in_context() {
+        let g2p = ChineseG2P::new();
+        let pinyin = |text: &str| -> Vec<String> { g2p.text_to_pinyin(text).iter().map(|s| s.with_tone_number()).collect() };
+        assert_eq!(pinyin("我得走了"), ["wo3", "dei3", "zou3", "le5"]);
+        assert_eq!(pinyin("他跑得很快"), ["ta1", "pao3", "de5", "hen3", "kuai4"]);
+        assert_eq!(pinyin("他买了一只猫"), ["ta1", "mai3", "le5", "yi1", "zhi1", "mao1"]);
+        assert_eq!(pinyin("明天还给我")[2], "huan2");
+        assert_eq!(pinyin("他还没来")[1], "hai2");
+    }
 }
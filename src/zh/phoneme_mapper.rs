@@ -6,6 +6,7 @@
 //! Zhuyin (注音符號) is a phonetic system for Mandarin Chinese.
 //! The Kokoro Chinese model uses Zhuyin symbols with tone markers.
 
+use super::erhua;
 use super::pinyin::PinyinSyllable;
 use phf::phf_map;
 
@@ -25,6 +26,44 @@ const TONE_MARKERS: [char; 6] = [
     ' ',  // 5: neutral tone (no marker)
 ];
 
+/// Chinese and ASCII punctuation mapped to the vocabulary's punctuation
+static PUNCTUATION: phf::Map<char, char> = phf_map! {
+    '，' => ',',
+    '、' => ',',
+    '。' => '.',
+    '．' => '.',
+    '！' => '!',
+    '？' => '?',
+    '；' => ';',
+    '：' => ':',
+    '…' => '…',
+    '⋯' => '…',
+    '—' => '—',
+    '（' => '(',
+    '）' => ')',
+    '「' => '\u{201C}',
+    '」' => '\u{201D}',
+    '『' => '\u{201C}',
+    '』' => '\u{201D}',
+    '《' => '\u{201C}',
+    '》' => '\u{201D}',
+    '〈' => '\u{201C}',
+    '〉' => '\u{201D}',
+    '【' => '\u{201C}',
+    '】' => '\u{201D}',
+    '\u{201C}' => '\u{201C}',
+    '\u{201D}' => '\u{201D}',
+    ',' => ',',
+    '.' => '.',
+    '!' => '!',
+    '?' => '?',
+    ';' => ';',
+    ':' => ':',
+    '(' => '(',
+    ')' => ')',
+    '"' => '"',
+};
+
 /// Pinyin initials to Zhuyin mapping
 static INITIALS: phf::Map<&'static str, &'static str> = phf_map! {
     "b" => "ㄅ",
@@ -193,6 +232,11 @@ static SYLLABLES: phf::Map<&'static str, &'static str> = phf_map! {
 fn syllable_to_zhuyin(syllable: &str) -> String {
     let syllable_lower = syllable.to_lowercase();
 
+    // Erhua: the r-coloured final is written as the base syllable plus ㄦ
+    if erhua::is_erhua_syllable(&syllable_lower) {
+        return syllable_to_zhuyin(&syllable_lower[..syllable_lower.len() - 1]) + "ㄦ";
+    }
+
     // First check complete syllable mapping
     if let Some(&zhuyin) = SYLLABLES.get(syllable_lower.as_str()) {
         return zhuyin.to_string();
@@ -258,6 +302,16 @@ fn split_initial_final(syllable: &str) -> (&str, &str) {
     ("", syllable)
 }
 
+/// Append a syllable's Zhuyin and tone marker to `result`
+fn push_syllable(result: &mut String, syl: &PinyinSyllable) {
+    result.push_str(&syllable_to_zhuyin(&syl.syllable));
+
+    // Add tone marker (except for neutral tone 5)
+    if syl.tone >= 1 && syl.tone <= 4 {
+        result.push(TONE_MARKERS[syl.tone as usize]);
+    }
+}
+
 /// Convert a sequence of PinyinSyllables to Zhuyin string with tone markers
 pub fn pinyin_to_zhuyin(syllables: &[PinyinSyllable]) -> String {
     let mut result = String::new();
@@ -267,20 +321,27 @@ pub fn pinyin_to_zhuyin(syllables: &[PinyinSyllable]) -> String {
         if i > 0 {
             result.push(' ');
         }
+        push_syllable(&mut result, syl);
+    }
 
-        // Convert syllable to Zhuyin
-        let zhuyin = syllable_to_zhuyin(&syl.syllable);
-        result.push_str(&zhuyin);
+    result
+}
 
-        // Add tone marker (except for neutral tone 5)
-        if syl.tone >= 1 && syl.tone <= 4 {
-            result.push(TONE_MARKERS[syl.tone as usize]);
-        }
+/// Convert the syllables of one word to Zhuyin, written without spaces so
+/// that spaces mark word boundaries
+pub fn word_to_zhuyin(syllables: &[PinyinSyllable]) -> String {
+    let mut result = String::new();
+    for syl in syllables {
+        push_syllable(&mut result, syl);
     }
-
     result
 }
 
+/// Map a punctuation mark to the vocabulary punctuation it is read as
+pub fn map_punctuation(c: char) -> Option<char> {
+    PUNCTUATION.get(&c).copied()
+}
+
 /// Convert a single pinyin string with tone number to Zhuyin
 pub fn convert_single(pinyin_with_tone: &str) -> String {
     use super::pinyin::parse_pinyin;
@@ -327,6 +388,28 @@ mod tests {
         assert!(result.contains("ㄏ"));
     }
 
+    #[test]
+    fn test_word_to_zhuyin() {
+        let syllables = vec![PinyinSyllable::new("shi", 4), PinyinSyllable::new("jie", 4)];
+        assert_eq!(word_to_zhuyin(&syllables), "ㄕ↘ㄐㄧㄝ↘");
+        assert_eq!(pinyin_to_zhuyin(&syllables), "ㄕ↘ ㄐㄧㄝ↘");
+    }
+
+    #[test]
+    fn test_erhua_syllables() {
+        assert_eq!(syllable_to_zhuyin("er"), "ㄦ");
+        assert_eq!(syllable_to_zhuyin("dianr"), "ㄉㄧㄢㄦ");
+        assert_eq!(convert_single("nar3"), "ㄋㄚㄦ↓");
+    }
+
+    #[test]
+    fn test_map_punctuation() {
+        assert_eq!(map_punctuation('，'), Some(','));
+        assert_eq!(map_punctuation('、'), Some(','));
+        assert_eq!(map_punctuation('《'), Some('\u{201C}'));
+        assert_eq!(map_punctuation('中'), None);
+    }
+
     #[test]
     fn test_tone_markers() {
         let syllables = vec![
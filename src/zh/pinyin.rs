@@ -1,9 +1,11 @@
 //! Hanzi to Pinyin conversion
 //!
 //! This module handles conversion of Chinese characters (Hanzi) to Pinyin
-//! with tone numbers.
+//! with tone numbers. Every CJK Unified Ideograph (including Extension A)
+//! with a Mandarin reading in Unihan is covered by a table compiled by
+//! build.rs from `dictionaries/zh_pinyin.txt`.
 
-use super::polyphone;
+use super::{erhua, polyphone};
 use phf::phf_map;
 
 /// Pinyin syllable with tone
@@ -13,6 +15,8 @@ pub struct PinyinSyllable {
     pub syllable: String,
     /// Tone number (1-5, where 5 is neutral/light tone)
     pub tone: u8,
+    /// The character the syllable was read from, when known
+    pub hanzi: Option<char>,
 }
 
 impl PinyinSyllable {
@@ -20,17 +24,29 @@ impl PinyinSyllable {
         Self {
             syllable: syllable.to_string(),
             tone,
+            hanzi: None,
         }
     }
 
+    /// Record the character the syllable was read from
+    pub fn with_hanzi(mut self, hanzi: char) -> Self {
+        self.hanzi = Some(hanzi);
+        self
+    }
+
     /// Get pinyin with tone number suffix (e.g., "ni3")
     pub fn with_tone_number(&self) -> String {
         format!("{}{}", self.syllable, self.tone)
     }
 }
 
+// Default reading of every character, compiled by build.rs from
+// dictionaries/zh_pinyin.txt
+include!(concat!(env!("OUT_DIR"), "/zh_pinyin.rs"));
+
 /// Common single-character pinyin mappings (most frequent characters)
-/// This is a subset; for full coverage, we use the polyphone dictionary
+/// These take precedence over the generated table where its Unihan reading
+/// is not the one used in running text
 static CHAR_TO_PINYIN: phf::Map<char, &'static str> = phf_map! {
     '的' => "de5",
     '一' => "yi1",
@@ -191,35 +207,83 @@ pub fn char_to_pinyin(c: char) -> Option<PinyinSyllable> {
         return Some(parse_pinyin(pinyin));
     }
 
-    None
+    // Fall back to the full character table
+    table_pinyin(c).map(parse_pinyin)
+}
+
+/// Parse a dictionary reading of `chars`, one syllable per character
+fn phrase_syllables<'a>(chars: &'a [char], pinyins: &'a str) -> impl Iterator<Item = PinyinSyllable> + 'a {
+    chars
+        .iter()
+        .zip(pinyins.split(' '))
+        .map(|(&c, pinyin)| parse_pinyin(pinyin).with_hanzi(c))
+}
+
+/// Look up a character in the generated table
+fn table_pinyin(c: char) -> Option<&'static str> {
+    let offset = (c as u32).checked_sub(HANZI_START)?;
+    match *HANZI_INDEX.get(offset as usize)? {
+        0 => None,
+        id => Some(HANZI_SYLLABLES[id as usize]),
+    }
+}
+
+/// Length in characters of the longest dictionary phrase starting at
+/// `chars[start]`, with its pinyin
+fn longest_phrase(chars: &[char], start: usize) -> Option<(usize, &'static str)> {
+    let longest = polyphone::MAX_PHRASE_CHARS.min(chars.len() - start);
+    (2..=longest).rev().find_map(|len| {
+        let phrase: String = chars[start..start + len].iter().collect();
+        polyphone::lookup_phrase(&phrase).map(|pinyin| (len, pinyin))
+    })
 }
 
 /// Convert a word to pinyin with POS tag for disambiguation
 pub fn to_pinyin_with_pos(word: &str, pos: &str) -> Vec<PinyinSyllable> {
+    to_pinyin_in_context(word, &polyphone::Context::new(pos))
+}
+
+/// Convert a word to pinyin, disambiguating polyphones by its POS tag and
+/// the words around it
+pub fn to_pinyin_in_context(word: &str, context: &polyphone::Context) -> Vec<PinyinSyllable> {
+    let chars: Vec<char> = word.chars().collect();
+
     // First check if this exact phrase has a polyphone entry
     if let Some(pinyins) = polyphone::lookup_phrase(word) {
-        return pinyins
-            .split(' ')
-            .map(|p| parse_pinyin(p))
-            .collect();
+        let mut result = phrase_syllables(&chars, pinyins).collect();
+        erhua::merge(word, &mut result);
+        return result;
     }
 
-    // Otherwise, convert character by character
+    // Otherwise, convert character by character, reading any dictionary
+    // phrases inside the word (longest match first) as a whole
     let mut result = Vec::new();
-    for c in word.chars() {
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
         // Skip non-Chinese characters
         if !super::segmenter::Segmenter::is_chinese_char(c) {
+            i += 1;
             continue;
         }
 
-        // Try POS-based disambiguation first
-        if let Some(pinyin) = polyphone::lookup_with_pos(c, pos) {
-            result.push(parse_pinyin(pinyin));
+        if let Some((len, pinyins)) = longest_phrase(&chars, i) {
+            result.extend(phrase_syllables(&chars[i..i + len], pinyins));
+            i += len;
+            continue;
+        }
+
+        // Try context rules before the default reading
+        if let Some(pinyin) = polyphone::lookup_in_context(&chars, i, context) {
+            result.push(parse_pinyin(pinyin).with_hanzi(c));
         } else if let Some(pinyin) = char_to_pinyin(c) {
-            result.push(pinyin);
+            result.push(pinyin.with_hanzi(c));
         }
+        i += 1;
     }
 
+    // Merge a final 儿 into the syllable before it (一点儿 → yi1 dianr3)
+    erhua::merge(word, &mut result);
     result
 }
 
@@ -263,6 +327,42 @@ mod tests {
         assert_eq!(result[1].tone, 3);
     }
 
+    #[test]
+    fn test_full_coverage() {
+        // Characters outside the hand-written tables come from the generated one
+        assert_eq!(char_to_pinyin('龙').unwrap().with_tone_number(), "long2");
+        assert_eq!(char_to_pinyin('绿').unwrap().with_tone_number(), "lv4");
+        assert_eq!(char_to_pinyin('㐀').unwrap().with_tone_number(), "qiu1");
+        // Hand-written readings take precedence
+        assert_eq!(char_to_pinyin('长').unwrap().with_tone_number(), "chang2");
+        assert!(char_to_pinyin('a').is_none());
+        assert!(char_to_pinyin('ㄅ').is_none());
+    }
+
+    #[test]
+    fn test_table_syllables_map_to_zhuyin() {
+        for pinyin in &HANZI_SYLLABLES[1..] {
+            let zhuyin = super::super::phoneme_mapper::convert_single(pinyin);
+            assert!(!zhuyin.chars().any(|c| c.is_ascii_alphabetic()), "{} -> {}", pinyin, zhuyin);
+        }
+    }
+
+    #[test]
+    fn test_phrases_within_words() {
+        let readings = |word: &str| -> Vec<String> { to_pinyin(word).iter().map(|p| p.with_tone_number()).collect() };
+        assert_eq!(readings("银行"), ["yin2", "hang2"]);
+        assert_eq!(readings("中国银行"), ["zhong1", "guo2", "yin2", "hang2"]);
+        assert_eq!(readings("音乐会"), ["yin1", "yue4", "hui4"]);
+    }
+
+    #[test]
+    fn test_erhua() {
+        let readings = |word: &str| -> Vec<String> { to_pinyin(word).iter().map(|p| p.with_tone_number()).collect() };
+        assert_eq!(readings("一点儿"), ["yi1", "dianr3"]);
+        assert_eq!(readings("女儿"), ["nv3", "er2"]);
+        assert_eq!(readings("儿子"), ["er2", "zi5"]);
+    }
+
     #[test]
     fn test_number_chars() {
         let result = to_pinyin("一二三");
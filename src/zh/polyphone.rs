@@ -3,280 +3,881 @@
 //! Many Chinese characters have multiple pronunciations (polyphones/多音字).
 //! This module handles disambiguation based on:
 //! 1. Phrase-based lookup (highest priority)
-//! 2. POS-based disambiguation
+//! 2. Context rules over the word's POS tag, the character's place in the
+//!    word and the characters and POS tags around it
 //! 3. Frequency-based default
 
 use once_cell::sync::Lazy;
 use phf::phf_map;
 use std::collections::HashMap;
 
-/// Phrase-based polyphone mappings (word -> pinyin with tones)
-/// These are common multi-character words where pronunciation is context-dependent
-static PHRASE_PINYIN: phf::Map<&'static str, &'static str> = phf_map! {
-    // 行 (xíng = walk/travel, háng = row/profession)
-    "行走" => "xing2 zou3",
-    "行人" => "xing2 ren2",
-    "行动" => "xing2 dong4",
-    "行为" => "xing2 wei2",
-    "执行" => "zhi2 xing2",
-    "进行" => "jin4 xing2",
-    "举行" => "ju3 xing2",
-    "银行" => "yin2 hang2",
-    "行业" => "hang2 ye4",
-    "行列" => "hang2 lie4",
-    "一行" => "yi4 hang2",
-    "同行" => "tong2 hang2",
-
-    // 了 (le = particle, liǎo = understand/finish)
-    "了解" => "liao3 jie3",
-    "了不起" => "liao3 bu4 qi3",
-    "了结" => "liao3 jie2",
-    "明了" => "ming2 liao3",
-    "受不了" => "shou4 bu4 liao3",
-
-    // 得 (de = particle, dé = obtain, děi = must)
-    "得到" => "de2 dao4",
-    "取得" => "qu3 de2",
-    "获得" => "huo4 de2",
-    "得知" => "de2 zhi1",
-    "觉得" => "jue2 de5",
-
-    // 地 (dì = earth/ground, de = particle)
-    "地方" => "di4 fang1",
-    "地球" => "di4 qiu2",
-    "地区" => "di4 qu1",
-    "土地" => "tu3 di4",
-
-    // 的 (de = particle, dí = target, dì = indeed)
-    "目的" => "mu4 di4",
-    "的确" => "di2 que4",
-
-    // 还 (hái = still, huán = return)
-    "还是" => "hai2 shi4",
-    "还有" => "hai2 you3",
-    "还要" => "hai2 yao4",
-    "归还" => "gui1 huan2",
-    "偿还" => "chang2 huan2",
-    "还原" => "huan2 yuan2",
-
-    // 长 (cháng = long, zhǎng = grow/chief)
-    "长城" => "chang2 cheng2",
-    "长度" => "chang2 du4",
-    "长期" => "chang2 qi1",
-    "长久" => "chang2 jiu3",
-    "成长" => "cheng2 zhang3",
-    "生长" => "sheng1 zhang3",
-    "增长" => "zeng1 zhang3",
-    "校长" => "xiao4 zhang3",
-    "部长" => "bu4 zhang3",
-    "市长" => "shi4 zhang3",
-
-    // 重 (zhòng = heavy, chóng = repeat)
-    "重要" => "zhong4 yao4",
-    "重点" => "zhong4 dian3",
-    "重视" => "zhong4 shi4",
-    "重量" => "zhong4 liang4",
-    "重复" => "chong2 fu4",
-    "重新" => "chong2 xin1",
-
-    // 乐 (lè = happy, yuè = music)
-    "快乐" => "kuai4 le4",
-    "欢乐" => "huan1 le4",
-    "音乐" => "yin1 yue4",
-    "乐器" => "yue4 qi4",
-
-    // 教 (jiào = teach/religion, jiāo = instruct)
-    "教育" => "jiao4 yu4",
-    "教学" => "jiao4 xue2",
-    "教室" => "jiao4 shi4",
-    "宗教" => "zong1 jiao4",
-    "教书" => "jiao1 shu1",
-
-    // 数 (shù = number, shǔ = count)
-    "数字" => "shu4 zi4",
-    "数学" => "shu4 xue2",
-    "数量" => "shu4 liang4",
-    "数据" => "shu4 ju4",
-
-    // 空 (kōng = empty/sky, kòng = free time)
-    "空气" => "kong1 qi4",
-    "空间" => "kong1 jian1",
-    "天空" => "tian1 kong1",
-    "空调" => "kong1 tiao2",
-    "有空" => "you3 kong4",
-
-    // 差 (chà = differ, chāi = send, chā = difference)
-    "差不多" => "cha4 bu5 duo1",
-    "差别" => "cha1 bie2",
-    "出差" => "chu1 chai1",
-
-    // 难 (nán = difficult, nàn = disaster)
-    "困难" => "kun4 nan2",
-    "难过" => "nan2 guo4",
-    "难题" => "nan2 ti2",
-    "灾难" => "zai1 nan4",
-    "难民" => "nan4 min2",
-
-    // 便 (biàn = convenient, pián = cheap)
-    "方便" => "fang1 bian4",
-    "便利" => "bian4 li4",
-    "便宜" => "pian2 yi5",
-
-    // 兴 (xīng = prosper, xìng = interest)
-    "高兴" => "gao1 xing4",
-    "兴趣" => "xing4 qu4",
-    "兴奋" => "xing1 fen4",
-    "复兴" => "fu4 xing1",
-
-    // 朝 (cháo = dynasty/toward, zhāo = morning)
-    "朝代" => "chao2 dai4",
-    "朝向" => "chao2 xiang4",
-
-    // 更 (gèng = more, gēng = change)
-    "更加" => "geng4 jia1",
-    "更好" => "geng4 hao3",
-    "变更" => "bian4 geng1",
-
-    // 处 (chù = place, chǔ = handle)
-    "处理" => "chu3 li3",
-    "到处" => "dao4 chu4",
-    "处于" => "chu3 yu2",
-
-    // 调 (diào = tone/transfer, tiáo = adjust)
-    "调查" => "diao4 cha2",
-    "调整" => "tiao2 zheng3",
-    "调节" => "tiao2 jie2",
-    "声调" => "sheng1 diao4",
-
-    // 藏 (cáng = hide, zàng = Tibet/storage)
-    "西藏" => "xi1 zang4",
-    "收藏" => "shou1 cang2",
-    "隐藏" => "yin3 cang2",
-
-    // 称 (chēng = call, chèn = fit)
-    "称呼" => "cheng1 hu1",
-    "称为" => "cheng1 wei2",
-    "名称" => "ming2 cheng1",
-    "对称" => "dui4 chen4",
-    "匀称" => "yun2 chen4",
-
-    // 少 (shǎo = few, shào = young)
-    "多少" => "duo1 shao3",
-    "减少" => "jian3 shao3",
-    "少年" => "shao4 nian2",
-    "少数" => "shao3 shu4",
-
-    // 省 (shěng = province/save, xǐng = reflect)
-    "省份" => "sheng3 fen4",
-    "节省" => "jie2 sheng3",
-    "反省" => "fan3 xing3",
-
-    // 相 (xiāng = mutual, xiàng = appearance)
-    "相信" => "xiang1 xin4",
-    "相同" => "xiang1 tong2",
-    "相关" => "xiang1 guan1",
-    "照相" => "zhao4 xiang4",
-    "相机" => "xiang4 ji1",
-    "真相" => "zhen1 xiang4",
-
-    // 好 (hǎo = good, hào = like)
-    "你好" => "ni3 hao3",
-    "好的" => "hao3 de5",
-    "爱好" => "ai4 hao4",
-    "好奇" => "hao4 qi2",
-
-    // 中 (zhōng = middle, zhòng = hit)
-    "中国" => "zhong1 guo2",
-    "中间" => "zhong1 jian1",
-    "中心" => "zhong1 xin1",
-    "命中" => "ming4 zhong4",
-
-    // 没 (méi = not have, mò = sink)
-    "没有" => "mei2 you3",
-    "没关系" => "mei2 guan1 xi5",
-    "淹没" => "yan1 mo4",
-    "沉没" => "chen2 mo4",
-
-    // Common phrases
-    "什么" => "shen2 me5",
-    "怎么" => "zen3 me5",
-    "那么" => "na4 me5",
-    "这么" => "zhe4 me5",
-    "为什么" => "wei4 shen2 me5",
-    "喜欢" => "xi3 huan1",
-    "知道" => "zhi1 dao4",
-    "可以" => "ke3 yi3",
-    "应该" => "ying1 gai1",
-    "需要" => "xu1 yao4",
-    "已经" => "yi3 jing1",
-    "虽然" => "sui1 ran2",
-    "但是" => "dan4 shi4",
-    "因为" => "yin1 wei4",
-    "所以" => "suo3 yi3",
-    "如果" => "ru2 guo3",
-    "这样" => "zhe4 yang4",
-    "那样" => "na4 yang4",
-    "非常" => "fei1 chang2",
-    "特别" => "te4 bie2",
-    "比较" => "bi3 jiao4",
-    "可能" => "ke3 neng2",
-    "必须" => "bi4 xu1",
-    "其实" => "qi2 shi2",
-    "现在" => "xian4 zai4",
-    "以后" => "yi3 hou4",
-    "以前" => "yi3 qian2",
-};
+// Phrase-based polyphone mappings (word -> pinyin with tones), compiled by
+// build.rs from dictionaries/zh_phrases.txt
+include!(concat!(env!("OUT_DIR"), "/zh_phrases.rs"));
+
+/// Characters that come before a measure word (两只, 这种)
+const COUNTERS: &str = "一二两三四五六七八九十百千万几半每这那哪某各整";
+
+/// Numerals only, for measure words that start a segmented word (一只)
+const NUMERALS: &str = "一二两三四五六七八九十百千万几半每";
+
+/// Last characters of words for organisations and groups, whose head is
+/// 长 zhǎng (校长, 董事长)
+const HEADS: &str = "家校部市队院局县省班组科处船社首师村厂团所站州镇乡署司馆店旅营连排军酋族议警机学兄会园台课股";
+
+/// Where a character sits in its word
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Place {
+    Anywhere,
+    /// The character is a word on its own
+    Alone,
+    /// First character of a longer word
+    First,
+    /// Last character of a longer word
+    Last,
+}
 
-/// POS-based default pinyin for polyphones
-/// Maps (character, POS tag prefix) to pinyin
-static POS_BASED_PINYIN: Lazy<HashMap<(char, &'static str), &'static str>> = Lazy::new(|| {
-    let mut m = HashMap::new();
+/// A reading for a character that applies when all of its conditions hold;
+/// empty conditions always hold
+#[derive(Debug)]
+struct Rule {
+    hanzi: char,
+    pinyin: &'static str,
+    place: Place,
+    /// POS tag prefixes of the word containing the character
+    pos: &'static [&'static str],
+    /// Characters one of which comes directly before this one
+    after: &'static str,
+    /// Characters one of which comes directly after this one
+    before: &'static str,
+    /// POS tag prefixes of the previous word
+    after_pos: &'static [&'static str],
+    /// POS tag prefixes of the next word
+    before_pos: &'static [&'static str],
+}
 
-    // 行: xíng for verbs, háng for nouns
-    m.insert(('行', "v"), "xing2");
-    m.insert(('行', "n"), "hang2");
+impl Rule {
+    const fn new(hanzi: char, pinyin: &'static str) -> Self {
+        Self {
+            hanzi,
+            pinyin,
+            place: Place::Anywhere,
+            pos: &[],
+            after: "",
+            before: "",
+            after_pos: &[],
+            before_pos: &[],
+        }
+    }
 
-    // 了: liǎo for verbs, le for particles
-    m.insert(('了', "v"), "liao3");
-    m.insert(('了', "u"), "le5");
+    const fn alone(self) -> Self {
+        Self { place: Place::Alone, ..self }
+    }
+
+    const fn first(self) -> Self {
+        Self { place: Place::First, ..self }
+    }
 
-    // 得: dé for verbs, de for particles, děi for auxiliary
-    m.insert(('得', "v"), "de2");
-    m.insert(('得', "u"), "de5");
+    const fn last(self) -> Self {
+        Self { place: Place::Last, ..self }
+    }
 
-    // 地: dì for nouns, de for particles
-    m.insert(('地', "n"), "di4");
-    m.insert(('地', "u"), "de5");
+    const fn pos(self, pos: &'static [&'static str]) -> Self {
+        Self { pos, ..self }
+    }
 
-    // 还: hái for adverbs, huán for verbs
-    m.insert(('还', "d"), "hai2");
-    m.insert(('还', "v"), "huan2");
+    const fn after(self, after: &'static str) -> Self {
+        Self { after, ..self }
+    }
 
-    // 长: cháng for adjectives, zhǎng for verbs/nouns (chief)
-    m.insert(('长', "a"), "chang2");
-    m.insert(('长', "v"), "zhang3");
-    m.insert(('长', "n"), "zhang3");
+    const fn before(self, before: &'static str) -> Self {
+        Self { before, ..self }
+    }
 
-    // 重: zhòng for adjectives, chóng for adverbs/verbs
-    m.insert(('重', "a"), "zhong4");
-    m.insert(('重', "d"), "chong2");
+    const fn after_pos(self, after_pos: &'static [&'static str]) -> Self {
+        Self { after_pos, ..self }
+    }
 
-    // 乐: lè for adjectives, yuè for nouns
-    m.insert(('乐', "a"), "le4");
-    m.insert(('乐', "n"), "yue4");
+    const fn before_pos(self, before_pos: &'static [&'static str]) -> Self {
+        Self { before_pos, ..self }
+    }
 
-    // 教: jiào for nouns, jiāo for verbs
-    m.insert(('教', "n"), "jiao4");
-    m.insert(('教', "v"), "jiao1");
+    fn matches(&self, word: &[char], index: usize, context: &Context) -> bool {
+        let alone = word.len() == 1;
+        let place = match self.place {
+            Place::Anywhere => true,
+            Place::Alone => alone,
+            Place::First => !alone && index == 0,
+            Place::Last => !alone && index + 1 == word.len(),
+        };
+        let prev = index.checked_sub(1).map(|i| word[i]).or(context.prev_char);
+        let next = word.get(index + 1).copied().or(context.next_char);
+        place
+            && tag_matches(self.pos, context.pos)
+            && char_matches(self.after, prev)
+            && char_matches(self.before, next)
+            && tag_matches(self.after_pos, context.prev_pos)
+            && tag_matches(self.before_pos, context.next_pos)
+    }
+}
 
-    // 数: shù for nouns, shǔ for verbs
-    m.insert(('数', "n"), "shu4");
-    m.insert(('数', "v"), "shu3");
+fn tag_matches(prefixes: &[&str], tag: &str) -> bool {
+    prefixes.is_empty() || prefixes.iter().any(|prefix| tag.starts_with(prefix))
+}
 
+fn char_matches(chars: &str, c: Option<char>) -> bool {
+    chars.is_empty() || c.is_some_and(|c| chars.contains(c))
+}
+
+/// Context rules for common polyphones, tried in order for each character.
+/// They cover characters outside the phrase dictionary: single-character
+/// words, read from their jieba POS tag and their neighbours, and words
+/// jieba builds that the dictionary does not list. About 270 characters have
+/// rules; any other polyphone gets its `DEFAULT_PINYIN` reading.
+static RULES: &[Rule] = &[
+    // 行: háng as a measure word and noun (两行字, 行业), xíng as a verb
+    Rule::new('行', "hang2").alone().after(COUNTERS),
+    Rule::new('行', "hang2").last().after("二两三四五六七八九十百千万几半每"),
+    Rule::new('行', "xing2").alone().pos(&["v", "a"]),
+    Rule::new('行', "hang2").alone().pos(&["n", "q"]),
+    // 长: zhǎng for growing (长大, 长得) and for heads (校长, 镇长)
+    Rule::new('长', "zhang3").first().before("大高出成得胖"),
+    Rule::new('长', "zhang3").alone().before("大高得着胖"),
+    Rule::new('长', "zhang3").last().after(HEADS),
+    Rule::new('长', "zhang3").alone().pos(&["v"]),
+    Rule::new('长', "chang2").alone().pos(&["a"]),
+    // 重: chóng for "again" and "layer" (重新, 双重)
+    Rule::new('重', "chong2").first().before("新复建叠逢申播启演组阳围写做来温合现拾返整修订庆"),
+    Rule::new('重', "chong2").last().after("双多层几"),
+    Rule::new('重', "chong2").alone().after("一两二三四五六七八九十几"),
+    Rule::new('重', "chong2").alone().pos(&["d"]),
+    Rule::new('重', "zhong4").alone().pos(&["a"]),
+    // 还: huán for "return" (还钱, 归还)
+    Rule::new('还', "huan2").first().before("给钱债款书账清击原价礼乡魂俗"),
+    Rule::new('还', "huan2").last().after("归偿退送交奉返生"),
+    Rule::new('还', "huan2").alone().before("给钱债账"),
+    Rule::new('还', "huan2").alone().after_pos(&["n"]).before("了"),
+    Rule::new('还', "huan2").alone().pos(&["v"]),
+    Rule::new('还', "hai2").alone().pos(&["d"]),
+    // 了: liǎo in potential complements (做不了) and "finish, understand"
+    Rule::new('了', "liao3").after("不"),
+    Rule::new('了', "liao3").first().before("解结却断如若望然"),
+    Rule::new('了', "le5").alone().pos(&["u"]),
+    // 得: děi for "must" (我得走了, 还得去), dé for "obtain", de as a particle
+    Rule::new('得', "de2").alone().after_pos(&["r", "n"]).before("了"),
+    Rule::new('得', "dei3").alone().after("还就也都总必非只可").before_pos(&["v", "d", "p"]),
+    Rule::new('得', "dei3").alone().after_pos(&["r"]).before_pos(&["v", "d", "p"]),
+    Rule::new('得', "de2").alone().pos(&["v"]),
+    Rule::new('得', "de5").alone().pos(&["u"]),
+    // 地: de as the adverbial particle (慢慢地)
+    Rule::new('地', "de5").alone().pos(&["u"]),
+    Rule::new('地', "di4").alone().pos(&["n"]),
+    // 着: zháo for "reach, catch" (睡不着, 着急), zhuó for "touch, apply"
+    Rule::new('着', "zhuo2").after("不").before("边"),
+    Rule::new('着', "zhao2").after("不"),
+    Rule::new('着', "zhao2").alone().after("找见猜够"),
+    Rule::new('着', "zhao2").first().before("急火凉迷慌"),
+    Rule::new('着', "zhuo2").first().before("陆地手装重力落眼想实色笔墨"),
+    Rule::new('着', "zhuo2").last().after("沉执附胶"),
+    // 为: wéi for "be, act as" (成为, 为人), wèi for "for"
+    Rule::new('为', "wei2").last().after("成作认以视变分称改定列选评化沦行较极广尤甚"),
+    Rule::new('为', "wei2").first().before("人难首期止伍"),
+    Rule::new('为', "wei2").alone().pos(&["v"]),
+    Rule::new('为', "wei4").alone().pos(&["p"]),
+    // 都: dū for "capital, city" (首都, 都市)
+    Rule::new('都', "du1").last().after("首古国建定迁陪旧故帝京"),
+    Rule::new('都', "du1").first().before("市城督"),
+    // 只: zhī as a measure word (一只猫)
+    Rule::new('只', "zhi1").alone().after(COUNTERS),
+    Rule::new('只', "zhi1").last().after(NUMERALS),
+    Rule::new('只', "zhi1").first().before("身言字影"),
+    Rule::new('只', "zhi1").alone().pos(&["q"]),
+    // 种: zhòng for "plant" (种树, 耕种)
+    Rule::new('种', "zhong4").first().before("植地田菜树花"),
+    Rule::new('种', "zhong4").last().after("耕栽接轮抢"),
+    Rule::new('种', "zhong4").alone().pos(&["v"]),
+    // 中: zhòng for "hit, be hit by" (中奖, 猜中)
+    Rule::new('中', "zhong4").first().before("奖毒弹暑标选计邪彩枪"),
+    Rule::new('中', "zhong4").last().after("考射猜命击选言"),
+    Rule::new('中', "zhong4").alone().before("了"),
+    Rule::new('中', "zhong4").alone().pos(&["v"]),
+    // 教: jiāo for "teach" as a verb (教我, 教书)
+    Rule::new('教', "jiao1").first().before("书课"),
+    Rule::new('教', "jiao1").alone().pos(&["v"]),
+    Rule::new('教', "jiao4").alone().pos(&["n"]),
+    // 数: shǔ for "count" (数一数, 数不清)
+    Rule::new('数', "shu3").first().before("落说一不清得数"),
+    Rule::new('数', "shu3").last().after("一"),
+    Rule::new('数', "shu3").alone().pos(&["v"]),
+    Rule::new('数', "shu4").alone().pos(&["n", "m"]),
+    // 乐: yuè for music (乐器, 音乐)
+    Rule::new('乐', "yue4").first().before("器曲队团谱章坛理师府手迷"),
+    Rule::new('乐', "yue4").last().after("音声器民礼弦管奏配"),
+    Rule::new('乐', "yue4").alone().pos(&["n"]),
+    Rule::new('乐', "le4").alone().pos(&["a"]),
+    // 空: kòng for "free time, vacant" (有空, 空白)
+    Rule::new('空', "kong4").first().before("闲隙缺白地格档余"),
+    Rule::new('空', "kong4").last().after("抽有没闲填留亏补"),
+    Rule::new('空', "kong4").alone().after("有没抽"),
+    Rule::new('空', "kong4").alone().pos(&["v"]),
+    // 处: chǔ for "handle, be situated" (处理, 相处)
+    Rule::new('处', "chu3").first().before("理置罚分决死境世事于在方女"),
+    Rule::new('处', "chu3").last().after("相共独杂"),
+    Rule::new('处', "chu3").alone().pos(&["v"]),
+    // 量: liáng for "measure" (量体温, 测量)
+    Rule::new('量', "liang2").first().before("体一杯尺具"),
+    Rule::new('量', "liang2").last().after("测丈估衡思商打掂较"),
+    Rule::new('量', "liang2").alone().before("一"),
+    Rule::new('量', "liang2").alone().pos(&["v"]),
+    // 传: zhuàn for biographies and classics (自传, 水浒传)
+    Rule::new('传', "zhuan4").first().before("记略"),
+    Rule::new('传', "zhuan4").last().after("自外经正列评别小史浒"),
+    // 藏: zàng for Tibet and treasure troves (藏族, 宝藏)
+    Rule::new('藏', "zang4").first().before("族语文医药獒区历民戏传青袍北南"),
+    Rule::new('藏', "zang4").last().after("西宝经道"),
+    // 朝: zhāo for "morning" (朝霞, 今朝)
+    Rule::new('朝', "zhao1").first().before("夕霞晖气露暮思三令"),
+    Rule::new('朝', "zhao1").last().after("今一终"),
+    // 假: jià for holidays (放假, 假期)
+    Rule::new('假', "jia4").first().before("期日条"),
+    Rule::new('假', "jia4").last().after("放请暑寒休度病年婚产事丧长春例"),
+    // 好: hào for "be fond of" (好奇, 爱好)
+    Rule::new('好', "hao4").first().before("奇客胜强战色"),
+    Rule::new('好', "hao4").last().after("爱喜嗜癖偏同"),
+    // 便: pián in 便宜
+    Rule::new('便', "pian2").first().before("宜"),
+    // 差: chāi for errands (出差), chā for "difference" (差别, 误差)
+    Rule::new('差', "chai1").first().before("事遣使役旅"),
+    Rule::new('差', "chai1").last().after("出邮钦交信"),
+    Rule::new('差', "cha1").first().before("别异距错价额"),
+    Rule::new('差', "cha1").last().after("误偏时温落反视色"),
+    Rule::new('差', "ci1").last().after("参"),
+    // 调: tiáo for "adjust, mix" (调整, 空调)
+    Rule::new('调', "tiao2").first().before("整节和解皮剂料味侃戏笑配控休养理停教试"),
+    Rule::new('调', "tiao2").last().after("空协失烹"),
+    // 当: dàng for "proper" and "treat as" (恰当, 当作)
+    Rule::new('当', "dang4").first().before("作做成真铺"),
+    Rule::new('当', "dang4").last().after("恰妥适稳上典得停勾"),
+    // 少: shào for "young" (少年, 少女)
+    Rule::new('少', "shao4").first().before("年女爷妇将校帅儿东林先"),
+    // 省: xǐng for "reflect, visit" (反省, 省亲)
+    Rule::new('省', "xing3").first().before("悟亲察视"),
+    Rule::new('省', "xing3").last().after("反自内"),
+    // 相: xiàng for "appearance, minister" (相貌, 首相)
+    Rule::new('相', "xiang4").first().before("貌片机册声扑"),
+    Rule::new('相', "xiang4").last().after("首丞宰真照亮面变长外卖吃"),
+    // 兴: xīng for "rise, prosper" (兴起, 复兴)
+    Rule::new('兴', "xing1").first().before("奋起建旺盛衰办修亡隆"),
+    Rule::new('兴', "xing1").last().after("复新振时"),
+    // 更: gēng for "change" and night watches (更新, 三更)
+    Rule::new('更', "geng1").first().before("新改换正衣替迭生"),
+    Rule::new('更', "geng1").last().after("变三五打"),
+    // 难: nàn for "disaster" (灾难, 难民)
+    Rule::new('难', "nan4").first().before("民友胞兄"),
+    Rule::new('难', "nan4").last().after("灾遇苦患落避逃空罹殉受发责刁"),
+    // 称: chèn for "fit, match" (称心, 对称)
+    Rule::new('称', "chen4").first().before("心职"),
+    Rule::new('称', "chen4").last().after("对相匀"),
+    // 将: jiàng for generals (将军, 大将)
+    Rule::new('将', "jiang4").first().before("领士帅官"),
+    Rule::new('将', "jiang4").last().after("大主名猛老武上少中良"),
+    // 应: yìng for "respond, apply" (应用, 反应)
+    Rule::new('应', "ying4").first().before("用对付邀聘急变酬战征考试届声运验"),
+    Rule::new('应', "ying4").last().after("反适回响效供对相呼照感接报顺"),
+    // 给: jǐ for "supply" (给予, 供给)
+    Rule::new('给', "ji3").first().before("予养"),
+    Rule::new('给', "ji3").last().after("供补配自"),
+    // 倒: dǎo for "fall" (摔倒, 倒闭), dào for "pour, reverse"
+    Rule::new('倒', "dao3").first().before("霉闭塌台卖班"),
+    Rule::new('倒', "dao3").last().pos(&["v"]),
+    Rule::new('倒', "dao3").alone().after_pos(&["v"]),
+    Rule::new('倒', "dao3").alone().before("在下"),
+    Rule::new('倒', "dao4").first().before("是车退影立水茶"),
+    // 发: fà for hair (头发, 白发)
+    Rule::new('发', "fa4").last().after("头理白黑金短长卷假毛秀银鬓须红"),
+    // 角: jué for roles and contests (主角, 角色)
+    Rule::new('角', "jue2").first().before("色逐斗"),
+    Rule::new('角', "jue2").last().after("主配名丑旦"),
+    // 率: shuài for "frank, lead" (坦率, 率领)
+    Rule::new('率', "shuai4").last().pos(&["a"]),
+    Rule::new('率', "shuai4").first().before("领先直真性"),
+    // 禁: jīn for "bear, endure" (禁不住, 不禁)
+    Rule::new('禁', "jin1").first().before("不住得起受"),
+    Rule::new('禁', "jin1").last().after("不"),
+    // 舍: shè for "house" (宿舍, 寒舍)
+    Rule::new('舍', "she4").last().after("宿校寒农旅茅"),
+    // 载: zǎi for "record, year" (记载, 连载)
+    Rule::new('载', "zai3").last().after("记转刊登连千"),
+    // 转: zhuàn for "revolve" (转动, 转悠)
+    Rule::new('转', "zhuan4").first().before("动圈速盘椅悠"),
+    // 供: gòng for "offer, confess" (供品, 口供)
+    Rule::new('供', "gong4").first().before("品奉词认桌职"),
+    Rule::new('供', "gong4").last().after("口招上"),
+    // 丧: sāng for mourning (丧事, 丧礼)
+    Rule::new('丧', "sang1").first().before("礼事葬服钟"),
+    // 缝: féng for "sew" (缝补, 缝衣服)
+    Rule::new('缝', "feng2").first().before("补合纫制"),
+    Rule::new('缝', "feng2").alone().pos(&["v"]),
+    // 薄: bó in literary words (单薄, 薄弱)
+    Rule::new('薄', "bo2").first().before("弱膜利命情"),
+    Rule::new('薄', "bo2").last().after("单刻稀淡浅微轻菲厚"),
+    // 剥, 削: bāo, xiāo for peeling as a plain verb
+    Rule::new('剥', "bao1").alone().pos(&["v"]),
+    Rule::new('削', "xiao1").alone().pos(&["v"]),
+    // 盛: chéng for "fill, ladle" (盛饭)
+    Rule::new('盛', "cheng2").first().before("饭汤水菜"),
+    Rule::new('盛', "cheng2").alone().pos(&["v"]),
+    // 仔: zǎi for "young man, young animal" (牛仔)
+    Rule::new('仔', "zai3").last().after("牛打猪"),
+    // 撒: sǎ for "sprinkle" (撒种, 撒盐)
+    Rule::new('撒', "sa3").first().before("种盐播落"),
+    // 哄: hǒng for "coax" (哄孩子)
+    Rule::new('哄', "hong3").alone().pos(&["v"]),
+    // 会: kuài in 会计
+    Rule::new('会', "kuai4").first().before("计"),
+    // 系: jì for "tie" (系鞋带)
+    Rule::new('系', "ji4").first().before("鞋带扣领好紧"),
+    Rule::new('系', "ji4").alone().before("好紧上"),
+    // 分: fèn for "component, share" (成分, 过分)
+    Rule::new('分', "fen4").last().after("成过充身水养本安"),
+    // 要: yāo for "demand, coerce" (要求, 要挟)
+    Rule::new('要', "yao1").first().before("求挟"),
+    // 觉: jiào for sleep (睡一觉)
+    Rule::new('觉', "jiao4").alone().after("一"),
+    // 说: shuì for "persuade" (说服, 游说)
+    Rule::new('说', "shui4").first().before("服"),
+    Rule::new('说', "shui4").last().after("游"),
+    // 塞: sè for "block" (堵塞), sài for frontier passes (边塞)
+    Rule::new('塞', "se4").last().after("闭堵阻搪"),
+    Rule::new('塞', "sai4").last().after("边要关"),
+    // 校: jiào for "check, proofread" (校对)
+    Rule::new('校', "jiao4").first().before("对正验准订"),
+    // 厦: xià in 厦门
+    Rule::new('厦', "xia4").first().before("门"),
+    // 炮: páo for "prepare herbs" (炮制)
+    Rule::new('炮', "pao2").first().before("制"),
+    // 扁: piān in 扁舟
+    Rule::new('扁', "pian1").first().before("舟"),
+    // 杆: gǎn for "shaft, stick" (枪杆, 笔杆)
+    Rule::new('杆', "gan3").last().after("枪笔秤烟杠"),
+    // 划: huá for "row, be worth it" (划船, 划算)
+    Rule::new('划', "hua2").first().before("船桨算"),
+    // 横: hèng for "unexpected, unruly" (横财, 蛮横)
+    Rule::new('横', "heng4").first().before("财祸暴"),
+    Rule::new('横', "heng4").last().after("蛮"),
+    // 夹: jiá for "lined" (夹袄)
+    Rule::new('夹', "jia2").first().before("克袄"),
+    // 间: jiàn for "gap, separate" (间隔, 离间)
+    Rule::new('间', "jian4").first().before("隔谍接断歇"),
+    Rule::new('间', "jian4").last().after("离反"),
+    // 卷: juàn for papers and scrolls (试卷, 画卷)
+    Rule::new('卷', "juan4").last().after("试考答画书"),
+    Rule::new('卷', "juan4").first().before("宗"),
+    // 累: lěi for "accumulate, implicate" (积累, 累计), léi in 累赘
+    Rule::new('累', "lei3").first().before("积计"),
+    Rule::new('累', "lei3").last().after("积连牵"),
+    Rule::new('累', "lei2").first().before("赘"),
+    // 抹: mā for "wipe" (抹布)
+    Rule::new('抹', "ma1").first().before("布"),
+    // 磨: mò for "mill" (磨坊)
+    Rule::new('磨', "mo4").first().before("坊盘"),
+    // 漂: piāo for "float" (漂流), piǎo for "bleach" (漂白)
+    Rule::new('漂', "piao1").first().before("浮流泊移"),
+    Rule::new('漂', "piao3").first().before("白洗"),
+    // 屏: bǐng for "hold, reject" (屏住, 屏弃)
+    Rule::new('屏', "bing3").first().before("住息弃除"),
+    // 铺: pū for "spread" (铺床, 铺路), pù for "shop, bed" (店铺)
+    Rule::new('铺', "pu1").first().before("张设垫盖路床天"),
+    Rule::new('铺', "pu1").alone().pos(&["v"]),
+    // 曝: pù in 曝晒
+    Rule::new('曝', "pu4").first().before("晒"),
+    // 悄: qiǎo for "quiet" (悄然)
+    Rule::new('悄', "qiao3").first().before("然声"),
+    // 翘: qiáo for "raise" (翘首)
+    Rule::new('翘', "qiao2").first().before("首楚"),
+    // 切: qiē for "cut" (切菜)
+    Rule::new('切', "qie1").first().before("菜开割断片除线"),
+    Rule::new('切', "qie1").alone().pos(&["v"]),
+    // 亲: qìng in 亲家
+    Rule::new('亲', "qing4").first().before("家"),
+    // 圈: juàn for pens (猪圈)
+    Rule::new('圈', "juan4").last().after("猪羊牛马"),
+    // 散: sǎn for "loose" (松散, 散文)
+    Rule::new('散', "san3").last().after("松零懒闲"),
+    Rule::new('散', "san3").first().before("文装漫光"),
+    // 刹: chà in 刹那
+    Rule::new('刹', "cha4").first().before("那"),
+    // 扇: shān for "fan" as a verb
+    Rule::new('扇', "shan1").alone().pos(&["v"]),
+    // 折: shé for "lose money" (折本), zhē in 折腾
+    Rule::new('折', "she2").first().before("本耗"),
+    Rule::new('折', "zhe1").first().before("腾"),
+    // 识: zhì in 标识
+    Rule::new('识', "zhi4").last().after("标"),
+    // 宿: xiǔ for nights (一宿)
+    Rule::new('宿', "xiu3").alone().after(NUMERALS),
+    // 提: dī in 提防
+    Rule::new('提', "di1").first().before("防"),
+    // 挑: tiǎo for "provoke, raise" (挑战, 挑起)
+    Rule::new('挑', "tiao3").first().before("战衅拨逗明起"),
+    // 帖: tiě for notes and posts (请帖, 帖子)
+    Rule::new('帖', "tie3").first().before("子"),
+    Rule::new('帖', "tie3").last().after("字碑请喜发跟回"),
+    // 吐: tù for "vomit" (呕吐, 吐血)
+    Rule::new('吐', "tu4").last().after("呕"),
+    Rule::new('吐', "tu4").first().before("血沫"),
+    // 鲜: xiǎn for "rare" (鲜见)
+    Rule::new('鲜', "xian3").first().before("为见有"),
+    // 巷: hàng in 巷道
+    Rule::new('巷', "hang4").first().before("道"),
+    // 吓: hè for "threaten" (恐吓)
+    Rule::new('吓', "he4").last().after("恐"),
+    // 旋: xuàn for "whirl" (旋风)
+    Rule::new('旋', "xuan4").first().before("风"),
+    // 咽: yān for the throat (咽喉), yè for "sob" (哽咽)
+    Rule::new('咽', "yan1").first().before("喉"),
+    Rule::new('咽', "ye4").last().after("哽"),
+    // 与: yù for "take part" (参与)
+    Rule::new('与', "yu4").first().before("会"),
+    Rule::new('与', "yu4").last().after("参"),
+    // 晕: yùn for motion sickness (晕车)
+    Rule::new('晕', "yun4").first().before("车船"),
+    // 扎: zā for "tie up" (扎辫子)
+    Rule::new('扎', "za1").first().before("辫"),
+    // 炸: zhá for "deep-fry" (炸鸡, 油炸)
+    Rule::new('炸', "zha2").first().before("鸡酱薯鱼丸糕"),
+    Rule::new('炸', "zha2").last().after("油"),
+    // 粘: nián for "sticky"
+    Rule::new('粘', "nian2").alone().pos(&["a"]),
+    // 涨: zhàng for "swell, flush" (涨红)
+    Rule::new('涨', "zhang4").first().before("红"),
+    // 挣: zhèng for "earn, break free" (挣钱, 挣脱)
+    Rule::new('挣', "zheng4").first().before("钱脱开"),
+    Rule::new('挣', "zheng4").alone().pos(&["v"]),
+    // 咖: gā in 咖喱
+    Rule::new('咖', "ga1").first().before("喱"),
+    // 干: gān for "dry" (很干)
+    Rule::new('干', "gan1").alone().pos(&["a"]),
+    Rule::new('干', "gan4").alone().pos(&["v"]),
+    // 度: duó for "estimate" (揣度)
+    Rule::new('度', "duo2").last().after("揣忖"),
+    // 待: dāi for "stay" (待一会)
+    Rule::new('待', "dai1").alone().before("会"),
+    // 曲: qǔ for songs (歌曲, 作曲)
+    Rule::new('曲', "qu3").last().after("歌作戏插序舞名乐组神小单金新"),
+    // 模: mú for moulds (模样, 模具)
+    Rule::new('模', "mu2").first().before("样具板子"),
+    // 尽: jǐn for "as far as possible" (尽管, 尽量)
+    Rule::new('尽', "jin3").first().before("管量快早可先"),
+    // 结: jiē in 结实, 结巴
+    Rule::new('结', "jie1").first().before("实巴"),    // 阿: ē in 阿胶, 阿谀, 阿弥陀佛
+    Rule::new('阿', "e1").first().before("胶谀附弥"),
+    // 挨: ái for "suffer, endure" (挨打, 挨饿)
+    Rule::new('挨', "ai2").first().before("打骂饿冻揍批整宰训"),
+    Rule::new('挨', "ai2").alone().before("打骂饿冻揍批整宰训"),
+    // 拗: niù for "stubborn" (执拗, 拗不过)
+    Rule::new('拗', "niu4").last().after("执"),
+    Rule::new('拗', "niu4").alone().before("不"),
+    // 扒: pá for "pick, braise" (扒手, 扒鸡)
+    Rule::new('扒', "pa2").first().before("手窃鸡犁"),
+    // 把: bà for handles (刀把, 枪把)
+    Rule::new('把', "ba4").last().after("刀印枪车门锅壶伞"),
+    // 膀, 磅: páng in 膀胱, 磅礴
+    Rule::new('膀', "pang2").before("胱"),
+    Rule::new('磅', "pang2").before("礴"),
+    // 蚌: bèng in 蚌埠
+    Rule::new('蚌', "beng4").before("埠"),
+    // 堡: pù in place names (十里堡)
+    Rule::new('堡', "pu4").last().after("里"),
+    // 背: bēi for "carry on the back" (背包, 背着)
+    Rule::new('背', "bei1").first().before("包负债篓带起黑着"),
+    Rule::new('背', "bei1").alone().before("着起上走回"),
+    // 奔: bèn for "head for" (投奔, 奔头, 奔北京)
+    Rule::new('奔', "ben4").last().after("投直"),
+    Rule::new('奔', "ben4").first().before("头向命"),
+    Rule::new('奔', "ben4").alone().before_pos(&["ns", "s", "f"]),
+    // 臂: bei in 胳臂
+    Rule::new('臂', "bei5").last().after("胳"),
+    // 辟: bì for "ward off" and in 复辟
+    Rule::new('辟', "bi4").first().before("邪谷"),
+    Rule::new('辟', "bi4").last().after("复"),
+    // 柏: bó in 柏林
+    Rule::new('柏', "bo2").first().before("林"),
+    // 参: shēn for ginseng (人参, 海参), cēn in 参差
+    Rule::new('参', "shen1").last().after("人海党丹沙"),
+    Rule::new('参', "cen1").first().before("差"),
+    // 曾: zēng for great-grandchildren and as a surname (曾孙, 曾国藩)
+    Rule::new('曾', "zeng1").first().before("孙祖"),
+    Rule::new('曾', "zeng1").pos(&["nr"]),
+    // 叉: chǎ for "spread apart" (叉开, 叉腿)
+    Rule::new('叉', "cha3").first().before("开腿"),
+    // 查: zhā as a surname (查良镛)
+    Rule::new('查', "zha1").first().pos(&["nr"]),
+    // 颤: zhàn for shivering (颤栗, 打颤)
+    Rule::new('颤', "zhan4").first().before("栗"),
+    Rule::new('颤', "zhan4").last().after("打"),
+    // 场: cháng for threshing floors (场院)
+    Rule::new('场', "chang2").first().before("院"),
+    // 乘: shèng for chariots and annals (千乘, 史乘)
+    Rule::new('乘', "sheng4").last().after("千万史"),
+    // 匙: chí for spoons (汤匙, 茶匙); shi in 钥匙 is the default
+    Rule::new('匙', "chi2").last().after("汤茶羹调"),
+    Rule::new('匙', "chi2").alone(),
+    // 冲: chòng for "facing, forceful" (冲着, 冲劲)
+    Rule::new('冲', "chong4").first().before("劲床压"),
+    Rule::new('冲', "chong4").alone().before("着我你他她它门"),
+    // 臭: xiù for smell (乳臭, 铜臭)
+    Rule::new('臭', "xiu4").last().after("乳铜无"),
+    // 畜: xù for raising livestock (畜牧, 畜养)
+    Rule::new('畜', "xu4").first().before("牧养产"),
+    // 揣: chuǎi for "guess" (揣测, 揣摩)
+    Rule::new('揣', "chuai3").first().before("测摩度想"),
+    // 创: chuāng for wounds (创伤, 重创)
+    Rule::new('创', "chuang1").first().before("伤口痕面"),
+    Rule::new('创', "chuang1").last().after("重刀"),
+    // 伺: sì for "watch for" (伺机, 窥伺); cì in 伺候 is the default
+    Rule::new('伺', "si4").first().before("机服"),
+    Rule::new('伺', "si4").last().after("窥环"),
+    // 攒: cuán for "gather" (攒动, 人头攒动)
+    Rule::new('攒', "cuan2").first().before("动聚集"),
+    Rule::new('攒', "cuan2").after("头").before("动"),
+    // 撮: zuǒ as a measure word for hair (一撮毛)
+    Rule::new('撮', "zuo3").alone().after(COUNTERS).before("毛胡头"),
+    // 答: dā in 答应, 答理
+    Rule::new('答', "da1").first().before("应理腔碴"),
+    // 大: dài in 大夫 "doctor"
+    Rule::new('大', "dai4").first().before("夫"),
+    // 逮: dài in 逮捕
+    Rule::new('逮', "dai4").first().before("捕"),
+    // 单: chán in 单于, shàn as a surname
+    Rule::new('单', "chan2").first().before("于"),
+    Rule::new('单', "shan4").first().pos(&["nr"]),
+    // 担: dàn for loads (担子, 重担, 一担水)
+    Rule::new('担', "dan4").first().before("子"),
+    Rule::new('担', "dan4").last().after("重扁挑货"),
+    Rule::new('担', "dan4").alone().after(COUNTERS),
+    // 弹: tán for "play, spring" (弹琴, 弹性)
+    Rule::new('弹', "tan2").first().before("琴奏唱跳簧性力指"),
+    Rule::new('弹', "tan2").alone().pos(&["v"]),
+    // 的: dí in 的确, 的士; dì for "target" (目的, 标的)
+    Rule::new('的', "di2").first().before("确士"),
+    Rule::new('的', "di4").last().after("目标"),
+    // 钉: dìng for "nail, sew on" (钉扣子)
+    Rule::new('钉', "ding4").first().before("扣住上在"),
+    Rule::new('钉', "ding4").alone().pos(&["v"]),
+    // 斗: dǒu for dippers and measures (北斗, 漏斗, 一斗米)
+    Rule::new('斗', "dou3").after("北漏烟熨星泰车"),
+    Rule::new('斗', "dou3").first().before("笠篷胆室"),
+    Rule::new('斗', "dou3").alone().after(NUMERALS),
+    // 囤: tún for "hoard" (囤积, 囤货)
+    Rule::new('囤', "tun2").first().before("积货聚"),
+    // 恶: wù for "hate" (可恶, 厌恶), ě in 恶心
+    Rule::new('恶', "wu4").last().after("可厌憎好"),
+    Rule::new('恶', "e3").first().before("心"),
+    // 坊: fáng for workshops (磨坊, 作坊)
+    Rule::new('坊', "fang2").last().after("磨作染油粉酒"),
+    // 佛: fú in 仿佛
+    Rule::new('佛', "fu2").last().after("仿"),
+    // 服: fù as a measure word for medicine (一服药)
+    Rule::new('服', "fu4").alone().after(NUMERALS).before("药"),
+    // 脯: fǔ for dried meat and fruit (果脯, 肉脯)
+    Rule::new('脯', "fu3").last().after("果肉杏桃"),
+    // 勾: gòu in 勾当
+    Rule::new('勾', "gou4").first().before("当"),
+    // 估: gù in 估衣
+    Rule::new('估', "gu4").first().before("衣"),
+    // 骨: gū in 骨朵, 骨碌
+    Rule::new('骨', "gu1").first().before("朵碌"),
+    // 观: guàn for Taoist temples (道观)
+    Rule::new('观', "guan4").last().after("道"),
+    // 冠: guàn for "first place" (冠军, 夺冠)
+    Rule::new('冠', "guan4").first().before("军名"),
+    Rule::new('冠', "guan4").last().after("夺卫登"),
+    // 号: háo for "howl" (号叫, 呼号)
+    Rule::new('号', "hao2").first().before("叫啕哭"),
+    Rule::new('号', "hao2").last().after("呼哀怒"),
+    // 喝: hè for "shout" (喝彩, 棒喝), he in 吆喝
+    Rule::new('喝', "he4").first().before("彩令斥问"),
+    Rule::new('喝', "he4").last().after("大棒"),
+    Rule::new('喝', "he5").last().after("吆"),
+    // 和: hè for "echo" (附和), huó in 和面, huo in 暖和
+    Rule::new('和', "he4").last().after("附唱应"),
+    Rule::new('和', "huo2").first().before("面泥"),
+    Rule::new('和', "huo5").last().after("暖软热"),
+    // 荷: hè for "carry, load" (负荷, 荷枪)
+    Rule::new('荷', "he4").first().before("枪载重"),
+    Rule::new('荷', "he4").last().after("负电"),
+    // 糊: hù in 糊弄, hū for "paste over" (糊墙)
+    Rule::new('糊', "hu4").first().before("弄"),
+    Rule::new('糊', "hu1").first().before("墙"),
+    // 哗: huá for uproar (哗然, 喧哗)
+    Rule::new('哗', "hua2").first().before("然众变"),
+    Rule::new('哗', "hua2").last().after("喧"),
+    // 晃: huàng for swaying (摇晃, 晃动)
+    Rule::new('晃', "huang4").last().after("摇"),
+    Rule::new('晃', "huang4").first().before("动荡悠"),
+    Rule::new('晃', "huang4").alone().before("来去着"),
+    // 混: hún in 混蛋
+    Rule::new('混', "hun2").first().before("蛋"),
+    // 豁: huò for "open, exempt" (豁达, 豁免)
+    Rule::new('豁', "huo4").first().before("达然免亮"),
+    // 几: jī in 几乎 and for small tables (茶几)
+    Rule::new('几', "ji1").first().before("乎"),
+    Rule::new('几', "ji1").last().after("茶"),
+    // 济: jǐ in 济南, 济济
+    Rule::new('济', "ji3").first().before("南济"),
+    // 监: jiàn in 太监, 国子监
+    Rule::new('监', "jian4").last().after("太子"),
+    // 解: jiè for "escort" (押解, 解送)
+    Rule::new('解', "jie4").last().after("押起"),
+    Rule::new('解', "jie4").first().before("送元"),
+    // 劲: jìng for "strong" (劲敌, 强劲)
+    Rule::new('劲', "jing4").first().before("敌旅松草爆"),
+    Rule::new('劲', "jing4").last().after("强刚苍遒雄"),
+    // 颈: gěng in 脖颈
+    Rule::new('颈', "geng3").last().after("脖"),
+    // 卡: qiǎ for checkpoints and "get stuck" (关卡, 卡住)
+    Rule::new('卡', "qia3").last().after("关哨"),
+    Rule::new('卡', "qia3").first().before("住壳脖"),
+    Rule::new('卡', "qia3").alone().before("住在"),
+    // 看: kān for "look after" (看守, 看门)
+    Rule::new('看', "kan1").first().before("守门护管家押"),
+    // 壳: qiào in 地壳, 甲壳
+    Rule::new('壳', "qiao4").last().after("地甲躯"),
+    // 可: kè in 可汗
+    Rule::new('可', "ke4").first().before("汗"),
+    // 勒: lè for "rein in" (勒令, 勒索, 弥勒)
+    Rule::new('勒', "le4").first().before("令索"),
+    Rule::new('勒', "le4").last().after("弥悬"),
+    // 俩: liǎng in 伎俩
+    Rule::new('俩', "liang3").last().after("伎"),
+    // 令: líng in 令狐
+    Rule::new('令', "ling2").first().before("狐"),
+    // 六: lù in 六安
+    Rule::new('六', "lu4").first().before("安"),
+    // 笼: lǒng for "cover" (笼罩, 笼统)
+    Rule::new('笼', "long3").first().before("罩统络"),
+    // 露: lòu for "show" (露面, 露一手)
+    Rule::new('露', "lou4").first().before("面脸馅怯头富底"),
+    Rule::new('露', "lou4").alone().before("一"),
+    // 绿: lù in 绿林, 鸭绿江
+    Rule::new('绿', "lu4").first().before("林"),
+    Rule::new('绿', "lu4").last().after("鸭"),
+    // 论: lún in 论语
+    Rule::new('论', "lun2").first().before("语"),
+    // 捋: luō for "roll up" (捋袖子)
+    Rule::new('捋', "luo1").first().before("袖"),
+    // 埋: mán in 埋怨
+    Rule::new('埋', "man2").first().before("怨"),
+    // 蔓: wàn for vines (瓜蔓)
+    Rule::new('蔓', "wan4").last().after("瓜藤"),
+    // 没: mò for "sink, confiscate" (淹没, 没收)
+    Rule::new('没', "mo4").first().before("收落顶"),
+    Rule::new('没', "mo4").last().after("淹埋沉出隐覆吞泯湮"),
+    // 闷: mēn for "stuffy, silent" (闷热, 闷头)
+    Rule::new('闷', "men1").first().before("热声头气"),
+    // 蒙: měng in 蒙古, mēng for "cheat, guess" (蒙骗, 瞎蒙)
+    Rule::new('蒙', "meng3").first().before("古"),
+    Rule::new('蒙', "meng1").first().before("骗"),
+    Rule::new('蒙', "meng1").last().after("瞎"),
+    // 秘: bì in 秘鲁
+    Rule::new('秘', "bi4").first().before("鲁"),
+    // 宁: nìng for "would rather" (宁可, 宁愿)
+    Rule::new('宁', "ning4").first().before("可愿肯死"),
+    Rule::new('宁', "ning4").alone().before("可愿肯死"),
+    // 拧: nǐng for "twist" (拧开, 拧紧)
+    Rule::new('拧', "ning3").first().before("开紧"),
+    Rule::new('拧', "ning3").alone().before("开紧"),
+    // 弄: lòng for lanes (弄堂, 里弄)
+    Rule::new('弄', "long4").first().before("堂"),
+    Rule::new('弄', "long4").last().after("里"),
+    // 疟: yào in 疟子
+    Rule::new('疟', "yao4").first().before("子"),
+    // 喷: pèn in 喷香
+    Rule::new('喷', "pen4").first().before("香"),
+    // 片: piān in 片子 "film"
+    Rule::new('片', "pian1").first().before("子"),
+    // 撇: piě for "curl, stroke" (撇嘴, 一撇)
+    Rule::new('撇', "pie3").first().before("嘴"),
+    Rule::new('撇', "pie3").alone().after(NUMERALS),
+    // 朴: pō in 朴刀, pò in 厚朴, piáo as a surname
+    Rule::new('朴', "po1").first().before("刀"),
+    Rule::new('朴', "po4").last().after("厚"),
+    Rule::new('朴', "piao2").pos(&["nr"]),
+    // 奇: jī for odd numbers (奇数)
+    Rule::new('奇', "ji1").first().before("数偶"),
+    // 茄: jiā in 雪茄, 茄克
+    Rule::new('茄', "jia1").last().after("雪"),
+    Rule::new('茄', "jia1").first().before("克"),
+    // 强: qiǎng for "force" (勉强, 强迫), jiàng in 倔强
+    Rule::new('强', "qiang3").last().after("勉牵"),
+    Rule::new('强', "qiang3").first().before("迫求词辩人颜"),
+    Rule::new('强', "jiang4").last().after("倔"),
+    // 任: rén as a surname
+    Rule::new('任', "ren2").first().pos(&["nr"]),
+    // 色: shǎi for fading and dice (掉色, 色子)
+    Rule::new('色', "shai3").last().after("掉褪"),
+    Rule::new('色', "shai3").first().before("子"),
+    // 煞: shà for "very, evil spirit" (煞白, 凶煞)
+    Rule::new('煞', "sha4").first().before("白费气有"),
+    Rule::new('煞', "sha4").last().after("凶"),
+    // 杉: shā in 杉木
+    Rule::new('杉', "sha1").first().before("木篙"),
+    // 上: shǎng in 上声
+    Rule::new('上', "shang3").first().before("声"),
+    // 什: shí in 什锦, 什物
+    Rule::new('什', "shi2").first().before("锦物"),
+    // 甚: shén in 甚么
+    Rule::new('甚', "shen2").first().before("么"),
+    // 似: shì in 似的
+    Rule::new('似', "shi4").before("的"),
+    // 属: zhǔ for "fix one's mind on" (属意, 属望)
+    Rule::new('属', "zhu3").first().before("意望目"),
+    // 刷: shuà in 刷白
+    Rule::new('刷', "shua4").first().before("白"),
+    // 沓: dá as a measure word for paper (一沓纸)
+    Rule::new('沓', "da2").alone().after(COUNTERS),
+    Rule::new('沓', "da2").alone().pos(&["q"]),
+    // 苔: tāi in 舌苔
+    Rule::new('苔', "tai1").last().after("舌"),
+    // 趟: tāng for "wade" (趟水)
+    Rule::new('趟', "tang1").first().before("水地"),
+    // 同: tòng in 胡同
+    Rule::new('同', "tong4").last().after("胡"),
+    // 拓: tà for rubbings (拓片, 拓本)
+    Rule::new('拓', "ta4").first().before("片本印"),
+    // 瓦: wà in 瓦刀
+    Rule::new('瓦', "wa4").first().before("刀"),
+    // 纤: qiàn for tow ropes (纤夫, 拉纤)
+    Rule::new('纤', "qian4").first().before("夫绳"),
+    Rule::new('纤', "qian4").last().after("拉"),
+    // 血: xiě in 血淋淋
+    Rule::new('血', "xie3").first().before("淋"),
+    // 压: yà in 压根儿
+    Rule::new('压', "ya4").first().before("根"),
+    // 燕: yān for the old state and Beijing (燕京, 燕山)
+    Rule::new('燕', "yan1").first().before("京山赵"),
+    // 钥: yuè in 锁钥
+    Rule::new('钥', "yue4").last().after("锁"),
+    // 叶: xié in 叶韵
+    Rule::new('叶', "xie2").first().before("韵"),
+    // 佣: yòng for commission (佣金)
+    Rule::new('佣', "yong4").first().before("金钱"),
+    // 熨: yù in 熨帖
+    Rule::new('熨', "yu4").first().before("帖"),
+    // 择: zhái for "pick over" (择菜)
+    Rule::new('择', "zhai2").first().before("菜席"),
+    Rule::new('择', "zhai2").alone().before("菜"),
+    // 轧: zhá for rolling steel (轧钢)
+    Rule::new('轧', "zha2").first().before("钢机辊材"),
+    // 占: zhān for divination (占卜, 占星)
+    Rule::new('占', "zhan1").first().before("卜卦星梦"),
+    // 正: zhēng in 正月
+    Rule::new('正', "zheng1").first().before("月"),
+    // 症: zhēng in 症结
+    Rule::new('症', "zheng1").first().before("结"),
+    // 殖: shi in 骨殖
+    Rule::new('殖', "shi5").last().after("骨"),
+    // 轴: zhòu in 压轴
+    Rule::new('轴', "zhou4").after("压"),
+    // 幢: chuáng for banners (经幢)
+    Rule::new('幢', "chuang2").last().after("经石"),
+    // 钻: zuàn for drills and diamonds (钻石, 电钻)
+    Rule::new('钻', "zuan4").first().before("石头戒"),
+    Rule::new('钻', "zuan4").last().after("电风手台金"),
+    // 作: zuō in 作坊
+    Rule::new('作', "zuo1").first().before("坊"),
+    // 打: dá for dozens (一打鸡蛋)
+    Rule::new('打', "da2").last().after(NUMERALS).pos(&["m", "q"]),
+    Rule::new('打', "da2").alone().after(NUMERALS).before_pos(&["n"]),
+    // 泡: pāo for puffy things (眼泡, 泡桐)
+    Rule::new('泡', "pao1").last().after("眼豆"),
+    Rule::new('泡', "pao1").first().before("桐"),
+    // 迫: pǎi in 迫击炮
+    Rule::new('迫', "pai3").first().before("击"),
+    // 若: rě in 般若
+    Rule::new('若', "re3").last().after("般"),
+    // 莎: suō in 莎草
+    Rule::new('莎', "suo1").first().before("草"),
+    // 踏: tā in 踏实
+    Rule::new('踏', "ta1").first().before("实"),
+    // 体: tī in 体己
+    Rule::new('体', "ti1").first().before("己"),
+    // 殷: yān for dark red (殷红)
+    Rule::new('殷', "yan1").first().before("红"),
+    // 饮: yìn for watering animals (饮马)
+    Rule::new('饮', "yin4").first().before("马牛"),
+    Rule::new('饮', "yin4").alone().before("马牛"),
+    // 脏: zàng for organs (心脏, 内脏); zāng "dirty" is the default
+    Rule::new('脏', "zang4").last().after("心内肝肾脾肺五"),
+    Rule::new('脏', "zang4").first().before("器腑"),
+    // 吗: mǎ in 吗啡, má in 干吗
+    Rule::new('吗', "ma3").first().before("啡"),
+    Rule::new('吗', "ma2").last().after("干"),
+    // 呢: ní for woollen cloth (呢子, 毛呢)
+    Rule::new('呢', "ni2").first().before("子绒料"),
+    Rule::new('呢', "ni2").last().after("毛花"),
+    // 吁: yù for "appeal" (呼吁)
+    Rule::new('吁', "yu4").last().after("呼"),
+    // 龟: jūn in 龟裂, qiū in 龟兹
+    Rule::new('龟', "jun1").first().before("裂"),
+    Rule::new('龟', "qiu1").first().before("兹"),
+    // 稽: qǐ in 稽首
+    Rule::new('稽', "qi3").first().before("首"),
+    // 栅: shān in 栅极
+    Rule::new('栅', "shan1").first().before("极"),
+    // 扫: sào in 扫帚, 扫把
+    Rule::new('扫', "sao4").first().before("帚把"),
+    // 咯: kǎ in 咯血, lo as a sentence particle
+    Rule::new('咯', "ka3").first().before("血"),
+    Rule::new('咯', "lo5").alone().pos(&["y"]),
+    // 禅: shàn in 禅让
+    Rule::new('禅', "shan4").first().before("让"),
+    // 咋: zhā in 咋呼
+    Rule::new('咋', "zha1").first().before("呼"),
+    // 吭: háng in 引吭高歌
+    Rule::new('吭', "hang2").after("引"),
+    // 贾: gǔ for merchants (商贾)
+    Rule::new('贾', "gu3").last().after("商书"),
+    // 否: pǐ in 否极泰来
+    Rule::new('否', "pi3").first().before("极"),
+    // 蕃: bō in 吐蕃
+    Rule::new('蕃', "bo1").last().after("吐"),
+];
+
+/// Context rules grouped by character, in table order
+static RULES_BY_CHAR: Lazy<HashMap<char, Vec<&'static Rule>>> = Lazy::new(|| {
+    let mut m: HashMap<char, Vec<&'static Rule>> = HashMap::new();
+    for rule in RULES {
+        m.entry(rule.hanzi).or_default().push(rule);
+    }
     m
 });
 
-/// Default pinyin for characters (most common reading)
+/// A word's surroundings in the sentence, read by the context rules
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Context<'a> {
+    /// POS tag of the word itself
+    pub pos: &'a str,
+    /// Last character of the previous word
+    pub prev_char: Option<char>,
+    /// POS tag of the previous word
+    pub prev_pos: &'a str,
+    /// First character of the next word
+    pub next_char: Option<char>,
+    /// POS tag of the next word
+    pub next_pos: &'a str,
+}
+
+impl<'a> Context<'a> {
+    /// A word with the given POS tag and nothing around it
+    pub fn new(pos: &'a str) -> Self {
+        Self { pos, ..Default::default() }
+    }
+}
+
+/// Default pinyin for polyphones (most common reading), taking precedence
+/// over the full character table in `pinyin`
 static DEFAULT_PINYIN: phf::Map<char, &'static str> = phf_map! {
     '行' => "xing2",
     '了' => "le5",
@@ -306,6 +907,23 @@ static DEFAULT_PINYIN: phf::Map<char, &'static str> = phf_map! {
     '好' => "hao3",
     '中' => "zhong1",
     '没' => "mei2",
+    '佛' => "fo2",
+    '泊' => "bo2",
+    '嚼' => "jiao2",
+    '卜' => "bu3",
+    '曝' => "bao4",
+    '似' => "si4",
+    '尽' => "jin4",
+    '绩' => "ji4",
+    '著' => "zhu4",
+    '罗' => "luo2",
+    '拗' => "ao4",
+    '茄' => "qie2",
+    '拓' => "tuo4",
+    '甚' => "shen4",
+    '幢' => "zhuang4",
+    '沓' => "ta4",
+    '脏' => "zang1",
 };
 
 /// Look up a phrase in the polyphone dictionary
@@ -313,22 +931,18 @@ pub fn lookup_phrase(phrase: &str) -> Option<&'static str> {
     PHRASE_PINYIN.get(phrase).copied()
 }
 
-/// Look up a character's pinyin based on POS tag
-pub fn lookup_with_pos(c: char, pos: &str) -> Option<&'static str> {
-    // Try exact POS tag first
-    if let Some(&pinyin) = POS_BASED_PINYIN.get(&(c, pos)) {
-        return Some(pinyin);
-    }
-
-    // Try POS tag prefix (first character)
-    if !pos.is_empty() {
-        let pos_prefix = &pos[..1];
-        if let Some(&pinyin) = POS_BASED_PINYIN.get(&(c, pos_prefix)) {
-            return Some(pinyin);
-        }
-    }
+/// Pick the reading of `word[index]` from the context rules
+pub fn lookup_in_context(word: &[char], index: usize, context: &Context) -> Option<&'static str> {
+    RULES_BY_CHAR
+        .get(&word[index])?
+        .iter()
+        .find(|rule| rule.matches(word, index, context))
+        .map(|rule| rule.pinyin)
+}
 
-    None
+/// Look up the pinyin of a single-character word based on its POS tag
+pub fn lookup_with_pos(c: char, pos: &str) -> Option<&'static str> {
+    lookup_in_context(&[c], 0, &Context::new(pos))
 }
 
 /// Get the default pinyin for a character
@@ -344,6 +958,8 @@ mod tests {
     fn test_phrase_lookup() {
         assert_eq!(lookup_phrase("银行"), Some("yin2 hang2"));
         assert_eq!(lookup_phrase("行走"), Some("xing2 zou3"));
+        assert_eq!(lookup_phrase("董事长"), Some("dong3 shi4 zhang3"));
+        assert_eq!(lookup_phrase("头发"), Some("tou2 fa5"));
         assert_eq!(lookup_phrase("不存在"), None);
     }
 
@@ -355,6 +971,38 @@ mod tests {
         assert_eq!(lookup_with_pos('行', "n"), Some("hang2"));
     }
 
+    #[test]
+    fn test_context_rules() {
+        let measure = Context {
+            prev_char: Some('两'),
+            ..Context::new("q")
+        };
+        assert_eq!(lookup_in_context(&['只'], 0, &measure), Some("zhi1"));
+        assert_eq!(lookup_in_context(&['只'], 0, &Context::new("d")), None);
+
+        // 得 as "must" between a pronoun and a verb
+        let must = Context {
+            prev_char: Some('我'),
+            prev_pos: "r",
+            next_char: Some('走'),
+            next_pos: "v",
+            ..Context::new("ud")
+        };
+        assert_eq!(lookup_in_context(&['得'], 0, &must), Some("dei3"));
+
+        // Place in the word: 长 heads an organisation only at the end
+        let word: Vec<char> = "镇长".chars().collect();
+        assert_eq!(lookup_in_context(&word, 1, &Context::new("n")), Some("zhang3"));
+        let word: Vec<char> = "睡不着".chars().collect();
+        assert_eq!(lookup_in_context(&word, 2, &Context::new("v")), Some("zhao2"));
+
+        // Organs take zàng; "dirty" is the default
+        let word: Vec<char> = "心脏".chars().collect();
+        assert_eq!(lookup_in_context(&word, 1, &Context::new("n")), Some("zang4"));
+        assert_eq!(lookup_in_context(&['脏'], 0, &Context::new("a")), None);
+        assert_eq!(get_default_pinyin('脏'), Some("zang1"));
+    }
+
     #[test]
     fn test_default_pinyin() {
         assert_eq!(get_default_pinyin('行'), Some("xing2"));
@@ -41,6 +41,22 @@ impl Segmenter {
             .collect()
     }
 
+    /// Split a compound word in two at the shortest sub-word jieba's search
+    /// mode finds in it, returning the number of characters before the split
+    /// (e.g. 1 for 纸|老虎, 2 for 蒙古|包)
+    pub fn split_compound(word: &str) -> usize {
+        let length = |part: &str| part.chars().count();
+        let parts = JIEBA.cut_for_search(word, false);
+        let Some(shortest) = parts.into_iter().reduce(|a, b| if length(b) < length(a) { b } else { a }) else {
+            return 0;
+        };
+        if word.starts_with(shortest) {
+            length(shortest)
+        } else {
+            length(word) - length(shortest)
+        }
+    }
+
     /// Check if a character is a Chinese character (CJK Unified Ideograph)
     #[inline]
     pub fn is_chinese_char(c: char) -> bool {
@@ -91,6 +107,12 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_split_compound() {
+        assert_eq!(Segmenter::split_compound("纸老虎"), 1);
+        assert_eq!(Segmenter::split_compound("蒙古包"), 2);
+    }
+
     #[test]
     fn test_is_chinese_char() {
         assert!(Segmenter::is_chinese_char('中'));
@@ -5,12 +5,336 @@
 //! 2. 一 (yī) tone changes:
 //!    - Before 4th tone: yī → yí (1→2)
 //!    - Before 1st/2nd/3rd tone: yī → yì (1→4)
+//!    - Unchanged as an ordinal (第一) or in digit sequences (一九九八)
 //! 3. 不 (bù) tone change:
 //!    - Before 4th tone: bù → bú (4→2)
+//! 4. Neutral tone for reduplicated words (看看) and for suffixes and
+//!    particles (们, 子, 的, 了, 吧, ...)
+//!
+//! [`apply_word_sandhi`] works on jieba segments: it first merges them into
+//! prosodic units (不 and 一 with the following word, reduplications, short
+//! runs of third-tone words), then applies the rules within each unit, so
+//! third tones no longer chain across unit boundaries. The rules follow the
+//! tone sandhi step of PaddleSpeech's frontend, as used by g2pW and misaki.
 
 use super::pinyin::PinyinSyllable;
+use super::segmenter::Segmenter;
+
+/// A segmented word, or a prosodic unit of merged words
+#[derive(Debug, Clone, PartialEq)]
+pub struct Word {
+    /// The text of the word
+    pub text: String,
+    /// jieba POS tag (of the first word, for merged units)
+    pub pos: String,
+    /// One syllable per character read
+    pub syllables: Vec<PinyinSyllable>,
+}
+
+impl Word {
+    pub fn new(text: &str, pos: &str, syllables: Vec<PinyinSyllable>) -> Self {
+        Self {
+            text: text.to_string(),
+            pos: pos.to_string(),
+            syllables,
+        }
+    }
+
+    /// Append the following word to this one
+    fn merge(&mut self, next: Word) {
+        self.text.push_str(&next.text);
+        self.syllables.extend(next.syllables);
+    }
+
+    /// Whether the word is a single character repeated (e.g. 看看)
+    fn is_reduplication(&self) -> bool {
+        let mut chars = self.text.chars();
+        matches!((chars.next(), chars.next(), chars.next()), (Some(a), Some(b), None) if a == b)
+    }
+}
+
+/// Words that look reduplicated or suffixed but keep their full tones
+const KEEP_TONE_WORDS: &[&str] = &[
+    "男子", "女子", "分子", "原子", "量子", "莲子", "石子", "瓜子", "电子", "学子", "卵子", "算子",
+    "人人", "虎虎", "哈哈", "数数", "想想", "考考", "整整", "死死", "冉冉", "恳恳", "佼佼", "吵吵",
+    "打打", "熙熙", "攘攘", "袅袅", "莘莘", "青青", "花花草草", "家家户户",
+];
+
+/// Sentence-final particles read with the neutral tone
+const MODAL_PARTICLES: &str = "吧呢啊呐噻嘛吖嗨哦哒额滴哩哟喽啰耶喔诶";
+
+/// Digits read one by one in numbers such as years; 一 next to one keeps tone 1
+const SERIAL_DIGITS: &str = "零〇一二三四五六七八九";
+
+/// Characters before 个 that make it a neutral-tone measure word
+const MEASURE_PRECEDERS: &str = "零〇一二三四五六七八九十百千万亿两几有半多各整每做是";
+
+/// Apply tone sandhi to the segmented words of one phrase, returning the
+/// prosodic units they were grouped into
+pub fn apply_word_sandhi(words: Vec<Word>) -> Vec<Word> {
+    let units = merge_bu(words);
+    let units = merge_yi(units);
+    let units = merge_reduplication(units);
+    // Merge runs of third-tone words so that 3-3 sandhi applies across them
+    let units = merge_third_tones(units, |prev, next| all_tone_three(prev) && all_tone_three(next));
+    let mut units = merge_third_tones(units, |prev, next| {
+        prev.last().is_some_and(|s| s.tone == 3) && next.first().is_some_and(|s| s.tone == 3)
+    });
+
+    for unit in &mut units {
+        apply_bu_word(unit);
+        apply_yi_word(unit);
+        apply_neutral_tone(unit);
+        apply_third_tone_word(unit);
+    }
+    units
+}
+
+/// Merge 不 into the word that follows it
+fn merge_bu(words: Vec<Word>) -> Vec<Word> {
+    let mut result = Vec::with_capacity(words.len());
+    let mut bu: Option<Word> = None;
+    for word in words {
+        let word = match bu.take() {
+            Some(mut merged) => {
+                merged.pos = word.pos.clone();
+                merged.merge(word);
+                merged
+            }
+            None => word,
+        };
+        if word.text == "不" {
+            bu = Some(word);
+        } else {
+            result.push(word);
+        }
+    }
+    result.extend(bu);
+    result
+}
+
+/// Merge verb reduplications with 一 (看 一 看) and 一 into the word that
+/// follows it, keeping ordinals (第 一) and digit sequences apart
+fn merge_yi(words: Vec<Word>) -> Vec<Word> {
+    let mut result: Vec<Word> = Vec::with_capacity(words.len());
+    let mut words = words.into_iter().peekable();
+    while let Some(word) = words.next() {
+        if word.text == "一" {
+            if let Some(prev) = result.last_mut() {
+                if prev.pos.starts_with('v') && words.peek().is_some_and(|next| next.text == prev.text) {
+                    // 看 一 看
+                    let repeat = words.next().expect("peeked");
+                    prev.merge(word);
+                    prev.merge(repeat);
+                    continue;
+                }
+                if prev.text.ends_with('第') {
+                    prev.merge(word);
+                    continue;
+                }
+            }
+        }
+
+        match result.last_mut() {
+            Some(prev) if prev.text == "一" && !word.text.starts_with(|c| SERIAL_DIGITS.contains(c)) => {
+                prev.pos = word.pos.clone();
+                prev.merge(word);
+            }
+            _ => result.push(word),
+        }
+    }
+    result
+}
+
+/// Merge consecutive identical words (e.g. 试 试)
+fn merge_reduplication(words: Vec<Word>) -> Vec<Word> {
+    let mut result: Vec<Word> = Vec::with_capacity(words.len());
+    for word in words {
+        match result.last_mut() {
+            Some(prev) if prev.text == word.text => prev.merge(word),
+            _ => result.push(word),
+        }
+    }
+    result
+}
+
+/// Merge a word into the one before it when `joins` holds for their
+/// syllables, the result is at most three syllables and the earlier word is
+/// not a reduplication (which takes the neutral tone instead). A word that
+/// absorbed its predecessor is not merged again.
+fn merge_third_tones(words: Vec<Word>, joins: impl Fn(&[PinyinSyllable], &[PinyinSyllable]) -> bool) -> Vec<Word> {
+    let mut result: Vec<Word> = Vec::with_capacity(words.len());
+    let mut merged_last = false;
+    for word in words {
+        if let Some(prev) = result.last_mut() {
+            if !merged_last
+                && joins(&prev.syllables, &word.syllables)
+                && !prev.is_reduplication()
+                && prev.syllables.len() + word.syllables.len() <= 3
+            {
+                prev.merge(word);
+                merged_last = true;
+                continue;
+            }
+        }
+        result.push(word);
+        merged_last = false;
+    }
+    result
+}
+
+/// Whether every syllable has the third tone
+fn all_tone_three(syllables: &[PinyinSyllable]) -> bool {
+    !syllables.is_empty() && syllables.iter().all(|s| s.tone == 3)
+}
+
+/// Whether a syllable was read from `hanzi`, or, when its character is
+/// unknown, has the reading `syllable`
+fn is_char(syl: &PinyinSyllable, hanzi: char, syllable: &str) -> bool {
+    match syl.hanzi {
+        Some(c) => c == hanzi,
+        None => syl.syllable == syllable,
+    }
+}
+
+/// 不 sandhi within a unit: neutral in the middle of 对不起-style words,
+/// rising before a fourth tone
+fn apply_bu_word(unit: &mut Word) {
+    let syllables = &mut unit.syllables;
+    if syllables.len() == 3 && syllables[1].hanzi == Some('不') {
+        syllables[1].tone = 5;
+        return;
+    }
+    for i in 0..syllables.len().saturating_sub(1) {
+        if syllables[i].hanzi == Some('不') && syllables[i + 1].tone == 4 {
+            syllables[i].tone = 2;
+        }
+    }
+}
+
+/// 一 sandhi within a unit
+fn apply_yi_word(unit: &mut Word) {
+    let syllables = &mut unit.syllables;
+    let chars: Vec<Option<char>> = syllables.iter().map(|s| s.hanzi).collect();
+    let hanzi = |i: usize| chars.get(i).copied().flatten();
+
+    // 看一看: neutral between a reduplicated verb
+    if syllables.len() == 3 && hanzi(1) == Some('一') && hanzi(0) == hanzi(2) {
+        syllables[1].tone = 5;
+        return;
+    }
+
+    for i in 0..syllables.len() {
+        if hanzi(i) != Some('一') || i + 1 == syllables.len() {
+            continue;
+        }
+        let serial = |c: Option<char>| c.is_some_and(|c| SERIAL_DIGITS.contains(c));
+        let ordinal = i > 0 && hanzi(i - 1) == Some('第');
+        if ordinal || serial(hanzi(i + 1)) || (i > 0 && serial(hanzi(i - 1))) {
+            continue;
+        }
+        syllables[i].tone = if syllables[i + 1].tone == 4 { 2 } else { 4 };
+    }
+}
+
+/// Neutral tone for reduplications, suffixes and particles
+fn apply_neutral_tone(unit: &mut Word) {
+    let pos = unit.pos.as_str();
+    let keep_tone = KEEP_TONE_WORDS.contains(&unit.text.as_str());
+    let syllables = &mut unit.syllables;
+    let n = syllables.len();
+    if n == 0 {
+        return;
+    }
+    let hanzi: Vec<char> = syllables.iter().map(|s| s.hanzi.unwrap_or('\0')).collect();
+    let in_set = |c: char, set: &str| c != '\0' && set.contains(c);
+
+    // Reduplicated nouns, verbs and adjectives: 奶奶, 试试
+    if pos.starts_with(['n', 'v', 'a']) && !keep_tone {
+        for j in 1..n {
+            if hanzi[j] != '\0' && hanzi[j] == hanzi[j - 1] {
+                syllables[j].tone = 5;
+            }
+        }
+    }
+
+    let last = hanzi[n - 1];
+    let measure = hanzi.iter().position(|&c| c == '个').filter(|&i| {
+        (i > 0 && (in_set(hanzi[i - 1], MEASURE_PRECEDERS) || hanzi[i - 1].is_ascii_digit())) || n == 1
+    });
+    if in_set(last, MODAL_PARTICLES)
+        // Structural particles: 的, 地, 得
+        || (in_set(last, "的地得") && pos.starts_with('u'))
+        // Aspect particles: 走了, 看着, 去过
+        || (n == 1 && in_set(last, "了着过") && matches!(pos, "ul" | "uz" | "ug"))
+        // Suffixes: 我们, 刷子
+        || (n > 1 && in_set(last, "们子") && (pos.starts_with('r') || pos.starts_with('n')) && !keep_tone)
+        // Locations: 桌上, 地下, 家里
+        || (n > 1 && in_set(last, "上下里") && pos.starts_with(['s', 'l', 'f']))
+        // Directional complements: 上来, 下去
+        || (n > 1 && in_set(last, "来去") && in_set(hanzi[n - 2], "上下进出回过起开"))
+    {
+        syllables[n - 1].tone = 5;
+    } else if let Some(i) = measure {
+        // Measure word: 一个, 几个
+        syllables[i].tone = 5;
+    }
+}
+
+/// Third tone sandhi within a unit, following its internal structure
+fn apply_third_tone_word(unit: &mut Word) {
+    let syllables = &mut unit.syllables;
+    match syllables.len() {
+        2 => {
+            if all_tone_three(syllables) {
+                syllables[0].tone = 2;
+            }
+        }
+        3 => {
+            let text: String = syllables.iter().filter_map(|s| s.hanzi).collect();
+            let split = if text.chars().count() == 3 { Segmenter::split_compound(&text) } else { 1 };
+            if all_tone_three(syllables) {
+                match split {
+                    // 蒙古|包: both of the first pair rise
+                    2 => {
+                        syllables[0].tone = 2;
+                        syllables[1].tone = 2;
+                    }
+                    // 纸|老虎: only the middle rises
+                    1 => syllables[1].tone = 2,
+                    _ => {}
+                }
+            } else if (1..3).contains(&split) {
+                let (first, second) = syllables.split_at_mut(split);
+                for part in [&mut *first, &mut *second] {
+                    if part.len() == 2 && all_tone_three(part) {
+                        part[0].tone = 2;
+                    }
+                }
+                // 好|喜欢: a third tone before a word starting with one
+                if !all_tone_three(second) && second[0].tone == 3 && first[first.len() - 1].tone == 3 {
+                    first[first.len() - 1].tone = 2;
+                }
+            }
+        }
+        4 => {
+            // Four-character words read as two pairs: 岂有|此理
+            for pair in syllables.chunks_mut(2) {
+                if all_tone_three(pair) {
+                    pair[0].tone = 2;
+                }
+            }
+        }
+        _ => apply_third_tone_sandhi(syllables),
+    }
+}
 
-/// Apply all tone sandhi rules to a sequence of pinyin syllables
+/// Apply all tone sandhi rules to a flat sequence of pinyin syllables
+///
+/// Without word boundaries, third tones chain across the whole sequence; use
+/// [`apply_word_sandhi`] for segmented text. The 一 and 不 rules only apply
+/// to syllables read from those characters, or to any yi1/bu4 syllable whose
+/// character is unknown.
 pub fn apply_tone_sandhi(syllables: &[PinyinSyllable]) -> Vec<PinyinSyllable> {
     if syllables.is_empty() {
         return Vec::new();
@@ -32,18 +356,14 @@ pub fn apply_tone_sandhi(syllables: &[PinyinSyllable]) -> Vec<PinyinSyllable> {
 /// - In isolation or at end: remains yī (tone 1)
 fn apply_yi_sandhi(syllables: &mut [PinyinSyllable]) {
     for i in 0..syllables.len() {
-        if syllables[i].syllable == "yi" && syllables[i].tone == 1 {
-            // Check if this is 一 (not other yi1 syllables)
-            // This is a simplification; in practice we'd check the original character
-            if i + 1 < syllables.len() {
-                let next_tone = syllables[i + 1].tone;
-                match next_tone {
-                    4 => syllables[i].tone = 2, // Before 4th: yī → yí
-                    1 | 2 | 3 => syllables[i].tone = 4, // Before others: yī → yì
-                    _ => {} // Neutral tone: usually stays yī
-                }
+        // At end of phrase: stays yī (tone 1)
+        if is_char(&syllables[i], '一', "yi") && syllables[i].tone == 1 && i + 1 < syllables.len() {
+            let next_tone = syllables[i + 1].tone;
+            match next_tone {
+                4 => syllables[i].tone = 2, // Before 4th: yī → yí
+                1..=3 => syllables[i].tone = 4, // Before others: yī → yì
+                _ => {} // Neutral tone: usually stays yī
             }
-            // At end of phrase: stays yī (tone 1)
         }
     }
 }
@@ -52,10 +372,8 @@ fn apply_yi_sandhi(syllables: &mut [PinyinSyllable]) {
 /// - Before 4th tone: bù → bú (tone 2)
 fn apply_bu_sandhi(syllables: &mut [PinyinSyllable]) {
     for i in 0..syllables.len() {
-        if syllables[i].syllable == "bu" && syllables[i].tone == 4 {
-            if i + 1 < syllables.len() && syllables[i + 1].tone == 4 {
-                syllables[i].tone = 2; // Before 4th tone: bù → bú
-            }
+        if is_char(&syllables[i], '不', "bu") && syllables[i].tone == 4 && i + 1 < syllables.len() && syllables[i + 1].tone == 4 {
+            syllables[i].tone = 2; // Before 4th tone: bù → bú
         }
     }
 }
@@ -80,8 +398,8 @@ fn apply_third_tone_sandhi(syllables: &mut [PinyinSyllable]) {
             // If we have a sequence (more than one third tone)
             if j > i {
                 // Change all but the last to tone 2
-                for k in i..j {
-                    syllables[k].tone = 2;
+                for syllable in &mut syllables[i..j] {
+                    syllable.tone = 2;
                 }
             }
 
@@ -103,6 +421,60 @@ mod tests {
             .collect()
     }
 
+    /// Tone numbers of each unit after word-level sandhi
+    fn unit_tones(words: &[(&str, &str)]) -> Vec<(String, String)> {
+        let words = words
+            .iter()
+            .map(|(text, pos)| Word::new(text, pos, super::super::pinyin::to_pinyin_with_pos(text, pos)))
+            .collect();
+        apply_word_sandhi(words)
+            .into_iter()
+            .map(|unit| (unit.text, unit.syllables.iter().map(|s| s.tone.to_string()).collect()))
+            .collect()
+    }
+
+    fn units(specs: &[(&str, &str)]) -> Vec<(String, String)> {
+        specs.iter().map(|(text, tones)| (text.to_string(), tones.to_string())).collect()
+    }
+
+    #[test]
+    fn test_third_tones_stay_within_units() {
+        // 所有 and 老板 are too long to merge, so 有 keeps its third tone
+        assert_eq!(unit_tones(&[("所有", "b"), ("老板", "n")]), units(&[("所有", "23"), ("老板", "23")]));
+        // Short third-tone words merge into one unit
+        assert_eq!(unit_tones(&[("你", "r"), ("好", "a")]), units(&[("你好", "23")]));
+        assert_eq!(unit_tones(&[("纸老虎", "n")]), units(&[("纸老虎", "323")]));
+        assert_eq!(unit_tones(&[("展览馆", "n")]), units(&[("展览馆", "223")]));
+        assert_eq!(unit_tones(&[("岂有此理", "i")]), units(&[("岂有此理", "2323")]));
+    }
+
+    #[test]
+    fn test_yi_and_bu_by_character() {
+        // 医 and 布 share readings with 一 and 不 but take no sandhi
+        assert_eq!(unit_tones(&[("医院", "n")]), units(&[("医院", "14")]));
+        assert_eq!(unit_tones(&[("布料", "n")]), units(&[("布料", "44")]));
+        assert_eq!(unit_tones(&[("不", "d"), ("对", "a")]), units(&[("不对", "24")]));
+        assert_eq!(unit_tones(&[("对不起", "l")]), units(&[("对不起", "453")]));
+    }
+
+    #[test]
+    fn test_yi_exceptions() {
+        assert_eq!(unit_tones(&[("一", "m"), ("天", "q")]), units(&[("一天", "41")]));
+        assert_eq!(unit_tones(&[("第", "m"), ("一", "m"), ("次", "q")]), units(&[("第一", "41"), ("次", "4")]));
+        assert_eq!(unit_tones(&[("一九九八", "m")]), units(&[("一九九八", "1331")]));
+        assert_eq!(unit_tones(&[("看", "v"), ("一", "m"), ("看", "v")]), units(&[("看一看", "454")]));
+        assert_eq!(unit_tones(&[("一", "m"), ("个", "q")]), units(&[("一个", "25")]));
+    }
+
+    #[test]
+    fn test_neutral_tone() {
+        assert_eq!(unit_tones(&[("试试", "v")]), units(&[("试试", "45")]));
+        assert_eq!(unit_tones(&[("刷子", "n")]), units(&[("刷子", "15")]));
+        assert_eq!(unit_tones(&[("电子", "n")]), units(&[("电子", "43")]));
+        assert_eq!(unit_tones(&[("走", "v"), ("了", "ul")]), units(&[("走", "3"), ("了", "5")]));
+        assert_eq!(unit_tones(&[("桌上", "s")]), units(&[("桌上", "15")]));
+    }
+
     #[test]
     fn test_third_tone_sandhi() {
         // 你好 (nǐ hǎo) → (ní hǎo)
@@ -0,0 +1,31 @@
+//! Loading English dictionaries at runtime
+//!
+//! Only meaningful with the `external-dictionaries` feature, where nothing is
+//! embedded. Runs in its own process because installing dictionaries affects
+//! every English engine.
+#![cfg(feature = "external-dictionaries")]
+
+use kokoro_g2p::{G2PError, KPipeline};
+
+#[test]
+fn test_load_at_runtime() {
+    // Nothing is available until dictionaries are loaded
+    let err = kokoro_g2p::check_english_dictionaries("en-us").unwrap_err();
+    assert_eq!(err, G2PError::DictionaryNotLoaded("en-us".to_string()));
+    assert_eq!(kokoro_g2p::try_text_to_phonemes("hello", "en-us").unwrap_err().code(), 8);
+
+    // A pipeline created before loading picks the dictionaries up
+    let pipeline = KPipeline::new("en-us");
+    kokoro_g2p::load_english_dictionaries("en-us", "dictionaries/us_gold.json", "dictionaries/us_silver.json").unwrap();
+    assert!(kokoro_g2p::check_english_dictionaries("en-us").is_ok());
+    assert!(!pipeline.try_process("hello").unwrap().phonemes.contains('❓'));
+
+    // British English is loaded separately
+    assert!(kokoro_g2p::check_english_dictionaries("en-gb").is_err());
+    let gold = std::fs::read("dictionaries/gb_gold.json").unwrap();
+    kokoro_g2p::load_english_dictionaries_from_bytes("en-gb", gold, b"{}".to_vec()).unwrap();
+    assert!(kokoro_g2p::try_text_to_phonemes("hello", "en-gb").is_ok());
+
+    assert!(kokoro_g2p::load_english_dictionaries("es", "a.json", "b.json").is_err());
+    assert!(kokoro_g2p::load_english_dictionaries("en-us", "missing.lex", "missing.lex").is_err());
+}
@@ -646,4 +646,166 @@ mod tests {
         println!("  Exact matches: {} ({:.1}%)", exact, accuracy * 100.0);
         println!("  Avg PER: {:.3}", avg_per);
     }
+
+    /// Split tone-numbered pinyin with the spaces removed ("yin2hang2")
+    /// into syllables
+    #[cfg(feature = "chinese")]
+    fn split_tone_syllables(pinyin: &str) -> Vec<&str> {
+        let mut syllables = Vec::new();
+        let mut start = 0;
+        for (i, c) in pinyin.char_indices() {
+            if c.is_ascii_digit() {
+                syllables.push(&pinyin[start..=i]);
+                start = i + 1;
+            }
+        }
+        syllables
+    }
+
+    /// Tone of a Mandarin syllable from its IPA tone letters, 5 for the
+    /// neutral tone
+    #[cfg(feature = "chinese")]
+    fn mandarin_tone(contour: &str) -> u8 {
+        match contour {
+            "˥˥" | "˥" => 1,
+            "˧˥" | "˨˥" => 2,
+            "˨˩˦" | "˨˩˩" | "˨˩" | "˨˩˥" | "˧˨˦" => 3,
+            "˥˩" | "˥˧" | "˦˩" => 4,
+            _ => 5,
+        }
+    }
+
+    /// Mandarin words from WikiPron, an independent source, scored on the
+    /// tone of each syllable since the engine writes bopomofo rather than IPA
+    ///
+    /// Ignored by default because the data is not in the repository; it
+    /// fails rather than skips when run without a usable file.
+    #[test]
+    #[ignore = "needs WikiPron's Mandarin broad data in tests/wikipron/cmn_broad.tsv"]
+    #[cfg(feature = "chinese")]
+    fn test_validate_chinese_sample() {
+        use kokoro_g2p::zh::ChineseG2P;
+
+        let dir = get_test_data_dir();
+        let path = dir.join("cmn_broad.tsv");
+
+        let is_han = |c: char| ('\u{4E00}'..='\u{9FFF}').contains(&c) || ('\u{3400}'..='\u{4DBF}').contains(&c);
+        let entries: Vec<_> = load_wikipron(&path, usize::MAX)
+            .into_iter()
+            .filter(|(word, _)| word.chars().all(is_han))
+            .take(1000)
+            .collect();
+        assert!(
+            entries.len() >= 500,
+            "{} has {} Han entries; download WikiPron's cmn_hani_broad.tsv there",
+            path.display(),
+            entries.len()
+        );
+
+        let g2p = ChineseG2P::new();
+
+        let mut words = 0;
+        let mut exact = 0;
+        let mut syllables = 0;
+        let mut correct = 0;
+
+        for (word, ipa) in &entries {
+            // Tone letters are contiguous within a syllable once spaces are removed
+            let mut expected = Vec::new();
+            let mut contour = String::new();
+            for c in ipa.chars().chain(std::iter::once(' ')) {
+                if matches!(c, '˥' | '˦' | '˧' | '˨' | '˩') {
+                    contour.push(c);
+                } else if !contour.is_empty() {
+                    expected.push(mandarin_tone(&contour));
+                    contour.clear();
+                }
+            }
+            let predicted: Vec<u8> = g2p.text_to_pinyin(word).iter().map(|s| s.tone).collect();
+
+            words += 1;
+            syllables += expected.len();
+            if expected.len() != predicted.len() {
+                continue;
+            }
+            let matches = expected.iter().zip(&predicted).filter(|(e, p)| e == p).count();
+            correct += matches;
+            if matches == expected.len() {
+                exact += 1;
+            }
+        }
+
+        let syllable_accuracy = correct as f64 / syllables as f64;
+
+        println!("\nMandarin Validation Results:");
+        println!("  Words: {}", words);
+        println!("  Exact tone matches: {} ({:.1}%)", exact, exact as f64 / words as f64 * 100.0);
+        println!("  Syllable tone accuracy: {:.1}%", syllable_accuracy * 100.0);
+
+        assert!(syllable_accuracy > 0.9, "syllable tone accuracy {:.3}", syllable_accuracy);
+    }
+
+    /// Polyphone readings in running text, scored per syllable against
+    /// hand-checked sentences in WikiPron layout (text, then pinyin)
+    ///
+    /// The sentences were written alongside the polyphone rules, so this
+    /// guards against regressions; see `test_validate_chinese_sample` for an
+    /// independent check.
+    #[test]
+    #[cfg(feature = "chinese")]
+    fn test_validate_chinese_polyphones() {
+        use kokoro_g2p::zh::ChineseG2P;
+
+        let dir = get_test_data_dir();
+        let path = dir.join("cmn_polyphones.tsv");
+
+        if !path.exists() {
+            println!("Chinese polyphone data not found, skipping validation");
+            return;
+        }
+
+        let entries = load_wikipron(&path, 1000);
+        let g2p = ChineseG2P::new();
+
+        let mut total = 0;
+        let mut correct = 0;
+        let mut exact = 0;
+
+        for (text, expected) in &entries {
+            let expected = split_tone_syllables(expected);
+            let predicted: Vec<String> = g2p.text_to_pinyin(text).iter().map(|s| s.with_tone_number()).collect();
+
+            // Neutral tones are applied with tone sandhi and vary between
+            // dictionaries, so a neutral tone on either side matches any tone
+            let matches = expected
+                .iter()
+                .zip(&predicted)
+                .filter(|(e, p)| {
+                    let (e_base, e_tone) = e.split_at(e.len() - 1);
+                    let (p_base, p_tone) = p.split_at(p.len() - 1);
+                    e_base == p_base && (e_tone == p_tone || e_tone == "5" || p_tone == "5")
+                })
+                .count();
+
+            total += expected.len();
+            if expected.len() == predicted.len() {
+                correct += matches;
+                if matches == expected.len() {
+                    exact += 1;
+                    continue;
+                }
+            }
+            println!("  {} → {} (expected: {})", text, predicted.join(" "), expected.join(" "));
+        }
+
+        let syllable_accuracy = correct as f64 / total as f64;
+        let accuracy = exact as f64 / entries.len() as f64;
+
+        println!("\nChinese Polyphone Validation:");
+        println!("  Sentences: {}", entries.len());
+        println!("  Exact matches: {} ({:.1}%)", exact, accuracy * 100.0);
+        println!("  Syllable accuracy: {:.1}%", syllable_accuracy * 100.0);
+
+        assert!(syllable_accuracy > 0.98, "syllable accuracy {:.3}", syllable_accuracy);
+    }
 }